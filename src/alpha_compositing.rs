@@ -0,0 +1,206 @@
+use crate::blend_mode::BlendMode;
+
+/// Converts an sRGB-encoded value (0.0 to 1.0) to linear light.
+pub fn srgb_to_linear(v: f64) -> f64 {
+    if v <= 0.04045 {
+        v / 12.92
+    } else {
+        ((v + 0.055) / 1.055).powf(2.4)
+    }
+}
+/// Converts a value in linear light (0.0 to 1.0) to sRGB.
+pub fn linear_to_srgb(v: f64) -> f64 {
+    if v <= 0.0031308 {
+        v * 12.92
+    } else {
+        1.055 * v.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// The pixel's color (0.0 to 1.0, in linear light if linear is true) and alpha.
+fn decode(px: &[u8; 4], linear: bool) -> ([f64; 3], f64) {
+    let color = [px[0], px[1], px[2]].map(|v| {
+        let v = v as f64 / 255.0;
+        if linear {
+            srgb_to_linear(v)
+        } else {
+            v
+        }
+    });
+    (color, px[3] as f64 / 255.0)
+}
+fn encode(color: [f64; 3], alpha: f64, linear: bool) -> [u8; 4] {
+    let channel = |v: f64| (v.max(0.0).min(1.0) * 255.0).round() as u8;
+    let [r, g, b] = color.map(|v| {
+        let v = v.max(0.0).min(1.0);
+        channel(if linear { linear_to_srgb(v) } else { v })
+    });
+    [r, g, b, channel(alpha)]
+}
+
+/// Porter-Duff "source over": draws new onto old. Both pixels are straight (not premultiplied) RGBA, but they are mixed as premultiplied values, so a transparent or semi-transparent old pixel is handled correctly. If linear is true, the colors are mixed in linear light instead of in sRGB.
+pub fn over(old: &mut [u8; 4], new: &[u8; 4], linear: bool) {
+    match new[3] {
+        0 => return,
+        255 => {
+            *old = *new;
+            return;
+        }
+        _ => (),
+    }
+    let (backdrop, alpha_old) = decode(old, linear);
+    let (source, alpha_new) = decode(new, linear);
+    let alpha = alpha_new + alpha_old * (1.0 - alpha_new);
+    let color = [0, 1, 2]
+        .map(|i| (source[i] * alpha_new + backdrop[i] * alpha_old * (1.0 - alpha_new)) / alpha);
+    *old = encode(color, alpha, linear);
+}
+
+/// Like over, but where old isn't transparent, the new color is first combined with the old one using the blend mode (see the W3C compositing specification). If linear is true, this also happens in linear light.
+pub fn blend(old: &mut [u8; 4], new: &[u8; 4], mode: BlendMode, linear: bool) {
+    if new[3] == 0 {
+        return;
+    }
+    let (backdrop, alpha_old) = decode(old, linear);
+    let (source, alpha_new) = decode(new, linear);
+    let blended = mode.blend(backdrop, source);
+    let alpha = alpha_new + alpha_old * (1.0 - alpha_new);
+    let color = [0, 1, 2].map(|i| {
+        let source = (1.0 - alpha_old) * source[i] + alpha_old * blended[i].max(0.0).min(1.0);
+        (source * alpha_new + backdrop[i] * alpha_old * (1.0 - alpha_new)) / alpha
+    });
+    *old = encode(color, alpha, linear);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn over_px(old: [u8; 4], new: [u8; 4], linear: bool) -> [u8; 4] {
+        let mut old = old;
+        over(&mut old, &new, linear);
+        old
+    }
+    fn blend_px(old: [u8; 4], new: [u8; 4], mode: BlendMode) -> [u8; 4] {
+        let mut old = old;
+        blend(&mut old, &new, mode, false);
+        old
+    }
+
+    #[test]
+    fn srgb_round_trip() {
+        for v in 0..=255u8 {
+            let srgb = v as f64 / 255.0;
+            let back = linear_to_srgb(srgb_to_linear(srgb));
+            assert_eq!((back * 255.0).round() as u8, v);
+        }
+    }
+
+    #[test]
+    fn srgb_reference_values() {
+        // sRGB 50% gray is about 21.4% in linear light
+        assert!((srgb_to_linear(0.5) - 0.214041).abs() < 1e-6);
+        assert!((linear_to_srgb(0.214041) - 0.5).abs() < 1e-6);
+        assert_eq!(srgb_to_linear(0.0), 0.0);
+        assert!((srgb_to_linear(1.0) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn opaque_and_transparent_sources() {
+        for linear in [false, true] {
+            assert_eq!(
+                over_px([10, 20, 30, 40], [1, 2, 3, 255], linear),
+                [1, 2, 3, 255]
+            );
+            assert_eq!(
+                over_px([10, 20, 30, 40], [1, 2, 3, 0], linear),
+                [10, 20, 30, 40]
+            );
+        }
+    }
+
+    #[test]
+    fn over_transparent_canvas_keeps_color_and_alpha() {
+        // previously, this became a dark, opaque red
+        assert_eq!(
+            over_px([0, 0, 0, 0], [255, 0, 0, 128], false),
+            [255, 0, 0, 128]
+        );
+        assert_eq!(
+            over_px([0, 0, 0, 0], [255, 0, 0, 128], true),
+            [255, 0, 0, 128]
+        );
+        assert_eq!(
+            over_px([0, 0, 0, 0], [100, 150, 200, 64], false),
+            [100, 150, 200, 64]
+        );
+    }
+
+    #[test]
+    fn over_opaque_backdrop() {
+        // 128/255 red over blue: r = 255 * 0.502, b = 255 * 0.498
+        assert_eq!(
+            over_px([0, 0, 255, 255], [255, 0, 0, 128], false),
+            [128, 0, 127, 255]
+        );
+        // in linear light, 50% white over black is 50% linear light, which is sRGB 188
+        assert_eq!(
+            over_px([0, 0, 0, 255], [255, 255, 255, 128], true),
+            [188, 188, 188, 255]
+        );
+    }
+
+    #[test]
+    fn over_semi_transparent_backdrop() {
+        // alpha: 0.502 + 0.502 * 0.498 = 0.752 -> 192
+        // color: 0.502 / 0.752 = 0.6676 -> 170
+        assert_eq!(
+            over_px([0, 0, 0, 128], [255, 255, 255, 128], false),
+            [170, 170, 170, 192]
+        );
+    }
+
+    #[test]
+    fn blend_modes_on_opaque_pixels() {
+        let gray = [128, 128, 128, 255];
+        assert_eq!(
+            blend_px(gray, [255, 128, 0, 255], BlendMode::Multiply),
+            [128, 64, 0, 255]
+        );
+        assert_eq!(
+            blend_px(gray, [255, 128, 0, 255], BlendMode::Screen),
+            [255, 192, 128, 255]
+        );
+        assert_eq!(
+            blend_px(gray, [255, 128, 0, 255], BlendMode::Difference),
+            [127, 0, 128, 255]
+        );
+        assert_eq!(
+            blend_px(gray, [200, 100, 0, 255], BlendMode::Add),
+            [255, 228, 128, 255]
+        );
+        assert_eq!(
+            blend_px(gray, [200, 100, 0, 255], BlendMode::Darken),
+            [128, 100, 0, 255]
+        );
+        // luminosity of a gray backdrop with a gray source is the source
+        assert_eq!(
+            blend_px(gray, [64, 64, 64, 255], BlendMode::Luminosity),
+            [64, 64, 64, 255]
+        );
+    }
+
+    #[test]
+    fn blend_over_transparent_backdrop_is_normal_over() {
+        for mode in BlendMode::ALL {
+            assert_eq!(
+                blend_px([0, 0, 0, 0], [200, 100, 50, 128], mode),
+                [200, 100, 50, 128]
+            );
+            assert_eq!(
+                blend_px([0, 0, 0, 0], [200, 100, 50, 128], mode),
+                over_px([0, 0, 0, 0], [200, 100, 50, 128], false)
+            );
+        }
+    }
+}
@@ -0,0 +1,195 @@
+/// How the color of a new pixel is combined with the color that was there before (see CompositingMethod::Blend). These are the blend modes from the W3C compositing specification (and most image editors), plus Add and Subtract.
+#[derive(Clone, Copy, PartialEq)]
+pub enum BlendMode {
+    Multiply,
+    Screen,
+    Overlay,
+    Darken,
+    Lighten,
+    ColorDodge,
+    ColorBurn,
+    HardLight,
+    SoftLight,
+    Difference,
+    Exclusion,
+    /// Also called linear dodge.
+    Add,
+    Subtract,
+    // non-separable modes: these don't work on each channel separately
+    Hue,
+    Saturation,
+    Color,
+    Luminosity,
+}
+
+impl BlendMode {
+    pub const ALL: [Self; 17] = [
+        Self::Multiply,
+        Self::Screen,
+        Self::Overlay,
+        Self::Darken,
+        Self::Lighten,
+        Self::ColorDodge,
+        Self::ColorBurn,
+        Self::HardLight,
+        Self::SoftLight,
+        Self::Difference,
+        Self::Exclusion,
+        Self::Add,
+        Self::Subtract,
+        Self::Hue,
+        Self::Saturation,
+        Self::Color,
+        Self::Luminosity,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Multiply => "multiply",
+            Self::Screen => "screen",
+            Self::Overlay => "overlay",
+            Self::Darken => "darken",
+            Self::Lighten => "lighten",
+            Self::ColorDodge => "color dodge",
+            Self::ColorBurn => "color burn",
+            Self::HardLight => "hard light",
+            Self::SoftLight => "soft light",
+            Self::Difference => "difference",
+            Self::Exclusion => "exclusion",
+            Self::Add => "add",
+            Self::Subtract => "subtract",
+            Self::Hue => "hue",
+            Self::Saturation => "saturation",
+            Self::Color => "color",
+            Self::Luminosity => "luminosity",
+        }
+    }
+    /// The character used in project files (after the b in compositing:b[char]).
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::Multiply => 'm',
+            Self::Screen => 's',
+            Self::Overlay => 'o',
+            Self::Darken => 'd',
+            Self::Lighten => 'l',
+            Self::ColorDodge => 'D',
+            Self::ColorBurn => 'B',
+            Self::HardLight => 'h',
+            Self::SoftLight => 'S',
+            Self::Difference => 'x',
+            Self::Exclusion => 'e',
+            Self::Add => 'a',
+            Self::Subtract => 'u',
+            Self::Hue => 'H',
+            Self::Saturation => 'T',
+            Self::Color => 'C',
+            Self::Luminosity => 'L',
+        }
+    }
+    pub fn from_char(ch: char) -> Option<Self> {
+        Self::ALL.into_iter().find(|mode| mode.to_char() == ch)
+    }
+
+    /// Blends the colors (r, g, b; 0.0 to 1.0). backdrop is the color that was there before.
+    pub fn blend(&self, backdrop: [f64; 3], source: [f64; 3]) -> [f64; 3] {
+        let separable = |f: fn(f64, f64) -> f64| {
+            [
+                f(backdrop[0], source[0]),
+                f(backdrop[1], source[1]),
+                f(backdrop[2], source[2]),
+            ]
+        };
+        match self {
+            Self::Multiply => separable(|b, s| b * s),
+            Self::Screen => separable(screen),
+            Self::Overlay => separable(|b, s| hard_light(s, b)),
+            Self::Darken => separable(f64::min),
+            Self::Lighten => separable(f64::max),
+            Self::ColorDodge => separable(|b, s| {
+                if b <= 0.0 {
+                    0.0
+                } else if s >= 1.0 {
+                    1.0
+                } else {
+                    (b / (1.0 - s)).min(1.0)
+                }
+            }),
+            Self::ColorBurn => separable(|b, s| {
+                if b >= 1.0 {
+                    1.0
+                } else if s <= 0.0 {
+                    0.0
+                } else {
+                    1.0 - ((1.0 - b) / s).min(1.0)
+                }
+            }),
+            Self::HardLight => separable(hard_light),
+            Self::SoftLight => separable(|b, s| {
+                if s <= 0.5 {
+                    b - (1.0 - 2.0 * s) * b * (1.0 - b)
+                } else {
+                    let d = if b <= 0.25 {
+                        ((16.0 * b - 12.0) * b + 4.0) * b
+                    } else {
+                        b.sqrt()
+                    };
+                    b + (2.0 * s - 1.0) * (d - b)
+                }
+            }),
+            Self::Difference => separable(|b, s| (b - s).abs()),
+            Self::Exclusion => separable(|b, s| b + s - 2.0 * b * s),
+            Self::Add => separable(|b, s| (b + s).min(1.0)),
+            Self::Subtract => separable(|b, s| (b - s).max(0.0)),
+            Self::Hue => set_lum(set_sat(source, sat(backdrop)), lum(backdrop)),
+            Self::Saturation => set_lum(set_sat(backdrop, sat(source)), lum(backdrop)),
+            Self::Color => set_lum(source, lum(backdrop)),
+            Self::Luminosity => set_lum(backdrop, lum(source)),
+        }
+    }
+}
+
+fn screen(b: f64, s: f64) -> f64 {
+    b + s - b * s
+}
+fn hard_light(b: f64, s: f64) -> f64 {
+    if s <= 0.5 {
+        b * 2.0 * s
+    } else {
+        screen(b, 2.0 * s - 1.0)
+    }
+}
+
+fn lum(c: [f64; 3]) -> f64 {
+    0.3 * c[0] + 0.59 * c[1] + 0.11 * c[2]
+}
+fn clip_color(c: [f64; 3]) -> [f64; 3] {
+    let l = lum(c);
+    let n = c[0].min(c[1]).min(c[2]);
+    let x = c[0].max(c[1]).max(c[2]);
+    c.map(|v| {
+        let mut v = v;
+        if n < 0.0 && l - n != 0.0 {
+            v = l + (v - l) * l / (l - n);
+        }
+        if x > 1.0 && x - l != 0.0 {
+            v = l + (v - l) * (1.0 - l) / (x - l);
+        }
+        v
+    })
+}
+fn set_lum(c: [f64; 3], l: f64) -> [f64; 3] {
+    let d = l - lum(c);
+    clip_color(c.map(|v| v + d))
+}
+fn sat(c: [f64; 3]) -> f64 {
+    c[0].max(c[1]).max(c[2]) - c[0].min(c[1]).min(c[2])
+}
+fn set_sat(c: [f64; 3], s: f64) -> [f64; 3] {
+    let max = c[0].max(c[1]).max(c[2]);
+    let min = c[0].min(c[1]).min(c[2]);
+    if max > min {
+        c.map(|v| (v - min) * s / (max - min))
+    } else {
+        [0.0; 3]
+    }
+}
@@ -29,6 +29,12 @@ pub struct CustomArgs {
     pub action: Option<Action>,
     pub export_options: Option<crate::video_export_settings::VideoExportSettings>,
     pub assets_path: Option<PathBuf>,
+    pub font_dirs: Vec<PathBuf>,
+    /// The subtitle file and (optionally) the project's duration in seconds
+    pub subtitles: Option<(PathBuf, Option<f64>)>,
+    pub subtitle_template: Option<PathBuf>,
+    /// Only export subtitles from the video with this label (see Video::label) and its children
+    pub subtitle_root: Option<String>,
     pub cli_colored_output_disabled: bool,
 }
 
@@ -37,6 +43,8 @@ pub enum Action {
     OpenProjectInGui,
     OpenProjectInCli,
     ExportProjectToFrames,
+    ImportSubtitles,
+    ExportSubtitles,
     Exit,
 }
 impl std::fmt::Display for Action { fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{:?}", self) } }
@@ -55,10 +63,14 @@ impl CustomArgs {
                     "OpenProjectInGui" => Action::OpenProjectInGui,
                     "OpenProjectInCli" => Action::OpenProjectInCli,
                     "ExportProjectToFrames" => Action::ExportProjectToFrames,
-                    ac => panic!("\n{}{}{}\n    {}\n    {}\n",
+                    "ImportSubtitles" => Action::ImportSubtitles,
+                    "ExportSubtitles" => Action::ExportSubtitles,
+                    ac => panic!("\n{}{}{}\n    {}\n    {}\n    {}\n    {}\n",
                         Clz::error_info("Invalid action '"), Clz::error_cause(ac), Clz::error_info("'! [action] in --action [action] may only be one of the following:"),
                         Clz::undecided("OpenProjectInGui"),
                         Clz::undecided("ExportProjectToFrames"),
+                        Clz::undecided("ImportSubtitles"),
+                        Clz::undecided("ExportSubtitles"),
                     ),
                 }),
                 _ => panic!("\n{}\n",
@@ -108,17 +120,54 @@ impl CustomArgs {
                     Clz::error_info("Please specify a file following the assets-dir option!"),
                 ),
             },
+            "font-dir" => match arg.len() - 1 {
+                0 => panic!("\n{}\n",
+                    Clz::error_info("Please specify at least one directory following the font-dir option!"),
+                ),
+                _ => for dir in arg[1..].iter() {
+                    self.font_dirs.push(PathBuf::from(dir));
+                },
+            },
+            "subtitles" => match arg.len() - 1 {
+                1 => self.subtitles = Some((PathBuf::from(&arg[1]), None)),
+                2 => self.subtitles = Some((PathBuf::from(&arg[1]), Some(match arg[2].parse() {
+                    Ok(v) => v,
+                    Err(err) => panic!("\n{}\n{}{}{}{}\n",
+                        Clz::error_info("Could not read [duration] in --subtitles [path] [duration]:"),
+                        Clz::error_info("Could not parse '"), Clz::error_cause(arg[2].as_str()), Clz::error_info("' into a number: "), Clz::error_details(err.to_string().as_str()),
+                    ),
+                }))),
+                _ => panic!("\n{}\n",
+                    Clz::error_info("subtitles requires one or two arguments: --subtitles [path] [project duration in seconds (optional)]"),
+                ),
+            },
+            "subtitle-template" => match arg.len() - 1 {
+                1 => self.subtitle_template = Some(PathBuf::from(&arg[1])),
+                _ => panic!("\n{}\n",
+                    Clz::error_info("subtitle-template requires exactly one argument: --subtitle-template [path]"),
+                ),
+            },
+            "subtitle-root" => match arg.len() - 1 {
+                1 => self.subtitle_root = Some(arg[1].clone()),
+                _ => panic!("\n{}\n",
+                    Clz::error_info("subtitle-root requires exactly one argument: --subtitle-root [label] (a video that has label:[label]\\! in the project file)"),
+                ),
+            },
             "cli-nocolor" => match arg.len() - 1 {
                 0 => self.cli_colored_output_disabled = true,
                 _ => panic!("\n{}\n",
                     Clz::error_info("cli-nocolor takes no arguments!"),
                 ),
             },
-            invalid_arg => panic!("\n{} {} {}\n    {} {}\n    {} {}\n    {} {}\n    {}\n{}\n",
+            invalid_arg => panic!("\n{} {} {}\n    {} {}\n    {} {}\n    {} {}\n    {} {}\n    {} {}\n    {} {}\n    {} {}\n    {}\n{}\n",
                 Clz::error_info("--arg"), Clz::error_cause(invalid_arg), Clz::error_info("is invalid. Valid args are:"),
                 Clz::error_info("proj-path"), Clz::error_info("[path]"),
                 Clz::error_info("action"), Clz::error_info("[action]"),
                 Clz::error_info("assets-dir"), Clz::error_info("[dir]"),
+                Clz::error_info("font-dir"), Clz::error_info("[dir] [dir...]"),
+                Clz::error_info("subtitles"), Clz::error_info("[path] [duration]"),
+                Clz::error_info("subtitle-template"), Clz::error_info("[path]"),
+                Clz::error_info("subtitle-root"), Clz::error_info("[label]"),
                 Clz::error_info("cli-nocolor"),
                 Clz::error_info("To use these: --[arg] [...], for example: '--proj-path \"/path/to/file.txt\"'."),
             ),
@@ -0,0 +1,225 @@
+use std::{io, path::PathBuf};
+
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder, webp::WebPDecoder},
+    imageops::FilterType,
+    AnimationDecoder, DynamicImage, ImageFormat,
+};
+
+use super::{
+    content::{Content, GenericContentData},
+    image::Image,
+};
+
+/// An animated gif, png (APNG) or webp file. Files which aren't animated are shown as a still image.
+pub struct AnimatedImage {
+    path: PathBuf,
+    timing: AnimationTiming,
+    failed_to_load: bool,
+    /// Every frame with the time (in seconds) at which it starts. Like in InputVideo, each frame is an Image, which keeps its scaled version until the size changes. All frames are decoded the first time one of them is needed.
+    frames: Vec<(f64, Image)>,
+    /// How long the animation is, in seconds.
+    duration: f64,
+    /// Used for every frame.
+    pub fit: super::image_fit::ImageFitSettings,
+    generic_content_data: GenericContentData,
+    pub as_content_changes: AnimatedImageChanges,
+}
+#[derive(Default)]
+pub struct AnimatedImageChanges {
+    pub path: Option<PathBuf>,
+    pub timing: Option<AnimationTiming>,
+    pub fit: Option<super::image_fit::ImageFitSettings>,
+}
+
+#[derive(Clone)]
+pub enum AnimationTiming {
+    /// The animation is played once, stretched (or squashed) to the video's length.
+    Stretch,
+    /// The animation is played at the speed stored in the file. Since a video only knows its progress, not how long it is, length has to be the video's length in seconds. If looping is false, the last frame stays visible after the animation ended.
+    Native { length: f64, looping: bool },
+}
+
+impl Content for AnimatedImage {
+    fn clone_no_caching(&self) -> Self {
+        let mut img = Self::new(
+            self.path.clone(),
+            self.timing.clone(),
+            self.generic_content_data.reset(),
+        );
+        img.fit = self.fit.clone();
+        img
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        Vec::new()
+    }
+
+    fn has_changes(&self) -> bool {
+        self.as_content_changes.path.is_some()
+            || self.as_content_changes.timing.is_some()
+            || self.as_content_changes.fit.is_some()
+    }
+    fn apply_changes(&mut self) -> bool {
+        let mut out = false;
+        if let Some(path) = self.as_content_changes.path.take() {
+            self.set_path(path);
+            out = true;
+        }
+        if let Some(timing) = self.as_content_changes.timing.take() {
+            self.timing = timing;
+            out = true;
+        }
+        if let Some(fit) = self.as_content_changes.fit.take() {
+            self.fit = fit;
+            out = true;
+        }
+        out
+    }
+
+    fn generic_content_data(&mut self) -> &mut GenericContentData {
+        &mut self.generic_content_data
+    }
+}
+
+impl AnimatedImage {
+    pub fn new(
+        path: PathBuf,
+        timing: AnimationTiming,
+        generic_content_data: GenericContentData,
+    ) -> Self {
+        Self {
+            path,
+            timing,
+            failed_to_load: false,
+            frames: Vec::new(),
+            duration: 0.0,
+            fit: super::image_fit::ImageFitSettings::default(),
+            generic_content_data,
+            as_content_changes: AnimatedImageChanges::default(),
+        }
+    }
+    pub fn set_path(&mut self, new: PathBuf) {
+        self.path = new;
+        self.failed_to_load = false;
+        self.frames.clear();
+        self.duration = 0.0;
+    }
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+    pub fn timing(&self) -> &AnimationTiming {
+        &self.timing
+    }
+    pub fn set_timing(&mut self, timing: AnimationTiming) {
+        self.timing = timing;
+    }
+
+    fn load_if_necessary(&mut self) {
+        if !self.frames.is_empty() || self.failed_to_load {
+            return;
+        }
+        match Self::decode(&self.path) {
+            Ok(frames) if !frames.is_empty() => {
+                let mut start = 0.0;
+                for (delay, img) in frames {
+                    let mut frame =
+                        Image::new(self.path.clone(), self.generic_content_data.reset());
+                    frame.img_original = Some(img);
+                    self.frames.push((start, frame));
+                    start += delay;
+                }
+                self.duration = start;
+            }
+            Ok(_) => {
+                eprintln!("Animated image '{}' has no frames.", self.path.display());
+                self.failed_to_load = true;
+            }
+            Err(err) => {
+                eprintln!(
+                    "Could not load animated image '{}': {}",
+                    self.path.display(),
+                    err
+                );
+                self.failed_to_load = true;
+            }
+        }
+    }
+    /// All frames with their delays (in seconds).
+    fn decode(path: &PathBuf) -> image::ImageResult<Vec<(f64, DynamicImage)>> {
+        let data = std::fs::read(path)?;
+        let frames = match image::guess_format(&data)? {
+            ImageFormat::Gif => GifDecoder::new(io::Cursor::new(&data))?.into_frames(),
+            ImageFormat::Png => {
+                let decoder = PngDecoder::new(io::Cursor::new(&data))?;
+                if decoder.is_apng() {
+                    decoder.apng().into_frames()
+                } else {
+                    return Ok(vec![(1.0, image::load_from_memory(&data)?)]);
+                }
+            }
+            ImageFormat::WebP => {
+                let decoder = WebPDecoder::new(io::Cursor::new(&data))?;
+                if decoder.has_animation() {
+                    decoder.into_frames()
+                } else {
+                    return Ok(vec![(1.0, image::load_from_memory(&data)?)]);
+                }
+            }
+            _ => return Ok(vec![(1.0, image::load_from_memory(&data)?)]),
+        };
+        frames
+            .map(|frame| {
+                let frame = frame?;
+                let (numer, denom) = frame.delay().numer_denom_ms();
+                let mut delay = numer as f64 / denom.max(1) as f64 / 1000.0;
+                // like browsers do, very short delays are treated as 0.1 seconds
+                if delay <= 0.01 {
+                    delay = 0.1;
+                }
+                Ok((delay, DynamicImage::ImageRgba8(frame.into_buffer())))
+            })
+            .collect()
+    }
+
+    /// The frame that should be shown at this progress.
+    pub fn get_frame(&mut self, progress: f64) -> Option<&mut Image> {
+        self.load_if_necessary();
+        if self.frames.is_empty() {
+            return None;
+        }
+        let time = match &self.timing {
+            AnimationTiming::Stretch => progress * self.duration,
+            AnimationTiming::Native { length, looping } => {
+                let time = progress * length;
+                if *looping && self.duration > 0.0 {
+                    time.rem_euclid(self.duration)
+                } else {
+                    time
+                }
+            }
+        };
+        let index = self
+            .frames
+            .partition_point(|(start, _)| *start <= time)
+            .max(1)
+            - 1;
+        let frame = &mut self.frames[index].1;
+        frame.fit = self.fit.clone();
+        Some(frame)
+    }
+
+    pub fn draw(
+        &mut self,
+        image: &mut DynamicImage,
+        prep_draw: &crate::video::PrepDrawData,
+        scaling_filter: FilterType,
+    ) {
+        if let Some(frame) = self.get_frame(prep_draw.progress) {
+            frame.draw(image, prep_draw, scaling_filter);
+        }
+    }
+}
@@ -0,0 +1,225 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use image::DynamicImage;
+
+use crate::project::Diagnostics;
+
+/// How many generated images are kept, so that going back to a frame doesn't run the command again.
+const CACHE_SIZE: usize = 16;
+/// Used if no timeout was set.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+/// How often a running command is checked to see if it finished.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// A command that generates an Image's content. The command runs on another thread, and until it finishes, the previous image stays visible. Results are cached by the values of the %d26vid:...% replacements, so the command only runs again if one of them changed.
+pub struct ExternalImageCommand {
+    pub command: String,
+    /// %d26vid:...% in these is replaced before the command is run (see Image::draw).
+    pub args: Vec<String>,
+    pub output: ExternalImageOutput,
+    /// If the command takes longer than this, it is killed.
+    pub timeout: Duration,
+    /// If true, get waits for the command to finish instead of showing the previous image. Set for the final render.
+    pub wait: bool,
+    /// The most recently used result is last. None if the command failed.
+    cache: Vec<(Vec<String>, Option<DynamicImage>)>,
+    /// The replacement values and the result of the command that is currently running.
+    running: Option<(Vec<String>, mpsc::Receiver<Result<DynamicImage, String>>)>,
+    /// The replacement values of the image that is currently shown.
+    current: Option<Vec<String>>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExternalImageOutput {
+    /// The command writes the image to the Image's path.
+    File,
+    /// The command writes the image (in any format the image crate can guess) to its stdout.
+    Stdout,
+}
+
+impl ExternalImageCommand {
+    pub fn new(command: String, args: Vec<String>) -> Self {
+        Self {
+            command,
+            args,
+            output: ExternalImageOutput::File,
+            timeout: DEFAULT_TIMEOUT,
+            wait: false,
+            cache: Vec::new(),
+            running: None,
+            current: None,
+        }
+    }
+    pub fn clone_no_caching(&self) -> Self {
+        Self {
+            output: self.output,
+            timeout: self.timeout,
+            ..Self::new(self.command.clone(), self.args.clone())
+        }
+    }
+
+    /// Returns the image that should be shown now, but only if it is different from the one returned last time. Failures are reported to diagnostics, with path as the source.
+    /// While the wanted image is still being generated, the most recently generated one is returned instead, so that the preview keeps updating even if the replacements change on every frame (during playback).
+    pub fn get(
+        &mut self,
+        replacements: &[(String, String)],
+        path: &Path,
+        diagnostics: &Diagnostics,
+    ) -> Option<DynamicImage> {
+        let key: Vec<String> = replacements.iter().map(|(_, with)| with.clone()).collect();
+        self.receive(path, diagnostics, false);
+        if self.current.as_ref() == Some(&key) {
+            return None;
+        }
+        if !self.cache.iter().any(|(k, _)| *k == key) {
+            if self.wait {
+                // the final render must not show an outdated image
+                self.receive(path, diagnostics, true);
+                self.start(key.clone(), replacements, path);
+                self.receive(path, diagnostics, true);
+            } else if self.running.is_none() {
+                // if a command is running, this is tried again on the next draw
+                self.start(key.clone(), replacements, path);
+            }
+        }
+        let index = match self.cache.iter().position(|(k, _)| *k == key) {
+            Some(v) => v,
+            None => {
+                // the last entry was either just generated or is the one that is currently shown
+                let newest = self.cache.last()?;
+                if self.current.as_ref() == Some(&newest.0) {
+                    return None;
+                }
+                self.current = Some(newest.0.clone());
+                return newest.1.clone();
+            }
+        };
+        let entry = self.cache.remove(index);
+        let img = entry.1.clone();
+        self.cache.push(entry);
+        self.current = Some(key);
+        img
+    }
+
+    /// Moves the running command's result into the cache, if it is done (or, if block is true, once it is done).
+    fn receive(&mut self, path: &Path, diagnostics: &Diagnostics, block: bool) {
+        let result = match &self.running {
+            Some((_, receiver)) if block => receiver
+                .recv()
+                .unwrap_or_else(|_| Err("the command's thread stopped".to_string())),
+            Some((_, receiver)) => match receiver.try_recv() {
+                Ok(v) => v,
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => {
+                    Err("the command's thread stopped".to_string())
+                }
+            },
+            None => return,
+        };
+        let (key, _) = self.running.take().unwrap();
+        let img = match result {
+            Ok(img) => Some(img),
+            Err(err) => {
+                diagnostics.report(
+                    path.display().to_string(),
+                    format!("External image command '{}': {}", self.command, err),
+                );
+                None
+            }
+        };
+        self.cache.push((key, img));
+        if self.cache.len() > CACHE_SIZE {
+            self.cache.remove(0);
+        }
+    }
+
+    fn start(&mut self, key: Vec<String>, replacements: &[(String, String)], path: &Path) {
+        let args: Vec<_> = self
+            .args
+            .iter()
+            .map(|arg| {
+                let mut arg = arg.to_string();
+                for (replace, with) in replacements {
+                    arg = arg.replace(replace, with);
+                }
+                arg
+            })
+            .collect();
+        let (sender, receiver) = mpsc::channel();
+        let (command, output, timeout) = (self.command.clone(), self.output, self.timeout);
+        let path = path.to_path_buf();
+        std::thread::spawn(move || {
+            _ = sender.send(run(command, args, output, timeout, path));
+        });
+        self.running = Some((key, receiver));
+    }
+}
+
+/// Runs the command and loads the image it generated.
+fn run(
+    command: String,
+    args: Vec<String>,
+    output: ExternalImageOutput,
+    timeout: Duration,
+    path: PathBuf,
+) -> Result<DynamicImage, String> {
+    let mut child = Command::new(&command)
+        .args(&args)
+        .stdin(Stdio::null())
+        .stdout(match output {
+            ExternalImageOutput::File => Stdio::null(),
+            ExternalImageOutput::Stdout => Stdio::piped(),
+        })
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|err| format!("could not start: {err}"))?;
+    // the pipes are read on other threads so that the command can't block because they are full
+    let read_pipe = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                _ = pipe.read_to_end(&mut buf);
+            }
+            buf
+        })
+    };
+    let stdout = read_pipe(child.stdout.take().map(|p| Box::new(p) as _));
+    let stderr = read_pipe(child.stderr.take().map(|p| Box::new(p) as _));
+    let start = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if start.elapsed() >= timeout => {
+                _ = child.kill();
+                _ = child.wait();
+                return Err(format!("timed out after {:.1}s", timeout.as_secs_f64()));
+            }
+            Ok(None) => std::thread::sleep(POLL_INTERVAL),
+            Err(err) => return Err(format!("could not wait for the command: {err}")),
+        }
+    };
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+    if !status.success() {
+        let stderr = String::from_utf8_lossy(&stderr);
+        return Err(match stderr.trim() {
+            "" => format!("{status}"),
+            stderr => format!("{status}: {stderr}"),
+        });
+    }
+    let img = match output {
+        ExternalImageOutput::File => image::io::Reader::open(&path)
+            .map_err(|err| format!("could not open '{}': {err}", path.display()))?
+            .with_guessed_format()
+            .map_err(|err| format!("could not guess image format: {err}"))?
+            .decode(),
+        ExternalImageOutput::Stdout => image::load_from_memory(&stdout),
+    };
+    img.map_err(|err| format!("could not load image: {err}"))
+}
@@ -10,16 +10,27 @@ use super::content::{Content, GenericContentData};
 pub struct FfmpegVid {
     path: PathBuf,
     image: Option<DynamicImage>,
+    /// Cached result of get_frame_count, reset when the path changes.
+    frame_count: FrameCount,
+    pub fit: super::image_fit::ImageFitSettings,
     generic_content_data: crate::content::content::GenericContentData,
     pub as_content_changes: FfmpegVidChanges,
 }
+enum FrameCount {
+    NotCounted,
+    Counting(std::sync::mpsc::Receiver<Option<u32>>),
+    Counted(Option<u32>),
+}
 #[derive(Default)]
 pub struct FfmpegVidChanges {
     pub path: Option<PathBuf>,
+    pub fit: Option<super::image_fit::ImageFitSettings>,
 }
 impl Content for FfmpegVid {
     fn clone_no_caching(&self) -> Self {
-        Self::new(self.path.clone(), self.generic_content_data.reset())
+        let mut vid = Self::new(self.path.clone(), self.generic_content_data.reset());
+        vid.fit = self.fit.clone();
+        vid
     }
 
     fn children(&self) -> Vec<&Self> {
@@ -30,15 +41,19 @@ impl Content for FfmpegVid {
     }
 
     fn has_changes(&self) -> bool {
-        self.as_content_changes.path.is_some()
+        self.as_content_changes.path.is_some() || self.as_content_changes.fit.is_some()
     }
     fn apply_changes(&mut self) -> bool {
+        let mut out = false;
         if let Some(path) = self.as_content_changes.path.take() {
             self.set_path(path);
-            true
-        } else {
-            false
+            out = true;
+        }
+        if let Some(fit) = self.as_content_changes.fit.take() {
+            self.fit = fit;
+            out = true;
         }
+        out
     }
 
     fn generic_content_data(&mut self) -> &mut super::content::GenericContentData {
@@ -50,6 +65,8 @@ impl FfmpegVid {
         Self {
             path,
             image: None,
+            frame_count: FrameCount::NotCounted,
+            fit: super::image_fit::ImageFitSettings::default(),
             as_content_changes: FfmpegVidChanges::default(),
             generic_content_data,
         }
@@ -58,6 +75,7 @@ impl FfmpegVid {
 impl FfmpegVid {
     pub fn set_path(&mut self, new: PathBuf) {
         self.path = new;
+        self.frame_count = FrameCount::NotCounted;
     }
     pub fn path(&self) -> &PathBuf {
         &self.path
@@ -104,6 +122,65 @@ impl FfmpegVid {
             0.0
         }
     }
+    /// The number of frames in the first video stream, as counted by ffprobe. Counting requires reading the whole file, so it happens in a background thread (started by the first call) and the result is cached. Returns None while ffprobe is still counting, unless wait is true, and Some(None) if the frames could not be counted (this is reported to the project's diagnostics).
+    pub fn get_frame_count(&mut self, wait: bool) -> Option<Option<u32>> {
+        if let FrameCount::NotCounted = self.frame_count {
+            let (sender, receiver) = std::sync::mpsc::channel();
+            let path = self.path.clone();
+            let diagnostics = self.generic_content_data.project.diagnostics.clone();
+            std::thread::spawn(move || {
+                _ = sender.send(Self::count_frames(&path, &diagnostics));
+            });
+            self.frame_count = FrameCount::Counting(receiver);
+        }
+        if let FrameCount::Counting(receiver) = &self.frame_count {
+            let count = if wait {
+                receiver.recv().ok().flatten()
+            } else {
+                match receiver.try_recv() {
+                    Ok(count) => count,
+                    Err(std::sync::mpsc::TryRecvError::Empty) => return None,
+                    Err(std::sync::mpsc::TryRecvError::Disconnected) => None,
+                }
+            };
+            self.frame_count = FrameCount::Counted(count);
+        }
+        match self.frame_count {
+            FrameCount::Counted(count) => Some(count),
+            _ => None,
+        }
+    }
+    fn count_frames(path: &PathBuf, diagnostics: &crate::project::Diagnostics) -> Option<u32> {
+        // ffprobe -v error -select_streams v:0 -count_packets -show_entries stream=nb_read_packets -of default=noprint_wrappers=1:nokey=1 ~/Videos/wat.mp4
+        let message = match std::process::Command::new("ffprobe")
+            .args([
+                "-v",
+                "error",
+                "-select_streams",
+                "v:0",
+                "-count_packets",
+                "-show_entries",
+                "stream=nb_read_packets",
+                "-of",
+                "default=noprint_wrappers=1:nokey=1",
+                path.to_string_lossy().as_ref(),
+            ])
+            .stdin(std::process::Stdio::null())
+            .stderr(std::process::Stdio::null())
+            .output()
+        {
+            Ok(ffprobe_output) => match String::from_utf8_lossy(&ffprobe_output.stdout)
+                .trim()
+                .parse()
+            {
+                Ok(n) => return Some(n),
+                Err(_) => "ffprobe could not count the frames.",
+            },
+            Err(_) => "Could not run ffprobe to count the frames.",
+        };
+        diagnostics.report(path.to_string_lossy().to_string(), message.to_string());
+        None
+    }
     pub fn load_img_force_factor(&mut self, factor: f64) {
         self.load_img_force_seconds(self.get_length_secs() * factor)
     }
@@ -171,10 +248,15 @@ impl FfmpegVid {
         &mut self,
         width: u32,
         height: u32,
+        anchor: (f64, f64),
+        progress: f64,
         scaling_filter: FilterType,
     ) -> Option<DynamicImage> {
         match &self.image {
-            Some(img) => Some(img.resize_exact(width, height, scaling_filter)),
+            Some(img) => Some(
+                self.fit
+                    .apply(img, width, height, anchor, progress, scaling_filter),
+            ),
             None => None,
         }
     }
@@ -187,6 +269,8 @@ impl FfmpegVid {
         let img = self.get_img_scaled(
             prep_draw.pos_px.2 as _,
             prep_draw.pos_px.3 as _,
+            prep_draw.position.align.get_anchor(0.0, 0.5, 1.0),
+            prep_draw.progress,
             scaling_filter,
         );
         if let Some(img) = img {
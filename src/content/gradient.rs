@@ -0,0 +1,178 @@
+use image::{DynamicImage, RgbaImage};
+
+use crate::{curve::Curve, types::Color};
+
+use super::content::{Content, GenericContentData};
+
+/// Fills the video's box with colors that change along a line (linear), with the distance from a point (radial) or with the angle around a point (conic).
+pub struct Gradient {
+    kind: GradientKind,
+    stops: Vec<GradientStop>,
+    generic_content_data: GenericContentData,
+    pub as_content_changes: GradientChanges,
+}
+
+#[derive(Default)]
+pub struct GradientChanges {
+    pub kind: Option<GradientKind>,
+    pub stops: Option<Vec<GradientStop>>,
+}
+
+/// All points are relative to the video's box (0;0 is the top left corner, 1;1 the bottom right one).
+#[derive(Clone)]
+pub enum GradientKind {
+    /// Position 0 is at start, position 1 at end.
+    Linear {
+        start: (Curve, Curve),
+        end: (Curve, Curve),
+    },
+    /// Position 0 is at the center, position 1 at the radius. The radius is relative to the box's width (horizontally) and height (vertically), so the circles are ellipses unless the box is square.
+    Radial { center: (Curve, Curve), radius: Curve },
+    /// Position 0 (and 1) is at angle (in full turns, 0 is up), positions go around the center clockwise.
+    Conic { center: (Curve, Curve), angle: Curve },
+}
+
+#[derive(Clone)]
+pub struct GradientStop {
+    pub position: Curve,
+    pub color: Color,
+}
+
+impl Content for Gradient {
+    fn clone_no_caching(&self) -> Self {
+        Self::new(
+            self.kind.clone(),
+            self.stops.clone(),
+            self.generic_content_data.reset(),
+        )
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        Vec::new()
+    }
+
+    fn has_changes(&self) -> bool {
+        self.as_content_changes.kind.is_some() || self.as_content_changes.stops.is_some()
+    }
+    fn apply_changes(&mut self) -> bool {
+        let mut out = false;
+        if let Some(kind) = self.as_content_changes.kind.take() {
+            self.kind = kind;
+            out = true;
+        }
+        if let Some(stops) = self.as_content_changes.stops.take() {
+            self.stops = stops;
+            out = true;
+        }
+        out
+    }
+
+    fn generic_content_data(&mut self) -> &mut GenericContentData {
+        &mut self.generic_content_data
+    }
+}
+
+impl Gradient {
+    pub fn new(
+        kind: GradientKind,
+        stops: Vec<GradientStop>,
+        generic_content_data: GenericContentData,
+    ) -> Self {
+        Self {
+            kind,
+            stops,
+            generic_content_data,
+            as_content_changes: GradientChanges::default(),
+        }
+    }
+    pub fn kind(&self) -> &GradientKind {
+        &self.kind
+    }
+    pub fn stops(&self) -> &Vec<GradientStop> {
+        &self.stops
+    }
+
+    pub fn draw(&mut self, image: &mut DynamicImage, prep_draw: &crate::video::PrepDrawData) {
+        let (width, height) = (prep_draw.pos_px.2 as u32, prep_draw.pos_px.3 as u32);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let progress = prep_draw.progress;
+        let mut stops: Vec<_> = self
+            .stops
+            .iter()
+            .map(|stop| (stop.position.get_value(progress), stop.color.get_rgba(progress)))
+            .collect();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let (w, h) = (width as f64, height as f64);
+        // returns the position (not clamped) for a pixel, x and y are relative to the box
+        let position: Box<dyn Fn(f64, f64) -> f64> = match &self.kind {
+            GradientKind::Linear { start, end } => {
+                let (x1, y1) = (start.0.get_value(progress), start.1.get_value(progress));
+                let (x2, y2) = (end.0.get_value(progress), end.1.get_value(progress));
+                // in pixels, so that the gradient is perpendicular to the line even if the box isn't square
+                let (dx, dy) = ((x2 - x1) * w, (y2 - y1) * h);
+                let length_sq = dx * dx + dy * dy;
+                Box::new(move |x, y| {
+                    if length_sq > 0.0 {
+                        ((x - x1) * w * dx + (y - y1) * h * dy) / length_sq
+                    } else {
+                        0.0
+                    }
+                })
+            }
+            GradientKind::Radial { center, radius } => {
+                let (cx, cy) = (center.0.get_value(progress), center.1.get_value(progress));
+                let radius = radius.get_value(progress);
+                Box::new(move |x, y| {
+                    if radius > 0.0 {
+                        ((x - cx).powi(2) + (y - cy).powi(2)).sqrt() / radius
+                    } else {
+                        1.0
+                    }
+                })
+            }
+            GradientKind::Conic { center, angle } => {
+                let (cx, cy) = (center.0.get_value(progress), center.1.get_value(progress));
+                let angle = angle.get_value(progress);
+                Box::new(move |x, y| {
+                    // clockwise from the top, in full turns
+                    let a = ((x - cx) * w).atan2(-(y - cy) * h) / std::f64::consts::TAU;
+                    (a - angle).rem_euclid(1.0)
+                })
+            }
+        };
+        let layer = RgbaImage::from_fn(width, height, |x, y| {
+            let (r, g, b, a) = color_at(
+                &stops,
+                position((x as f64 + 0.5) / w, (y as f64 + 0.5) / h),
+            );
+            let channel = |v: f64| (v.max(0.0).min(1.0) * 255.0).round() as u8;
+            image::Rgba([channel(r), channel(g), channel(b), channel(a)])
+        });
+        crate::video::composite_images(image, &DynamicImage::ImageRgba8(layer), prep_draw);
+    }
+}
+
+/// The color at a position. stops have to be sorted by their position. Before the first and after the last stop, their colors are used. Colors are interpolated with premultiplied alpha, so that transparent stops don't darken the gradient.
+fn color_at(stops: &[(f64, (f64, f64, f64, f64))], position: f64) -> (f64, f64, f64, f64) {
+    let index = stops.partition_point(|(p, _)| *p <= position);
+    if index == 0 {
+        return stops.first().map_or((0.0, 0.0, 0.0, 0.0), |stop| stop.1);
+    }
+    if index == stops.len() {
+        return stops[index - 1].1;
+    }
+    let ((p1, c1), (p2, c2)) = (stops[index - 1], stops[index]);
+    // p1 <= position < p2
+    let t = (position - p1) / (p2 - p1);
+    let a = c1.3 + (c2.3 - c1.3) * t;
+    if a <= 0.0 {
+        return (0.0, 0.0, 0.0, 0.0);
+    }
+    let mix = |v1: f64, v2: f64| (v1 * c1.3 + (v2 * c2.3 - v1 * c1.3) * t) / a;
+    (mix(c1.0, c2.0), mix(c1.1, c2.1), mix(c1.2, c2.2), a)
+}
@@ -1,31 +1,42 @@
 use std::{
     io::{self, Read},
     path::PathBuf,
-    process::Command,
 };
 
 use image::{imageops::FilterType, DynamicImage};
 
-use super::content::{Content, GenericContentData};
+use super::{
+    content::{Content, GenericContentData},
+    external_image::ExternalImageCommand,
+    image_fit::{ImageFitKey, ImageFitSettings},
+};
 
 pub struct Image {
     path: PathBuf,
     failed_to_load_image: bool,
-    /// if set, every time an image should be displayed, this command is invoked. If a file is present at the given location, it will be (re)loaded as img_original.
-    pub external_command: Option<(String, Vec<String>)>,
+    /// if set, this command generates the image whenever one of the replacements' values changes (see ExternalImageCommand).
+    pub external_command: Option<ExternalImageCommand>,
     pub external_command_replacements: Vec<(String, String)>,
     pub img_original: Option<DynamicImage>,
-    pub img_scaled: Option<(FilterType, DynamicImage)>,
+    pub img_scaled: Option<(ImageFitKey, DynamicImage)>,
+    pub fit: ImageFitSettings,
     generic_content_data: crate::content::content::GenericContentData,
     pub as_content_changes: ImageChanges,
 }
 #[derive(Default)]
 pub struct ImageChanges {
     pub path: Option<PathBuf>,
+    pub fit: Option<ImageFitSettings>,
 }
 impl Content for Image {
     fn clone_no_caching(&self) -> Self {
-        Self::new(self.path.clone(), self.generic_content_data.reset())
+        let mut img = Self::new(self.path.clone(), self.generic_content_data.reset());
+        img.fit = self.fit.clone();
+        img.external_command = self
+            .external_command
+            .as_ref()
+            .map(|cmd| cmd.clone_no_caching());
+        img
     }
 
     fn children(&self) -> Vec<&Self> {
@@ -36,15 +47,19 @@ impl Content for Image {
     }
 
     fn has_changes(&self) -> bool {
-        self.as_content_changes.path.is_some()
+        self.as_content_changes.path.is_some() || self.as_content_changes.fit.is_some()
     }
     fn apply_changes(&mut self) -> bool {
+        let mut out = false;
         if let Some(path) = self.as_content_changes.path.take() {
             self.set_path(path);
-            true
-        } else {
-            false
+            out = true;
+        }
+        if let Some(fit) = self.as_content_changes.fit.take() {
+            self.fit = fit;
+            out = true;
         }
+        out
     }
 
     fn generic_content_data(&mut self) -> &mut super::content::GenericContentData {
@@ -60,6 +75,7 @@ impl Image {
             external_command_replacements: Vec::new(),
             img_original: None,
             img_scaled: None,
+            fit: ImageFitSettings::default(),
             as_content_changes: ImageChanges::default(),
             generic_content_data,
         }
@@ -104,35 +120,28 @@ impl Image {
         }
     }
     pub fn load_img_if_necessary(&mut self) {
-        if let Some((command, args)) = &self.external_command {
-            let mut command = Command::new(command);
-            let args: Vec<_> = args
-                .iter()
-                .map(|arg| {
-                    let mut arg = arg.to_string();
-                    for (replace, with) in &self.external_command_replacements {
-                        arg = arg.replace(replace, with);
-                    }
-                    arg
-                })
-                .collect();
-            if !args.is_empty() {
-                command.args(&args[..]);
+        if let Some(cmd) = &mut self.external_command {
+            if let Some(img) = cmd.get(
+                &self.external_command_replacements,
+                &self.path,
+                &self.generic_content_data.project.diagnostics,
+            ) {
+                self.img_original = Some(img);
+                self.img_scaled = None;
             }
-            match command.status() {
-                Ok(s) if s.success() => (),
-                s => eprintln!("Command status (image with custom command): {:?}", s),
-            }
-        }
-        if self.external_command.is_some()
-            || (self.img_original.is_none() && !self.failed_to_load_image)
-        {
+        } else if self.img_original.is_none() && !self.failed_to_load_image {
             self.load_img_force();
             if self.img_original.is_none() {
                 self.failed_to_load_image = true;
             };
         };
     }
+    /// If true, drawing waits for the external command (if any) to finish instead of showing the previous image.
+    pub fn set_wait_for_external_command(&mut self, wait: bool) {
+        if let Some(cmd) = &mut self.external_command {
+            cmd.wait = wait;
+        }
+    }
     pub fn get_img_original(&mut self) -> Option<&DynamicImage> {
         self.load_img_if_necessary();
         match &self.img_original {
@@ -141,32 +150,26 @@ impl Image {
         }
     }
 
+    /// The image, fitted into width x height pixels (see ImageFitSettings::apply). The result is cached until something that affects it changes.
     pub fn get_img_scaled(
         &mut self,
         width: u32,
         height: u32,
+        anchor: (f64, f64),
+        progress: f64,
         scaling_filter: FilterType,
     ) -> Option<&DynamicImage> {
-        if if let (false, Some((filter, img_scaled))) =
-            (self.external_command.is_some(), &self.img_scaled)
-        {
-            if *filter == scaling_filter
-                && img_scaled.width() == width
-                && img_scaled.height() == height
-            {
-                false
-            } else {
-                true
+        self.load_img_if_necessary();
+        if let Some(img_og) = &self.img_original {
+            let key = self
+                .fit
+                .key(img_og, width, height, anchor, progress, scaling_filter);
+            if !matches!(&self.img_scaled, Some((k, _)) if *k == key) {
+                let img = self
+                    .fit
+                    .apply(img_og, width, height, anchor, progress, scaling_filter);
+                self.img_scaled = Some((key, img));
             }
-        } else {
-            true
-        } {
-            if let Some(img_og) = self.get_img_original() {
-                self.img_scaled = Some((
-                    scaling_filter.clone(),
-                    img_og.resize_exact(width, height, scaling_filter),
-                ));
-            };
         };
         match &self.img_scaled {
             Some(v) => Some(&v.1),
@@ -194,7 +197,13 @@ impl Image {
                 ),
             ];
         }
-        let img = self.get_img_scaled(width, height, scaling_filter);
+        let img = self.get_img_scaled(
+            width,
+            height,
+            prep_draw.position.align.get_anchor(0.0, 0.5, 1.0),
+            prep_draw.progress,
+            scaling_filter,
+        );
         if let Some(img) = img {
             crate::video::composite_images(image, img, prep_draw);
         };
@@ -0,0 +1,168 @@
+use image::{imageops::FilterType, DynamicImage, RgbaImage};
+
+use crate::curve::Curve;
+
+/// How an image (or a video's frame) is fitted into the video's box. Parts of the box that aren't covered by the image stay transparent.
+#[derive(Clone, Default)]
+pub struct ImageFitSettings {
+    pub fit: ImageFit,
+    /// If set, only this part of the image is used.
+    pub crop: Option<ImageCrop>,
+}
+
+#[derive(Clone, Copy, Default, PartialEq)]
+pub enum ImageFit {
+    /// The image is scaled to exactly the box's size, even if that distorts it.
+    #[default]
+    Stretch,
+    /// The whole image is visible, with transparent bars on two sides if the aspect ratios don't match.
+    Contain,
+    /// The image covers the whole box, parts of it are cut off if the aspect ratios don't match.
+    Cover,
+    /// The image isn't scaled, one pixel in the image is one pixel in the output.
+    Original,
+    /// Like Original, but the image is repeated to fill the box.
+    Tile,
+}
+
+/// A rectangle in the source image, in pixels. If w or h are 0 or less, the crop extends to the right/bottom edge of the image.
+#[derive(Clone)]
+pub struct ImageCrop {
+    pub x: Curve,
+    pub y: Curve,
+    pub w: Curve,
+    pub h: Curve,
+}
+
+/// Everything that affects the fitted image. If this didn't change, a cached image can be reused.
+#[derive(Clone, PartialEq)]
+pub struct ImageFitKey {
+    fit: ImageFit,
+    crop: (u32, u32, u32, u32),
+    size: (u32, u32),
+    anchor: (f64, f64),
+    filter: FilterType,
+}
+
+impl ImageFitSettings {
+    pub fn new(fit: ImageFit) -> Self {
+        Self { fit, crop: None }
+    }
+
+    /// The crop rectangle (clamped to the image) at this progress.
+    fn crop_px(&self, img_width: u32, img_height: u32, progress: f64) -> (u32, u32, u32, u32) {
+        match &self.crop {
+            None => (0, 0, img_width, img_height),
+            Some(crop) => {
+                let x = (crop.x.get_value(progress).round().max(0.0) as u32).min(img_width);
+                let y = (crop.y.get_value(progress).round().max(0.0) as u32).min(img_height);
+                let w = crop.w.get_value(progress).round();
+                let h = crop.h.get_value(progress).round();
+                let w = if w > 0.0 {
+                    (w as u32).min(img_width - x)
+                } else {
+                    img_width - x
+                };
+                let h = if h > 0.0 {
+                    (h as u32).min(img_height - y)
+                } else {
+                    img_height - y
+                };
+                (x, y, w, h)
+            }
+        }
+    }
+
+    pub fn key(
+        &self,
+        img: &DynamicImage,
+        width: u32,
+        height: u32,
+        anchor: (f64, f64),
+        progress: f64,
+        filter: FilterType,
+    ) -> ImageFitKey {
+        ImageFitKey {
+            fit: self.fit,
+            crop: self.crop_px(img.width(), img.height(), progress),
+            size: (width, height),
+            anchor,
+            filter,
+        }
+    }
+
+    /// Returns an image of exactly width x height pixels. anchor decides which part of the image is visible (Cover, Original, Tile) and where the image is placed (Contain, Original): (0, 0) is the top left corner, (1, 1) the bottom right one.
+    pub fn apply(
+        &self,
+        img: &DynamicImage,
+        width: u32,
+        height: u32,
+        anchor: (f64, f64),
+        progress: f64,
+        filter: FilterType,
+    ) -> DynamicImage {
+        let (cx, cy, cw, ch) = self.crop_px(img.width(), img.height(), progress);
+        if cw == 0 || ch == 0 || width == 0 || height == 0 {
+            return DynamicImage::ImageRgba8(RgbaImage::new(width, height));
+        }
+        let (ax, ay) = anchor;
+        match self.fit {
+            ImageFit::Stretch => {
+                if (cx, cy, cw, ch) == (0, 0, img.width(), img.height()) {
+                    img.resize_exact(width, height, filter)
+                } else {
+                    img.crop_imm(cx, cy, cw, ch)
+                        .resize_exact(width, height, filter)
+                }
+            }
+            ImageFit::Cover => {
+                let scale = (width as f64 / cw as f64).max(height as f64 / ch as f64);
+                // the part of the (cropped) image that is visible
+                let vw = ((width as f64 / scale).round() as u32).max(1).min(cw);
+                let vh = ((height as f64 / scale).round() as u32).max(1).min(ch);
+                let vx = cx + ((cw - vw) as f64 * ax).round() as u32;
+                let vy = cy + ((ch - vh) as f64 * ay).round() as u32;
+                img.crop_imm(vx, vy, vw, vh)
+                    .resize_exact(width, height, filter)
+            }
+            ImageFit::Contain => {
+                let scale = (width as f64 / cw as f64).min(height as f64 / ch as f64);
+                let sw = ((cw as f64 * scale).round() as u32).max(1).min(width);
+                let sh = ((ch as f64 * scale).round() as u32).max(1).min(height);
+                let scaled = img.crop_imm(cx, cy, cw, ch).resize_exact(sw, sh, filter);
+                let mut out = RgbaImage::new(width, height);
+                image::imageops::replace(
+                    &mut out,
+                    &scaled.to_rgba8(),
+                    ((width - sw) as f64 * ax).round() as i64,
+                    ((height - sh) as f64 * ay).round() as i64,
+                );
+                DynamicImage::ImageRgba8(out)
+            }
+            ImageFit::Original => {
+                let mut out = RgbaImage::new(width, height);
+                // the image is positioned using the anchor, so it may start outside of the box
+                image::imageops::replace(
+                    &mut out,
+                    &img.crop_imm(cx, cy, cw, ch).to_rgba8(),
+                    ((width as f64 - cw as f64) * ax).round() as i64,
+                    ((height as f64 - ch as f64) * ay).round() as i64,
+                );
+                DynamicImage::ImageRgba8(out)
+            }
+            ImageFit::Tile => {
+                let tile = img.crop_imm(cx, cy, cw, ch).to_rgba8();
+                // one tile is placed like in Original, the others are placed around it
+                let ox = ((width as f64 - cw as f64) * ax).round() as i64;
+                let oy = ((height as f64 - ch as f64) * ay).round() as i64;
+                let (ox, oy) = (ox.rem_euclid(cw as i64), oy.rem_euclid(ch as i64));
+                DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+                    *tile.get_pixel(
+                        (x as i64 - ox).rem_euclid(cw as i64) as u32,
+                        (y as i64 - oy).rem_euclid(ch as i64) as u32,
+                    )
+                }))
+            }
+        }
+    }
+}
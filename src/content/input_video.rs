@@ -1,34 +1,63 @@
 use std::{
+    collections::{HashMap, HashSet},
+    fmt::Display,
     fs::{self},
     io::{self},
-    path::PathBuf,
-    rc::Rc,
-    sync::{Arc, RwLock},
+    path::{Path, PathBuf},
+    sync::mpsc,
 };
 
-use super::content::{Content, GenericContentData};
+use image::DynamicImage;
+
+use super::{
+    animated_image::AnimationTiming,
+    content::{Content, GenericContentData},
+};
+
+/// How many decoded frames are kept by default.
+pub const DEFAULT_CACHE_SIZE: usize = 64;
+/// How many frames after the current one are decoded in the background.
+const PREFETCH_FRAMES: usize = 8;
+/// Used with AnimationTiming::Native if no frame rate was set.
+pub const DEFAULT_FRAME_RATE: f64 = 25.0;
 
 pub struct InputVideo {
+    /// The directory containing the frames, or a path whose file name is a pattern for the frames' file names (see list_frames).
     images_directory: PathBuf,
-    /// A vec containing the image data. The length of the vec is the amount of frames available.
-    /// FrameData: raw image data for all given frames.
-    /// Option<i8>: For every frame, if this is 0, the frame is available and ready to be used. If it is positive, the nearest available frame is n entries further down the vec. If it is negative, the same thing applies, but in the opposite direction. If it is none, there is no value close enough.
-    frames_image_data: Vec<(Option<i8>, crate::content::image::Image)>,
+    /// The paths of all frames, in order. Frames are only decoded when they are needed.
+    frames: Vec<PathBuf>,
+    cache: FrameCache,
+    /// Started when the first frame is requested.
+    prefetcher: Option<Prefetcher>,
     /// The first frame, (the last frame (excl), false) or (how many frames to remove from the end, true) - default: (0, 0, true)
     crop: (u32, u32, bool),
+    /// Stretch shows all frames over the video's length, Native shows them at frame_rate.
+    pub timing: AnimationTiming,
+    /// The source's frames per second.
+    pub frame_rate: f64,
+    /// Used for every frame.
+    pub fit: crate::content::image_fit::ImageFitSettings,
     generic_content_data: crate::content::content::GenericContentData,
     pub as_content_changes: InputVideoChanges,
 }
 #[derive(Default)]
 pub struct InputVideoChanges {
     pub images_directory: Option<(PathBuf, PathBuf)>,
+    pub timing: Option<AnimationTiming>,
+    pub frame_rate: Option<f64>,
+    pub fit: Option<crate::content::image_fit::ImageFitSettings>,
 }
 impl Content for InputVideo {
     fn clone_no_caching(&self) -> Self {
         Self {
             images_directory: self.images_directory.clone(),
-            frames_image_data: Vec::new(),
+            frames: self.frames.clone(),
+            cache: FrameCache::new(self.cache.capacity),
+            prefetcher: None,
             crop: self.crop.clone(),
+            timing: self.timing.clone(),
+            frame_rate: self.frame_rate,
+            fit: self.fit.clone(),
             as_content_changes: InputVideoChanges::default(),
             generic_content_data: self.generic_content_data.reset(),
         }
@@ -43,14 +72,42 @@ impl Content for InputVideo {
 
     fn has_changes(&self) -> bool {
         self.as_content_changes.images_directory.is_some()
+            || self.as_content_changes.timing.is_some()
+            || self.as_content_changes.frame_rate.is_some()
+            || self.as_content_changes.fit.is_some()
     }
     fn apply_changes(&mut self) -> bool {
+        let mut out = false;
         if let Some(images_directory) = self.as_content_changes.images_directory.take() {
             self.images_directory = images_directory.1;
-            true
-        } else {
-            false
+            self.frames = match list_frames(&self.images_directory) {
+                Ok(v) => v,
+                Err(err) => {
+                    eprintln!(
+                        "Could not load frames from '{}': {}",
+                        self.images_directory.display(),
+                        err
+                    );
+                    Vec::new()
+                }
+            };
+            self.cache.clear();
+            self.prefetcher = None;
+            out = true;
+        }
+        if let Some(timing) = self.as_content_changes.timing.take() {
+            self.timing = timing;
+            out = true;
+        }
+        if let Some(frame_rate) = self.as_content_changes.frame_rate.take() {
+            self.frame_rate = frame_rate;
+            out = true;
         }
+        if let Some(fit) = self.as_content_changes.fit.take() {
+            self.fit = fit;
+            out = true;
+        }
+        out
     }
 
     fn generic_content_data(&mut self) -> &mut super::content::GenericContentData {
@@ -61,8 +118,13 @@ impl InputVideo {
     pub fn new(generic_content_data: GenericContentData) -> Self {
         Self {
             images_directory: PathBuf::new(),
-            frames_image_data: Vec::new(),
+            frames: Vec::new(),
+            cache: FrameCache::new(DEFAULT_CACHE_SIZE),
+            prefetcher: None,
             crop: (0, 0, true),
+            timing: AnimationTiming::Stretch,
+            frame_rate: DEFAULT_FRAME_RATE,
+            fit: crate::content::image_fit::ImageFitSettings::default(),
             generic_content_data,
             as_content_changes: InputVideoChanges::default(),
         }
@@ -70,54 +132,62 @@ impl InputVideo {
 
     // ffmpeg -ss 00:01:00 -i ~/Videos/wat.mp4 -frames:v 1 /tmp/video/frame.png
 
-    /// You can use "ffmpeg -i vids/video.mp4 path/%09d.png" or something similar to generate such a directory. Make sure the path ends in the path separator (likely \ on windows and / on unix)! | crop default is (0, 0, true)!
+    /// You can use "ffmpeg -i vids/video.mp4 path/%09d.png" or something similar to generate such a directory. images_directory can also be a pattern like path/%09d.png or path/*.png, see list_frames. | crop default is (0, 0, true)!
     pub fn new_from_directory_full_of_frames(
         images_directory: PathBuf,
         crop: (u32, u32, bool),
         generic_content_data: GenericContentData,
-    ) -> Result<Self, io::Error> {
-        let dir_files_iter = fs::read_dir(&images_directory)?;
-        let mut frames_image_data = Vec::new();
-        for pot_file in dir_files_iter {
-            let path = pot_file?.path();
-            if path.is_file() {
-                frames_image_data.push(path.file_name().unwrap().to_string_lossy().to_string());
-            };
-        }
-        frames_image_data.sort_unstable();
-        let frames_image_data = Vec::from_iter(frames_image_data.into_iter().map(|file_name| {
-            (
-                None,
-                crate::content::image::Image::new(
-                    {
-                        let mut p = images_directory.clone();
-                        p.push(file_name);
-                        p
-                    },
-                    generic_content_data.clone(),
-                ),
-            )
-        }));
+    ) -> Result<Self, ImageSequenceError> {
+        let frames = list_frames(&images_directory)?;
         Ok(Self {
-            images_directory: images_directory,
-            frames_image_data,
+            images_directory,
+            frames,
             crop,
-            as_content_changes: InputVideoChanges::default(),
-            generic_content_data,
+            ..Self::new(generic_content_data)
         })
     }
+    /// How many decoded frames are kept in memory.
+    pub fn set_cache_size(&mut self, frames: usize) {
+        self.cache.capacity = frames.max(1);
+        self.cache.shrink();
+    }
 }
 impl InputVideo {
     pub fn get_length(&self) -> usize {
         if self.crop.2 {
-            self.frames_image_data.len() - self.crop.0 as usize - self.crop.1 as usize
+            self.frames
+                .len()
+                .saturating_sub(self.crop.0 as usize + self.crop.1 as usize)
         } else {
-            (self.crop.1 - self.crop.0) as usize
+            (self.crop.1.saturating_sub(self.crop.0) as usize)
+                .min(self.frames.len().saturating_sub(self.crop.0 as usize))
         }
     }
     pub fn get_dir(&self) -> &PathBuf {
         &self.images_directory
     }
+    /// The index (in frames) of the frame that should be shown at this progress.
+    fn frame_index(&self, progress: f64) -> Option<usize> {
+        let length = self.get_length();
+        if length == 0 {
+            return None;
+        }
+        let frame = match &self.timing {
+            AnimationTiming::Stretch => ((length - 1) as f64 * progress).round().max(0.0) as usize,
+            AnimationTiming::Native {
+                length: seconds,
+                looping,
+            } => {
+                let frame = (progress * seconds * self.frame_rate).floor().max(0.0) as usize;
+                if *looping {
+                    frame % length
+                } else {
+                    frame.min(length - 1)
+                }
+            }
+        };
+        Some(self.crop.0 as usize + frame.min(length - 1))
+    }
     /// Equivalent to get_frame_fast with max_frames_distance = 0.
     pub fn get_frame<'a>(
         &'a mut self,
@@ -125,63 +195,416 @@ impl InputVideo {
     ) -> Option<&'a mut crate::content::image::Image> {
         self.get_frame_fast(progress, 0)
     }
-    /// If there is a frame that has already been loaded near the current frame, use that frame instead. DO NOT USE THIS FOR RENDERING THE FINAL VIDEO - IT WILL SKIP FRAMES WHENEVER IT POSSIBLY CAN! Returns None when there are no frames available (frames_image_data.is_empty()).
+    /// If there is a frame that has already been loaded near the current frame, use that frame instead. DO NOT USE THIS FOR RENDERING THE FINAL VIDEO - IT WILL SKIP FRAMES WHENEVER IT POSSIBLY CAN! Returns None when there are no frames available.
     pub fn get_frame_fast<'a>(
         &'a mut self,
         progress: f64,
         max_frames_distance: i8,
     ) -> Option<&'a mut crate::content::image::Image> {
-        if self.frames_image_data.is_empty() {
-            return None;
-        };
-        let mut index =
-            self.crop.0 as usize + ((self.get_length() - 1) as f64 * progress).round() as usize;
-        let offset = self.frames_image_data[index].0;
-        if if let Some(o) = offset {
-            let o_abs = o.abs();
-            if o_abs <= max_frames_distance {
-                if o == 0 {
-                } else if o > 0 {
-                    index += o_abs as usize;
-                } else if o < 0 {
-                    index -= o_abs as usize;
-                };
-                true
+        let mut index = self.frame_index(progress)?;
+        self.receive_prefetched();
+        match self
+            .cache
+            .nearest(index, max_frames_distance.max(0) as usize)
+        {
+            Some(nearest) => index = nearest,
+            // the image loads the frame when it is drawn
+            None => self.cache.insert(
+                index,
+                crate::content::image::Image::new(
+                    self.frames[index].clone(),
+                    self.generic_content_data.reset(),
+                ),
+            ),
+        }
+        self.prefetch(index);
+        let fit = self.fit.clone();
+        let frame = self.cache.get(index)?;
+        frame.fit = fit;
+        Some(frame)
+    }
+
+    /// Moves all frames that were decoded in the background into the cache.
+    fn receive_prefetched(&mut self) {
+        if let Some(prefetcher) = &mut self.prefetcher {
+            while let Ok((index, img)) = prefetcher.results.try_recv() {
+                prefetcher.pending.remove(&index);
+                if let Some(img) = img {
+                    let mut frame = crate::content::image::Image::new(
+                        self.frames[index].clone(),
+                        self.generic_content_data.reset(),
+                    );
+                    frame.img_original = Some(img);
+                    self.cache.insert(index, frame);
+                }
+            }
+        }
+    }
+    /// Starts decoding the frames after this one in the background, unless they are cached already.
+    fn prefetch(&mut self, index: usize) {
+        let end = (self.crop.0 as usize + self.get_length())
+            .min(index + 1 + PREFETCH_FRAMES.min(self.cache.capacity / 2));
+        let prefetcher = self.prefetcher.get_or_insert_with(Prefetcher::new);
+        for index in index + 1..end {
+            if !self.cache.contains(index) && prefetcher.pending.insert(index) {
+                if prefetcher
+                    .requests
+                    .send((index, self.frames[index].clone()))
+                    .is_err()
+                {
+                    // the thread is gone, so nothing can be prefetched
+                    prefetcher.pending.remove(&index);
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The most recently used frames.
+struct FrameCache {
+    capacity: usize,
+    /// frame index -> (when it was last used, frame)
+    frames: HashMap<usize, (u64, crate::content::image::Image)>,
+    time: u64,
+}
+impl FrameCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            frames: HashMap::new(),
+            time: 0,
+        }
+    }
+    fn clear(&mut self) {
+        self.frames.clear();
+    }
+    fn contains(&self, index: usize) -> bool {
+        self.frames.contains_key(&index)
+    }
+    fn get(&mut self, index: usize) -> Option<&mut crate::content::image::Image> {
+        self.time += 1;
+        let time = self.time;
+        self.frames.get_mut(&index).map(|(used, frame)| {
+            *used = time;
+            &mut *frame
+        })
+    }
+    fn insert(&mut self, index: usize, frame: crate::content::image::Image) {
+        self.time += 1;
+        self.frames.insert(index, (self.time, frame));
+        self.shrink();
+    }
+    /// Removes the least recently used frames until there are at most capacity frames left.
+    fn shrink(&mut self) {
+        while self.frames.len() > self.capacity {
+            if let Some(oldest) = self
+                .frames
+                .iter()
+                .min_by_key(|(_, (used, _))| *used)
+                .map(|(index, _)| *index)
+            {
+                self.frames.remove(&oldest);
+            }
+        }
+    }
+    /// The cached frame closest to index, if it is at most max_distance frames away.
+    fn nearest(&self, index: usize, max_distance: usize) -> Option<usize> {
+        (0..=max_distance).find_map(|dist| {
+            if self.contains(index + dist) {
+                Some(index + dist)
+            } else if dist <= index && self.contains(index - dist) {
+                Some(index - dist)
             } else {
-                false
+                None
             }
-        } else {
-            false
-        } {
-            // close enough, index was adjusted if necessary
-            Some(&mut self.frames_image_data[index].1)
-        } else {
-            Some(self.load_image_at_index(index))
+        })
+    }
+}
+
+/// Decodes frames on another thread. The thread stops when this is dropped.
+struct Prefetcher {
+    requests: mpsc::Sender<(usize, PathBuf)>,
+    results: mpsc::Receiver<(usize, Option<DynamicImage>)>,
+    /// Frames that were requested, but not received yet.
+    pending: HashSet<usize>,
+}
+impl Prefetcher {
+    fn new() -> Self {
+        let (requests, requests_receiver) = mpsc::channel::<(usize, PathBuf)>();
+        let (results_sender, results) = mpsc::channel();
+        std::thread::spawn(move || {
+            for (index, path) in requests_receiver {
+                let img = image::io::Reader::open(&path)
+                    .and_then(|r| r.with_guessed_format())
+                    .ok()
+                    .and_then(|r| r.decode().ok());
+                if results_sender.send((index, img)).is_err() {
+                    break;
+                }
+            }
+        });
+        Self {
+            requests,
+            results,
+            pending: HashSet::new(),
         }
     }
-    fn load_image_at_index<'a>(&'a mut self, index: usize) -> &'a mut crate::content::image::Image {
-        /* adjust the distances */
-        {
-            for dist in 0..127.min(index) as i8 {
-                let nindex = index - dist as usize;
-                if let Some(prev_dist) = self.frames_image_data[nindex].0 {
-                    if prev_dist.abs() <= dist {
-                        break;
+}
+
+#[derive(Debug)]
+pub enum ImageSequenceError {
+    Io(io::Error),
+    /// A %d pattern that isn't followed by d (after the optional width).
+    InvalidPattern(String),
+    NoFrames,
+    /// For %d patterns: the ranges (first, last) of frame numbers that are missing.
+    MissingFrames(Vec<(u64, u64)>),
+}
+impl From<io::Error> for ImageSequenceError {
+    fn from(value: io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+impl Display for ImageSequenceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            Self::InvalidPattern(p) => write!(
+                f,
+                "Invalid pattern '{p}': % must be followed by d or 0[width]d (like %05d)."
+            ),
+            Self::NoFrames => write!(f, "No frames were found."),
+            Self::MissingFrames(ranges) => {
+                write!(f, "Frames are missing:")?;
+                for (first, last) in ranges {
+                    if first == last {
+                        write!(f, " {first}")?;
+                    } else {
+                        write!(f, " {first}-{last}")?;
                     }
-                };
-                self.frames_image_data[nindex].0 = Some(dist);
+                }
+                Ok(())
             }
-            for dist in 1..127.min(self.frames_image_data.len() - index) as i8 {
-                let nindex = index + dist as usize;
-                if let Some(prev_dist) = self.frames_image_data[nindex].0 {
-                    if prev_dist.abs() <= dist {
-                        break;
-                    }
-                };
-                self.frames_image_data[nindex].0 = Some(-dist);
+        }
+    }
+}
+
+/// Lists the frames of an image sequence. path can be
+/// - a directory: every file in it is a frame, sorted by name
+/// - a path with a printf-style pattern as its file name (frame_%05d.png or frame_%d.png): the frames are sorted by their number, and there may not be any gaps in the numbering
+/// - a path with a glob pattern as its file name (frame_*.png, * matches any number of characters and ? one character): the frames are sorted by name
+pub fn list_frames(path: &Path) -> Result<Vec<PathBuf>, ImageSequenceError> {
+    let pattern = match path.file_name().map(|n| n.to_string_lossy().to_string()) {
+        Some(name) if !path.is_dir() && name.contains(['%', '*', '?']) => name,
+        _ => {
+            let mut frames = Vec::new();
+            for entry in fs::read_dir(path)? {
+                let path = entry?.path();
+                if path.is_file() {
+                    frames.push(path);
+                }
             }
+            frames.sort_unstable();
+            return if frames.is_empty() {
+                Err(ImageSequenceError::NoFrames)
+            } else {
+                Ok(frames)
+            };
+        }
+    };
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    };
+    let mut names = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let entry = entry?;
+        if entry.path().is_file() {
+            names.push(entry.file_name().to_string_lossy().to_string());
+        }
+    }
+    let frames: Vec<_> = if let Some(percent) = pattern.find('%') {
+        let (prefix, rest) = (&pattern[..percent], &pattern[percent + 1..]);
+        let width_len = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        if !rest[width_len..].starts_with('d') {
+            return Err(ImageSequenceError::InvalidPattern(pattern));
+        }
+        let width: usize = rest[..width_len].parse().unwrap_or(0);
+        let suffix = &rest[width_len + 1..];
+        let mut numbered: Vec<(u64, String)> = names
+            .into_iter()
+            .filter_map(|name| {
+                let number = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                if number.is_empty()
+                    || number.len() < width
+                    || !number.chars().all(|c| c.is_ascii_digit())
+                {
+                    return None;
+                }
+                Some((number.parse().ok()?, name))
+            })
+            .collect();
+        numbered.sort_unstable();
+        let missing: Vec<_> = numbered
+            .windows(2)
+            .filter(|w| w[1].0 > w[0].0 + 1)
+            .map(|w| (w[0].0 + 1, w[1].0 - 1))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ImageSequenceError::MissingFrames(missing));
+        }
+        numbered
+            .into_iter()
+            .map(|(_, name)| dir.join(name))
+            .collect()
+    } else {
+        let pattern: Vec<char> = pattern.chars().collect();
+        let mut matching: Vec<_> = names
+            .into_iter()
+            .filter(|name| glob_matches(&pattern, &name.chars().collect::<Vec<_>>()))
+            .collect();
+        matching.sort_unstable();
+        matching.into_iter().map(|name| dir.join(name)).collect()
+    };
+    if frames.is_empty() {
+        Err(ImageSequenceError::NoFrames)
+    } else {
+        Ok(frames)
+    }
+}
+
+/// * matches any number of characters, ? matches exactly one.
+fn glob_matches(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| glob_matches(&pattern[1..], &name[skip..])),
+        Some('?') => !name.is_empty() && glob_matches(&pattern[1..], &name[1..]),
+        Some(c) => name.first() == Some(c) && glob_matches(&pattern[1..], &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates a new temporary directory containing the given (empty) files.
+    fn dir_with_files(test: &str, names: &[String]) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_list_frames_{test}", std::process::id()));
+        _ = fs::remove_dir_all(&dir);
+        if let Err(e) = fs::create_dir_all(&dir) {
+            panic!("{e}");
+        }
+        for name in names {
+            if let Err(e) = fs::write(dir.join(name), []) {
+                panic!("{e}");
+            }
+        }
+        dir
+    }
+
+    /// The file names of the frames found for the pattern, or the error.
+    fn list(
+        test: &str,
+        names: &[String],
+        pattern: &str,
+    ) -> Result<Vec<String>, ImageSequenceError> {
+        let dir = dir_with_files(test, names);
+        let frames = list_frames(&dir.join(pattern));
+        _ = fs::remove_dir_all(&dir);
+        frames.map(|frames| {
+            frames
+                .iter()
+                .map(|f| f.file_name().unwrap().to_string_lossy().to_string())
+                .collect()
+        })
+    }
+
+    fn strings(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn padded_number_pattern() {
+        let names = strings(&[
+            "f_00002.png",
+            "f_00001.png",
+            "f_00003.png",
+            "f_4.png",
+            "f_00001.jpg",
+            "other.png",
+        ]);
+        match list("padded", &names, "f_%05d.png") {
+            Ok(frames) => assert_eq!(
+                frames,
+                strings(&["f_00001.png", "f_00002.png", "f_00003.png"])
+            ),
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[test]
+    fn unpadded_number_pattern_sorts_numerically() {
+        let names: Vec<_> = (1..=11).map(|i| format!("f_{i}.png")).collect();
+        match list("unpadded", &names, "f_%d.png") {
+            Ok(frames) => assert_eq!(frames, names),
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[test]
+    fn gap_in_numbering_is_an_error() {
+        let names = strings(&["f_1.png", "f_2.png", "f_5.png", "f_7.png"]);
+        match list("gap", &names, "f_%d.png") {
+            Err(ImageSequenceError::MissingFrames(missing)) => {
+                assert_eq!(missing, vec![(3, 4), (6, 6)])
+            }
+            Err(e) => panic!("{e}"),
+            Ok(frames) => panic!("expected missing frames, got {frames:?}"),
+        }
+    }
+
+    #[test]
+    fn invalid_number_pattern() {
+        assert!(matches!(
+            list("invalid", &strings(&["f_1.png"]), "f_%x.png"),
+            Err(ImageSequenceError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn glob_patterns_sort_by_name() {
+        let names = strings(&["a1.png", "a2.png", "a10.png", "b1.png", "a1.jpg"]);
+        match list("star", &names, "a*.png") {
+            Ok(frames) => assert_eq!(frames, strings(&["a1.png", "a10.png", "a2.png"])),
+            Err(e) => panic!("{e}"),
+        }
+        match list("question", &names, "a?.png") {
+            Ok(frames) => assert_eq!(frames, strings(&["a1.png", "a2.png"])),
+            Err(e) => panic!("{e}"),
+        }
+        assert!(matches!(
+            list("no_match", &names, "c*.png"),
+            Err(ImageSequenceError::NoFrames)
+        ));
+    }
+
+    #[test]
+    fn glob_matching() {
+        let matches = |pattern: &str, name: &str| {
+            glob_matches(
+                &pattern.chars().collect::<Vec<_>>(),
+                &name.chars().collect::<Vec<_>>(),
+            )
         };
-        // return the image
-        &mut self.frames_image_data[index].1
+        assert!(matches("*", ""));
+        assert!(matches("*.png", "frame.png"));
+        assert!(matches("f*e*.png", "frame_01.png"));
+        assert!(!matches("*.png", "frame.png.bak"));
+        assert!(matches("frame_??.png", "frame_01.png"));
+        assert!(!matches("frame_??.png", "frame_1.png"));
+        assert!(!matches("?", ""));
     }
 }
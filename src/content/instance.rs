@@ -0,0 +1,112 @@
+use std::cell::RefCell;
+
+use image::DynamicImage;
+
+use crate::{video::PrepDrawData, video_render_settings::VideoRenderSettings};
+
+use super::content::{Content, GenericContentData};
+
+thread_local! {
+    /// The labels of all shared videos that are currently being drawn, used to detect shared videos that (indirectly) contain an instance of themselves.
+    static DRAWING: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+/// Draws one of the project's shared videos (see project::SharedVideos) inside this video's box, using this video's position and timing. Unlike a copy, all instances change when the shared video is changed.
+pub struct Instance {
+    label: String,
+    warned: bool,
+    generic_content_data: GenericContentData,
+    pub as_content_changes: InstanceChanges,
+}
+#[derive(Default)]
+pub struct InstanceChanges {
+    pub label: Option<String>,
+}
+
+impl Content for Instance {
+    fn clone_no_caching(&self) -> Self {
+        Self::new(self.label.clone(), self.generic_content_data.reset())
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        Vec::new()
+    }
+
+    fn has_changes(&self) -> bool {
+        self.as_content_changes.label.is_some()
+    }
+    fn apply_changes(&mut self) -> bool {
+        if let Some(label) = self.as_content_changes.label.take() {
+            self.set_label(label);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn generic_content_data(&mut self) -> &mut GenericContentData {
+        &mut self.generic_content_data
+    }
+}
+
+impl Instance {
+    pub fn new(label: String, generic_content_data: GenericContentData) -> Self {
+        Self {
+            label,
+            warned: false,
+            generic_content_data,
+            as_content_changes: InstanceChanges::default(),
+        }
+    }
+    pub fn label(&self) -> &String {
+        &self.label
+    }
+    pub fn set_label(&mut self, new: String) {
+        self.label = new;
+        self.warned = false;
+    }
+
+    pub fn draw(
+        &mut self,
+        image: &mut DynamicImage,
+        prep_draw: &PrepDrawData,
+        render_settings: &mut VideoRenderSettings,
+    ) {
+        if DRAWING.with(|drawing| drawing.borrow().contains(&self.label)) {
+            if !self.warned {
+                eprintln!(
+                    "Shared video '{}' contains an instance of itself, it will not be drawn there.",
+                    self.label
+                );
+                self.warned = true;
+            }
+            return;
+        }
+        let vid = match self
+            .generic_content_data
+            .project
+            .shared_videos
+            .get(&self.label)
+        {
+            Some(v) => v,
+            None => {
+                if !self.warned {
+                    eprintln!("There is no shared video called '{}'.", self.label);
+                    self.warned = true;
+                }
+                return;
+            }
+        };
+        DRAWING.with(|drawing| drawing.borrow_mut().push(self.label.clone()));
+        {
+            let mut vid = vid.lock().unwrap();
+            if let Some(prep_draw) = vid.prep_draw(prep_draw.progress, Some(prep_draw)) {
+                vid.draw(image, prep_draw, render_settings);
+            }
+        }
+        DRAWING.with(|drawing| drawing.borrow_mut().pop());
+    }
+}
@@ -1,5 +1,15 @@
 pub mod content;
 pub mod image;
+pub mod external_image;
+pub mod image_fit;
 pub mod input_video;
 pub mod ffmpeg_vid;
-pub mod text;
\ No newline at end of file
+pub mod text;
+pub mod text_layout;
+pub mod text_markup;
+pub mod shape;
+pub mod gradient;
+pub mod svg;
+pub mod animated_image;
+pub mod nested_project;
+pub mod instance;
@@ -0,0 +1,211 @@
+use std::{
+    cell::RefCell,
+    path::PathBuf,
+    time::{Duration, Instant, SystemTime},
+};
+
+use image::DynamicImage;
+
+use crate::{project::Project, video::PrepDrawData, video_render_settings::VideoRenderSettings};
+
+use super::content::{Content, GenericContentData};
+
+/// How often the file's modification time is checked to see if the project has to be reloaded.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+thread_local! {
+    /// The (canonicalized) paths of all nested projects that are currently being drawn, used to detect projects that (indirectly) contain themselves.
+    static DRAWING: RefCell<Vec<PathBuf>> = RefCell::new(Vec::new());
+}
+
+/// Another project file, drawn inside the video's box. The file is reloaded when it changes. Parameters replace {{name}} placeholders in the file (see file_handler::read_from_file_with_parameters), so one file can be used with different texts, colors, ...
+pub struct NestedProject {
+    path: PathBuf,
+    parameters: Vec<(String, String)>,
+    /// The path of the project this video is part of, so that a project can't contain itself.
+    outer_path: Option<PathBuf>,
+    canonical_path: PathBuf,
+    project: Option<Project>,
+    load_attempted: bool,
+    /// The file's modification time when it was loaded.
+    modified: Option<SystemTime>,
+    last_check: Option<Instant>,
+    warned_about_cycle: bool,
+    generic_content_data: GenericContentData,
+    pub as_content_changes: NestedProjectChanges,
+}
+#[derive(Default)]
+pub struct NestedProjectChanges {
+    pub path: Option<PathBuf>,
+    pub parameters: Option<Vec<(String, String)>>,
+}
+
+impl Content for NestedProject {
+    fn clone_no_caching(&self) -> Self {
+        Self::new(
+            self.path.clone(),
+            self.parameters.clone(),
+            self.generic_content_data.reset(),
+        )
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        Vec::new()
+    }
+
+    fn has_changes(&self) -> bool {
+        self.as_content_changes.path.is_some() || self.as_content_changes.parameters.is_some()
+    }
+    fn apply_changes(&mut self) -> bool {
+        let mut out = false;
+        if let Some(path) = self.as_content_changes.path.take() {
+            self.set_path(path);
+            out = true;
+        }
+        if let Some(parameters) = self.as_content_changes.parameters.take() {
+            self.set_parameters(parameters);
+            out = true;
+        }
+        out
+    }
+
+    fn generic_content_data(&mut self) -> &mut GenericContentData {
+        &mut self.generic_content_data
+    }
+}
+
+impl NestedProject {
+    pub fn new(
+        path: PathBuf,
+        parameters: Vec<(String, String)>,
+        generic_content_data: GenericContentData,
+    ) -> Self {
+        // try_lock: the project data might be locked while the project is being drawn or exported
+        let outer_path = match generic_content_data.project.proj.try_lock() {
+            Ok(proj) => proj
+                .path
+                .as_ref()
+                .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.clone())),
+            Err(_) => None,
+        };
+        Self {
+            canonical_path: std::fs::canonicalize(&path).unwrap_or_else(|_| path.clone()),
+            path,
+            parameters,
+            outer_path,
+            project: None,
+            load_attempted: false,
+            modified: None,
+            last_check: None,
+            warned_about_cycle: false,
+            generic_content_data,
+            as_content_changes: NestedProjectChanges::default(),
+        }
+    }
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+    pub fn set_path(&mut self, new: PathBuf) {
+        self.canonical_path = std::fs::canonicalize(&new).unwrap_or_else(|_| new.clone());
+        self.path = new;
+        self.unload();
+    }
+    pub fn parameters(&self) -> &Vec<(String, String)> {
+        &self.parameters
+    }
+    pub fn set_parameters(&mut self, new: Vec<(String, String)>) {
+        self.parameters = new;
+        self.unload();
+    }
+    fn unload(&mut self) {
+        self.project = None;
+        self.load_attempted = false;
+        self.modified = None;
+        self.last_check = None;
+        self.warned_about_cycle = false;
+    }
+
+    /// Loads the project if it wasn't loaded yet or if the file changed since it was loaded.
+    fn reload_if_necessary(&mut self) {
+        let now = Instant::now();
+        if self.load_attempted
+            && matches!(self.last_check, Some(t) if now.duration_since(t) < RELOAD_CHECK_INTERVAL)
+        {
+            return;
+        }
+        self.last_check = Some(now);
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok();
+        if self.load_attempted && modified == self.modified {
+            return;
+        }
+        self.load_attempted = true;
+        self.modified = modified;
+        self.project = match crate::files::file_handler::read_from_file_with_parameters(
+            &self.path,
+            &self.parameters,
+        ) {
+            Ok(Ok(project)) => Some(project),
+            Ok(Err(err)) => {
+                eprintln!("Could not load project '{}': {}", self.path.display(), err);
+                None
+            }
+            Err(err) => {
+                eprintln!("Could not open file at '{}': {}", self.path.display(), err);
+                None
+            }
+        };
+    }
+
+    pub fn draw(
+        &mut self,
+        image: &mut DynamicImage,
+        prep_draw: &PrepDrawData,
+        render_settings: &mut VideoRenderSettings,
+    ) {
+        let is_cycle = self.outer_path.as_ref() == Some(&self.canonical_path)
+            || DRAWING.with(|drawing| drawing.borrow().contains(&self.canonical_path));
+        if is_cycle {
+            if !self.warned_about_cycle {
+                eprintln!(
+                    "Project '{}' contains itself, it will not be drawn.",
+                    self.path.display()
+                );
+                self.warned_about_cycle = true;
+            }
+            return;
+        }
+        self.reload_if_necessary();
+        let vid = match &self.project {
+            Some(project) => project.vid(),
+            None => return,
+        };
+        // the outer project is part of the chain too, so that A -> B -> A is stopped before A is drawn inside B
+        let pushed = DRAWING.with(|drawing| {
+            let mut drawing = drawing.borrow_mut();
+            let len = drawing.len();
+            if let Some(outer_path) = &self.outer_path {
+                if !drawing.contains(outer_path) {
+                    drawing.push(outer_path.clone());
+                }
+            }
+            drawing.push(self.canonical_path.clone());
+            drawing.len() - len
+        });
+        {
+            let mut vid = vid.lock().unwrap();
+            if let Some(prep_draw) = vid.prep_draw(prep_draw.progress, Some(prep_draw)) {
+                vid.draw(image, prep_draw, render_settings);
+            }
+        }
+        DRAWING.with(|drawing| {
+            let mut drawing = drawing.borrow_mut();
+            let len = drawing.len() - pushed;
+            drawing.truncate(len);
+        });
+    }
+}
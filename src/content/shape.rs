@@ -0,0 +1,830 @@
+//! Vector shapes which are drawn into the video's box: rectangles (with rounded corners), ellipses, regular polygons and paths in SVG path-data syntax.
+
+use image::{DynamicImage, RgbaImage};
+
+use crate::{
+    curve::{Curve, CurveData},
+    types::Color,
+};
+
+use super::{
+    content::{Content, GenericContentData},
+    text_layout::Mask,
+};
+
+pub struct Shape {
+    kind: ShapeKind,
+    fill: Option<Color>,
+    stroke: Option<ShapeStroke>,
+    generic_content_data: GenericContentData,
+    pub as_content_changes: ShapeChanges,
+}
+
+#[derive(Default)]
+pub struct ShapeChanges {
+    pub kind: Option<ShapeKind>,
+    pub fill: Option<Option<Color>>,
+    pub stroke: Option<Option<ShapeStroke>>,
+}
+
+/// Sizes are relative to the smaller side of the video's box. Rectangles, ellipses and polygons fill the box, their stroke is drawn inside of it.
+#[derive(Clone)]
+pub enum ShapeKind {
+    Rectangle {
+        corner_radius: Curve,
+    },
+    Ellipse,
+    /// A regular polygon with its corners on the ellipse that fills the box. With a rotation of 0, one corner is at the top. rotation is in full turns (1 = 360°).
+    Polygon {
+        sides: Curve,
+        rotation: Curve,
+    },
+    Path(ShapePath),
+}
+
+/// A line along the shape's outline, centered on it, with round corners and ends.
+#[derive(Clone)]
+pub struct ShapeStroke {
+    pub width: Curve,
+    pub color: Color,
+}
+
+impl Content for Shape {
+    fn clone_no_caching(&self) -> Self {
+        let mut new = Self::new(self.kind.clone(), self.generic_content_data.reset());
+        new.fill = self.fill.clone();
+        new.stroke = self.stroke.clone();
+        new
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        Vec::new()
+    }
+
+    fn has_changes(&self) -> bool {
+        self.as_content_changes.kind.is_some()
+            || self.as_content_changes.fill.is_some()
+            || self.as_content_changes.stroke.is_some()
+    }
+    fn apply_changes(&mut self) -> bool {
+        let mut out = false;
+        if let Some(kind) = self.as_content_changes.kind.take() {
+            self.kind = kind;
+            out = true;
+        }
+        if let Some(fill) = self.as_content_changes.fill.take() {
+            self.fill = fill;
+            out = true;
+        }
+        if let Some(stroke) = self.as_content_changes.stroke.take() {
+            self.stroke = stroke;
+            out = true;
+        }
+        out
+    }
+
+    fn generic_content_data(&mut self) -> &mut GenericContentData {
+        &mut self.generic_content_data
+    }
+}
+
+impl Shape {
+    /// A white shape without a stroke.
+    pub fn new(kind: ShapeKind, generic_content_data: GenericContentData) -> Self {
+        Self {
+            kind,
+            fill: Some(Color::RGBA(
+                CurveData::Constant(1.0).into(),
+                CurveData::Constant(1.0).into(),
+                CurveData::Constant(1.0).into(),
+                CurveData::Constant(1.0).into(),
+            )),
+            stroke: None,
+            generic_content_data,
+            as_content_changes: ShapeChanges::default(),
+        }
+    }
+    pub fn kind(&self) -> &ShapeKind {
+        &self.kind
+    }
+    pub fn set_fill(&mut self, fill: Option<Color>) {
+        self.fill = fill;
+    }
+    pub fn set_stroke(&mut self, stroke: Option<ShapeStroke>) {
+        self.stroke = stroke;
+    }
+
+    pub fn draw(&mut self, image: &mut DynamicImage, prep_draw: &crate::video::PrepDrawData) {
+        let (width, height) = (prep_draw.pos_px.2 as u32, prep_draw.pos_px.3 as u32);
+        if width == 0 || height == 0 {
+            return;
+        }
+        let progress = prep_draw.progress;
+        let size = (width as f32, height as f32);
+        let unit = size.0.min(size.1);
+        let stroke_width = match &self.stroke {
+            Some(stroke) => (stroke.width.get_value(progress) as f32 * unit).max(0.0),
+            None => 0.0,
+        };
+        let outline = self.kind.outline(progress, size, stroke_width / 2.0);
+        let mut layer = RgbaImage::new(width, height);
+        if let Some(fill) = &self.fill {
+            fill_mask(&outline, width as usize, height as usize).paint(
+                &mut layer,
+                fill.get_rgba(progress),
+                (0, 0),
+            );
+        }
+        if let (Some(stroke), true) = (&self.stroke, stroke_width > 0.0) {
+            stroke_mask(&outline, stroke_width, width as usize, height as usize).paint(
+                &mut layer,
+                stroke.color.get_rgba(progress),
+                (0, 0),
+            );
+        }
+        crate::video::composite_images(image, &DynamicImage::ImageRgba8(layer), prep_draw);
+    }
+}
+
+type Point = (f32, f32);
+
+/// A part of an outline, in pixels.
+struct Subpath {
+    points: Vec<Point>,
+    closed: bool,
+}
+
+impl ShapeKind {
+    /// The outline in pixels. inset moves rectangles, ellipses and polygons inwards so that their stroke stays inside the box.
+    fn outline(&self, progress: f64, size: Point, inset: f32) -> Vec<Subpath> {
+        let unit = size.0.min(size.1);
+        let (cx, cy) = (size.0 / 2.0, size.1 / 2.0);
+        let (rx, ry) = ((cx - inset).max(0.0), (cy - inset).max(0.0));
+        match self {
+            Self::Rectangle { corner_radius } => {
+                let radius = (corner_radius.get_value(progress) as f32 * unit)
+                    .max(0.0)
+                    .min(rx)
+                    .min(ry);
+                let mut points = Vec::new();
+                // one quarter circle per corner, clockwise starting at the top right
+                for (corner, (sx, sy)) in [(1.0, -1.0), (1.0, 1.0), (-1.0, 1.0), (-1.0, -1.0)]
+                    .into_iter()
+                    .enumerate()
+                {
+                    let (ccx, ccy) = (cx + sx * (rx - radius), cy + sy * (ry - radius));
+                    let start = (corner as f32 - 1.0) * std::f32::consts::FRAC_PI_2;
+                    let n = if radius > 0.0 {
+                        segments(radius * std::f32::consts::FRAC_PI_2)
+                    } else {
+                        1
+                    };
+                    for i in 0..=n {
+                        let angle = start + std::f32::consts::FRAC_PI_2 * i as f32 / n as f32;
+                        points.push((ccx + radius * angle.cos(), ccy + radius * angle.sin()));
+                    }
+                }
+                vec![Subpath {
+                    points,
+                    closed: true,
+                }]
+            }
+            Self::Ellipse => {
+                let n = segments(std::f32::consts::TAU * rx.max(ry)).max(16);
+                vec![Subpath {
+                    points: (0..n)
+                        .map(|i| {
+                            let angle = std::f32::consts::TAU * i as f32 / n as f32;
+                            (cx + rx * angle.cos(), cy + ry * angle.sin())
+                        })
+                        .collect(),
+                    closed: true,
+                }]
+            }
+            Self::Polygon { sides, rotation } => {
+                let sides = (sides.get_value(progress).round() as usize).clamp(3, 512);
+                let rotation = rotation.get_value(progress) as f32 * std::f32::consts::TAU;
+                vec![Subpath {
+                    points: (0..sides)
+                        .map(|i| {
+                            let angle = rotation
+                                + std::f32::consts::TAU * i as f32 / sides as f32
+                                - std::f32::consts::FRAC_PI_2;
+                            (cx + rx * angle.cos(), cy + ry * angle.sin())
+                        })
+                        .collect(),
+                    closed: true,
+                }]
+            }
+            Self::Path(path) => path.flatten(size),
+        }
+    }
+}
+
+/// How many straight lines are used to approximate a curve which is about length pixels long.
+fn segments(length: f32) -> usize {
+    ((length / 3.0).ceil() as usize).clamp(4, 512)
+}
+
+/// A path in SVG path-data syntax (M, L, H, V, C, S, Q, T, A and Z, also relative). Coordinates are relative to the video's box: 0;0 is the top left corner and 1;1 the bottom right one.
+#[derive(Clone)]
+pub struct ShapePath {
+    commands: Vec<PathCommand>,
+}
+
+/// Absolute coordinates, relative to the box.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum PathCommand {
+    Move(Point),
+    Line(Point),
+    Quad(Point, Point),
+    Cubic(Point, Point, Point),
+    Arc {
+        radii: Point,
+        /// degrees
+        rotation: f32,
+        large_arc: bool,
+        sweep: bool,
+        to: Point,
+    },
+    Close,
+}
+
+impl ShapePath {
+    pub fn parse(data: &str) -> Result<Self, String> {
+        let mut reader = PathReader {
+            data: data.as_bytes(),
+            index: 0,
+        };
+        let mut commands = Vec::new();
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        // the last control point of a C/S or Q/T command, for S and T
+        let mut last_cubic: Option<Point> = None;
+        let mut last_quad: Option<Point> = None;
+        let mut previous: Option<u8> = None;
+        loop {
+            reader.skip_separators();
+            let command = match reader.peek() {
+                None => break,
+                Some(c) if c.is_ascii_alphabetic() => {
+                    reader.index += 1;
+                    c
+                }
+                // the previous command is repeated, a repeated M is an L
+                Some(_) => match previous {
+                    Some(b'M') => b'L',
+                    Some(b'm') => b'l',
+                    Some(b'Z' | b'z') | None => {
+                        return Err(format!(
+                            "expected a command at position {}",
+                            reader.index + 1
+                        ))
+                    }
+                    Some(c) => c,
+                },
+            };
+            if previous.is_none() && command != b'M' && command != b'm' {
+                return Err(format!("path data has to start with M or m"));
+            }
+            let offset = if command.is_ascii_lowercase() {
+                current
+            } else {
+                (0.0, 0.0)
+            };
+            let point = |reader: &mut PathReader| -> Result<Point, String> {
+                Ok((reader.number()? + offset.0, reader.number()? + offset.1))
+            };
+            let (mut cubic, mut quad) = (None, None);
+            match command.to_ascii_uppercase() {
+                b'M' => {
+                    current = point(&mut reader)?;
+                    start = current;
+                    commands.push(PathCommand::Move(current));
+                }
+                b'L' => {
+                    current = point(&mut reader)?;
+                    commands.push(PathCommand::Line(current));
+                }
+                b'H' => {
+                    current.0 = reader.number()? + offset.0;
+                    commands.push(PathCommand::Line(current));
+                }
+                b'V' => {
+                    current.1 = reader.number()? + offset.1;
+                    commands.push(PathCommand::Line(current));
+                }
+                b'C' | b'S' => {
+                    let c1 = if command.to_ascii_uppercase() == b'C' {
+                        point(&mut reader)?
+                    } else {
+                        reflect(last_cubic, current)
+                    };
+                    let c2 = point(&mut reader)?;
+                    current = point(&mut reader)?;
+                    cubic = Some(c2);
+                    commands.push(PathCommand::Cubic(c1, c2, current));
+                }
+                b'Q' | b'T' => {
+                    let c = if command.to_ascii_uppercase() == b'Q' {
+                        point(&mut reader)?
+                    } else {
+                        reflect(last_quad, current)
+                    };
+                    current = point(&mut reader)?;
+                    quad = Some(c);
+                    commands.push(PathCommand::Quad(c, current));
+                }
+                b'A' => {
+                    let radii = (reader.number()?.abs(), reader.number()?.abs());
+                    let rotation = reader.number()?;
+                    let large_arc = reader.flag()?;
+                    let sweep = reader.flag()?;
+                    current = point(&mut reader)?;
+                    commands.push(PathCommand::Arc {
+                        radii,
+                        rotation,
+                        large_arc,
+                        sweep,
+                        to: current,
+                    });
+                }
+                b'Z' => {
+                    current = start;
+                    commands.push(PathCommand::Close);
+                }
+                _ => return Err(format!("'{}' is not a path command", command as char)),
+            }
+            last_cubic = cubic;
+            last_quad = quad;
+            previous = Some(command);
+        }
+        Ok(Self { commands })
+    }
+
+    fn flatten(&self, size: Point) -> Vec<Subpath> {
+        let scale = |p: Point| (p.0 * size.0, p.1 * size.1);
+        let mut subpaths = Vec::new();
+        let mut points: Vec<Point> = Vec::new();
+        let mut current = (0.0, 0.0);
+        let mut start = (0.0, 0.0);
+        for command in self.commands.iter() {
+            match *command {
+                PathCommand::Move(p) => {
+                    if points.len() > 1 {
+                        subpaths.push(Subpath {
+                            points: std::mem::take(&mut points),
+                            closed: false,
+                        });
+                    }
+                    points = vec![scale(p)];
+                    start = p;
+                    current = p;
+                }
+                PathCommand::Line(p) => {
+                    points.push(scale(p));
+                    current = p;
+                }
+                PathCommand::Quad(c, p) => {
+                    let n = segments(distance(scale(current), scale(c)) + distance(scale(c), scale(p)));
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let u = 1.0 - t;
+                        points.push(scale((
+                            u * u * current.0 + 2.0 * u * t * c.0 + t * t * p.0,
+                            u * u * current.1 + 2.0 * u * t * c.1 + t * t * p.1,
+                        )));
+                    }
+                    current = p;
+                }
+                PathCommand::Cubic(c1, c2, p) => {
+                    let n = segments(
+                        distance(scale(current), scale(c1))
+                            + distance(scale(c1), scale(c2))
+                            + distance(scale(c2), scale(p)),
+                    );
+                    for i in 1..=n {
+                        let t = i as f32 / n as f32;
+                        let u = 1.0 - t;
+                        let (a, b, c, d) = (u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t);
+                        points.push(scale((
+                            a * current.0 + b * c1.0 + c * c2.0 + d * p.0,
+                            a * current.1 + b * c1.1 + c * c2.1 + d * p.1,
+                        )));
+                    }
+                    current = p;
+                }
+                PathCommand::Arc {
+                    radii,
+                    rotation,
+                    large_arc,
+                    sweep,
+                    to,
+                } => {
+                    arc_points(current, radii, rotation, large_arc, sweep, to, size, &mut |p| {
+                        points.push(scale(p))
+                    });
+                    current = to;
+                }
+                PathCommand::Close => {
+                    if points.len() > 1 {
+                        subpaths.push(Subpath {
+                            points: std::mem::take(&mut points),
+                            closed: true,
+                        });
+                    }
+                    // drawing can continue from the subpath's start
+                    points = vec![scale(start)];
+                    current = start;
+                }
+            }
+        }
+        if points.len() > 1 {
+            subpaths.push(Subpath {
+                points,
+                closed: false,
+            });
+        }
+        subpaths
+    }
+}
+
+/// Adds the points of an SVG arc (after from) by converting it to its center parameterization, see the SVG spec's implementation notes.
+fn arc_points(
+    from: Point,
+    radii: Point,
+    rotation: f32,
+    large_arc: bool,
+    sweep: bool,
+    to: Point,
+    size: Point,
+    add: &mut impl FnMut(Point),
+) {
+    let (mut rx, mut ry) = radii;
+    if rx == 0.0 || ry == 0.0 || (from.0 == to.0 && from.1 == to.1) {
+        add(to);
+        return;
+    }
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let (dx, dy) = ((from.0 - to.0) / 2.0, (from.1 - to.1) / 2.0);
+    let (x1, y1) = (cos * dx + sin * dy, -sin * dx + cos * dy);
+    // radii which are too small are scaled up
+    let lambda = (x1 * x1) / (rx * rx) + (y1 * y1) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y1 * y1 - ry * ry * x1 * x1;
+    let denominator = rx * rx * y1 * y1 + ry * ry * x1 * x1;
+    let mut factor = (numerator / denominator).max(0.0).sqrt();
+    if large_arc == sweep {
+        factor = -factor;
+    }
+    let (cx1, cy1) = (factor * rx * y1 / ry, -factor * ry * x1 / rx);
+    let center = (
+        cos * cx1 - sin * cy1 + (from.0 + to.0) / 2.0,
+        sin * cx1 + cos * cy1 + (from.1 + to.1) / 2.0,
+    );
+    let angle = |ux: f32, uy: f32| uy.atan2(ux);
+    let start = angle((x1 - cx1) / rx, (y1 - cy1) / ry);
+    let mut delta = angle((-x1 - cx1) / rx, (-y1 - cy1) / ry) - start;
+    if sweep && delta < 0.0 {
+        delta += std::f32::consts::TAU;
+    } else if !sweep && delta > 0.0 {
+        delta -= std::f32::consts::TAU;
+    }
+    let n = segments(delta.abs() * (rx * size.0).max(ry * size.1));
+    for i in 1..=n {
+        let a = start + delta * i as f32 / n as f32;
+        let (x, y) = (rx * a.cos(), ry * a.sin());
+        add((
+            center.0 + cos * x - sin * y,
+            center.1 + sin * x + cos * y,
+        ));
+    }
+}
+
+fn distance(a: Point, b: Point) -> f32 {
+    ((a.0 - b.0).powi(2) + (a.1 - b.1).powi(2)).sqrt()
+}
+
+/// The reflection of the previous command's last control point at the current point, or the current point if the previous command had no such control point.
+fn reflect(control: Option<Point>, current: Point) -> Point {
+    match control {
+        Some(c) => (2.0 * current.0 - c.0, 2.0 * current.1 - c.1),
+        None => current,
+    }
+}
+
+struct PathReader<'a> {
+    data: &'a [u8],
+    index: usize,
+}
+impl PathReader<'_> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.index).copied()
+    }
+    fn skip_separators(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r' | b',') = self.peek() {
+            self.index += 1;
+        }
+    }
+    fn number(&mut self) -> Result<f32, String> {
+        self.skip_separators();
+        let start = self.index;
+        if let Some(b'+' | b'-') = self.peek() {
+            self.index += 1;
+        }
+        let mut dot = false;
+        let mut exponent = false;
+        while let Some(c) = self.peek() {
+            match c {
+                b'0'..=b'9' => (),
+                // "0.5.5" is two numbers
+                b'.' if !dot && !exponent => dot = true,
+                b'e' | b'E' if !exponent && self.index > start => {
+                    exponent = true;
+                    if let Some(b'+' | b'-') = self.data.get(self.index + 1) {
+                        self.index += 1;
+                    }
+                }
+                _ => break,
+            }
+            self.index += 1;
+        }
+        let str = String::from_utf8_lossy(&self.data[start..self.index]);
+        str.parse()
+            .map_err(|_| format!("expected a number at position {}", start + 1))
+    }
+    /// An arc's large-arc and sweep flags are single characters and don't need separators.
+    fn flag(&mut self) -> Result<bool, String> {
+        self.skip_separators();
+        let out = match self.peek() {
+            Some(b'0') => false,
+            Some(b'1') => true,
+            _ => return Err(format!("expected 0 or 1 at position {}", self.index + 1)),
+        };
+        self.index += 1;
+        Ok(out)
+    }
+}
+
+/// Anti-aliased coverage of the area inside the outline (nonzero rule, open subpaths are closed).
+fn fill_mask(outline: &[Subpath], width: usize, height: usize) -> Mask {
+    // rows are sampled at this many heights per pixel, horizontal coverage is exact
+    const SAMPLES: usize = 4;
+    let mut mask = Mask::new(0, 0, width, height);
+    let edges: Vec<(Point, Point)> = outline
+        .iter()
+        .flat_map(|subpath| {
+            let points = &subpath.points;
+            (0..points.len()).map(move |i| (points[i], points[(i + 1) % points.len()]))
+        })
+        .filter(|(a, b)| a.1 != b.1)
+        .collect();
+    let mut crossings = Vec::new();
+    for y in 0..height {
+        let row = &mut mask.data[y * width..(y + 1) * width];
+        for sample in 0..SAMPLES {
+            let sy = y as f32 + (sample as f32 + 0.5) / SAMPLES as f32;
+            crossings.clear();
+            for (a, b) in edges.iter() {
+                if (a.1 <= sy) != (b.1 <= sy) {
+                    let t = (sy - a.1) / (b.1 - a.1);
+                    crossings.push((a.0 + t * (b.0 - a.0), if b.1 > a.1 { 1 } else { -1 }));
+                }
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut winding = 0;
+            for i in 0..crossings.len().saturating_sub(1) {
+                winding += crossings[i].1;
+                if winding != 0 {
+                    add_span(row, crossings[i].0, crossings[i + 1].0, 1.0 / SAMPLES as f32);
+                }
+            }
+        }
+        for v in row.iter_mut() {
+            *v = v.min(1.0);
+        }
+    }
+    mask
+}
+
+/// Adds weight times how much of each pixel is between x0 and x1.
+fn add_span(row: &mut [f32], x0: f32, x1: f32, weight: f32) {
+    let (x0, x1) = (x0.max(0.0), x1.min(row.len() as f32));
+    if x1 <= x0 {
+        return;
+    }
+    for x in (x0.floor() as usize)..(x1.ceil() as usize).min(row.len()) {
+        let covered = x1.min(x as f32 + 1.0) - x0.max(x as f32);
+        row[x] += covered * weight;
+    }
+}
+
+/// Anti-aliased coverage of a line of the given width along the outline.
+fn stroke_mask(outline: &[Subpath], stroke_width: f32, width: usize, height: usize) -> Mask {
+    let half = stroke_width / 2.0;
+    let mut mask = Mask::new(0, 0, width, height);
+    for subpath in outline {
+        let points = &subpath.points;
+        let count = if subpath.closed {
+            points.len()
+        } else {
+            points.len() - 1
+        };
+        for i in 0..count {
+            let (a, b) = (points[i], points[(i + 1) % points.len()]);
+            // only the pixels near the segment can be covered by it
+            let x0 = (a.0.min(b.0) - half - 1.0).floor().max(0.0) as usize;
+            let y0 = (a.1.min(b.1) - half - 1.0).floor().max(0.0) as usize;
+            let x1 = ((a.0.max(b.0) + half + 1.0).ceil().max(0.0) as usize).min(width);
+            let y1 = ((a.1.max(b.1) + half + 1.0).ceil().max(0.0) as usize).min(height);
+            let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+            let length_sq = dx * dx + dy * dy;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let p = (x as f32 + 0.5, y as f32 + 0.5);
+                    let t = if length_sq > 0.0 {
+                        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / length_sq).clamp(0.0, 1.0)
+                    } else {
+                        0.0
+                    };
+                    let d = distance(p, (a.0 + t * dx, a.1 + t * dy));
+                    let v = &mut mask.data[x + y * width];
+                    *v = v.max((half + 0.5 - d).clamp(0.0, 1.0));
+                }
+            }
+        }
+    }
+    mask
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(data: &str) -> Vec<PathCommand> {
+        match ShapePath::parse(data) {
+            Ok(v) => v.commands,
+            Err(e) => panic!("{e}"),
+        }
+    }
+    fn arc(from: Point, radii: Point, large_arc: bool, sweep: bool, to: Point) -> Vec<Point> {
+        let mut points = Vec::new();
+        arc_points(
+            from,
+            radii,
+            0.0,
+            large_arc,
+            sweep,
+            to,
+            (100.0, 100.0),
+            &mut |p| points.push(p),
+        );
+        points
+    }
+    fn close(a: Point, b: Point) -> bool {
+        distance(a, b) < 1e-4
+    }
+    /// if one of the points is within a segment's length of `p`
+    fn passes(points: &[Point], p: Point) -> bool {
+        points.iter().any(|q| distance(*q, p) < 0.02)
+    }
+
+    #[test]
+    fn repeated_coordinates_repeat_the_command() {
+        // a repeated M is an L
+        assert_eq!(
+            parse("M0 0 1 0 1,1z"),
+            vec![
+                PathCommand::Move((0.0, 0.0)),
+                PathCommand::Line((1.0, 0.0)),
+                PathCommand::Line((1.0, 1.0)),
+                PathCommand::Close,
+            ]
+        );
+        assert_eq!(
+            parse("M0 0 L.5.5 1 0"),
+            vec![
+                PathCommand::Move((0.0, 0.0)),
+                PathCommand::Line((0.5, 0.5)),
+                PathCommand::Line((1.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn relative_and_axis_commands() {
+        assert_eq!(
+            parse("m.25 .25 h.5 v.5 H0 V0 l.25-.25 z m.5 0"),
+            vec![
+                PathCommand::Move((0.25, 0.25)),
+                PathCommand::Line((0.75, 0.25)),
+                PathCommand::Line((0.75, 0.75)),
+                PathCommand::Line((0.0, 0.75)),
+                PathCommand::Line((0.0, 0.0)),
+                PathCommand::Line((0.25, -0.25)),
+                PathCommand::Close,
+                // after z, the current point is the subpath's start
+                PathCommand::Move((0.75, 0.25)),
+            ]
+        );
+    }
+
+    #[test]
+    fn smooth_curves_reflect_the_previous_control_point() {
+        assert_eq!(
+            parse("M0 0 C0 .5 .5 .5 .5 0 S1 -.5 1 0"),
+            vec![
+                PathCommand::Move((0.0, 0.0)),
+                PathCommand::Cubic((0.0, 0.5), (0.5, 0.5), (0.5, 0.0)),
+                PathCommand::Cubic((0.5, -0.5), (1.0, -0.5), (1.0, 0.0)),
+            ]
+        );
+        assert_eq!(
+            parse("M0 0 Q.25 .5 .5 0 T1 0"),
+            vec![
+                PathCommand::Move((0.0, 0.0)),
+                PathCommand::Quad((0.25, 0.5), (0.5, 0.0)),
+                PathCommand::Quad((0.75, -0.5), (1.0, 0.0)),
+            ]
+        );
+        // without a previous curve, the control point is the current point
+        assert_eq!(
+            parse("M0 0 L.5 0 T1 0"),
+            vec![
+                PathCommand::Move((0.0, 0.0)),
+                PathCommand::Line((0.5, 0.0)),
+                PathCommand::Quad((0.5, 0.0), (1.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn arc_flags_need_no_separators() {
+        let expected = PathCommand::Arc {
+            radii: (0.5, 0.5),
+            rotation: 0.0,
+            large_arc: true,
+            sweep: false,
+            to: (1.0, 0.5),
+        };
+        assert_eq!(parse("M0 .5 A.5 .5 0 1 0 1 .5")[1], expected);
+        assert_eq!(parse("M0 .5 A.5.5 0 10 1 .5")[1], expected);
+        assert_eq!(parse("M0 .5 a.5 .5 0 1,0 1,0")[1], expected);
+    }
+
+    #[test]
+    fn invalid_paths() {
+        assert!(ShapePath::parse("L1 1").is_err());
+        assert!(ShapePath::parse("M0 0 X1 1").is_err());
+        assert!(ShapePath::parse("M0 0 L1").is_err());
+        assert!(ShapePath::parse("M0 0 z 1 1").is_err());
+        assert!(ShapePath::parse("M0 0 A1 1 0 2 0 1 1").is_err());
+        assert!(ShapePath::parse("")
+            .map(|p| p.commands.is_empty())
+            .unwrap_or(false));
+    }
+
+    #[test]
+    fn arcs_end_at_the_target_and_stay_on_the_circle() {
+        for sweep in [false, true] {
+            let points = arc((0.0, 0.5), (0.5, 0.5), false, sweep, (1.0, 0.5));
+            assert!(points.len() > 2);
+            assert!(close(*points.last().unwrap(), (1.0, 0.5)));
+            for p in &points {
+                assert!((distance(*p, (0.5, 0.5)) - 0.5).abs() < 1e-4);
+            }
+            // the sweep flag chooses the half which is passed through
+            let middle = if sweep { (0.5, 0.0) } else { (0.5, 1.0) };
+            assert!(passes(&points, middle));
+        }
+    }
+
+    #[test]
+    fn arc_radii_are_scaled_up_or_ignored() {
+        // too small radii are scaled up to a semicircle
+        let points = arc((0.0, 0.5), (0.1, 0.1), false, true, (1.0, 0.5));
+        assert!(passes(&points, (0.5, 0.0)));
+        // a zero radius is a straight line
+        assert_eq!(
+            arc((0.0, 0.0), (0.0, 0.5), false, true, (1.0, 1.0)),
+            vec![(1.0, 1.0)]
+        );
+        // the large arc flag picks the longer way around a quarter circle
+        let small = arc((0.5, 0.0), (0.5, 0.5), false, true, (1.0, 0.5));
+        let large = arc((0.5, 0.0), (0.5, 0.5), true, false, (1.0, 0.5));
+        assert!(small
+            .iter()
+            .all(|p| (distance(*p, (0.5, 0.5)) - 0.5).abs() < 1e-4));
+        assert!(large
+            .iter()
+            .all(|p| (distance(*p, (0.5, 0.5)) - 0.5).abs() < 1e-4));
+        assert!(passes(&large, (0.0, 0.5)));
+        assert!(!passes(&small, (0.0, 0.5)));
+    }
+}
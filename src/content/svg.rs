@@ -0,0 +1,156 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
+
+use image::{DynamicImage, RgbaImage};
+use resvg::{tiny_skia, usvg};
+
+use super::content::{Content, GenericContentData};
+
+/// A vector image. Unlike Image, it is rendered at the exact size it is displayed at, so it stays sharp at any size.
+pub struct Svg {
+    path: PathBuf,
+    failed_to_load: bool,
+    tree: Option<usvg::Tree>,
+    /// The last rendered image. It is reused as long as the output size doesn't change.
+    rendered: Option<((u32, u32), DynamicImage)>,
+    generic_content_data: GenericContentData,
+    pub as_content_changes: SvgChanges,
+}
+#[derive(Default)]
+pub struct SvgChanges {
+    pub path: Option<PathBuf>,
+}
+
+impl Content for Svg {
+    fn clone_no_caching(&self) -> Self {
+        Self::new(self.path.clone(), self.generic_content_data.reset())
+    }
+
+    fn children(&self) -> Vec<&Self> {
+        Vec::new()
+    }
+    fn children_mut(&mut self) -> Vec<&mut Self> {
+        Vec::new()
+    }
+
+    fn has_changes(&self) -> bool {
+        self.as_content_changes.path.is_some()
+    }
+    fn apply_changes(&mut self) -> bool {
+        if let Some(path) = self.as_content_changes.path.take() {
+            self.set_path(path);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn generic_content_data(&mut self) -> &mut GenericContentData {
+        &mut self.generic_content_data
+    }
+}
+
+/// Fonts for text in svg files: the system fonts and all directories known to the font manager. Only loaded once.
+fn fontdb() -> Arc<usvg::fontdb::Database> {
+    static FONTDB: OnceLock<Arc<usvg::fontdb::Database>> = OnceLock::new();
+    FONTDB
+        .get_or_init(|| {
+            let mut db = usvg::fontdb::Database::new();
+            db.load_system_fonts();
+            for dir in crate::font_manager::get().directories() {
+                db.load_fonts_dir(dir);
+            }
+            Arc::new(db)
+        })
+        .clone()
+}
+
+impl Svg {
+    pub fn new(path: PathBuf, generic_content_data: GenericContentData) -> Self {
+        Self {
+            path,
+            failed_to_load: false,
+            tree: None,
+            rendered: None,
+            generic_content_data,
+            as_content_changes: SvgChanges::default(),
+        }
+    }
+    pub fn set_path(&mut self, new: PathBuf) {
+        self.path = new;
+        self.failed_to_load = false;
+        self.tree = None;
+        self.rendered = None;
+    }
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+
+    fn load_if_necessary(&mut self) {
+        if self.tree.is_some() || self.failed_to_load {
+            return;
+        }
+        let data = match std::fs::read(&self.path) {
+            Ok(v) => v,
+            Err(err) => {
+                eprintln!("Could not open file at '{}': {}", self.path.display(), err);
+                self.failed_to_load = true;
+                return;
+            }
+        };
+        let options = usvg::Options {
+            // relative paths (of images in the svg) are relative to the svg file
+            resources_dir: self.path.parent().map(|p| p.to_path_buf()),
+            fontdb: fontdb(),
+            ..Default::default()
+        };
+        match usvg::Tree::from_data(&data, &options) {
+            Ok(tree) => self.tree = Some(tree),
+            Err(err) => {
+                eprintln!("Could not load svg '{}': {}", self.path.display(), err);
+                self.failed_to_load = true;
+            }
+        }
+    }
+
+    /// The svg, stretched to width x height pixels.
+    pub fn get_img(&mut self, width: u32, height: u32) -> Option<&DynamicImage> {
+        if !matches!(&self.rendered, Some((size, _)) if *size == (width, height)) {
+            self.load_if_necessary();
+            let tree = self.tree.as_ref()?;
+            let mut pixmap = tiny_skia::Pixmap::new(width, height)?;
+            let size = tree.size();
+            resvg::render(
+                tree,
+                tiny_skia::Transform::from_scale(
+                    width as f32 / size.width(),
+                    height as f32 / size.height(),
+                ),
+                &mut pixmap.as_mut(),
+            );
+            // tiny_skia uses premultiplied alpha
+            let data = pixmap
+                .pixels()
+                .iter()
+                .flat_map(|px| {
+                    let px = px.demultiply();
+                    [px.red(), px.green(), px.blue(), px.alpha()]
+                })
+                .collect();
+            self.rendered = Some((
+                (width, height),
+                DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, data)?),
+            ));
+        }
+        self.rendered.as_ref().map(|(_, img)| img)
+    }
+
+    pub fn draw(&mut self, image: &mut DynamicImage, prep_draw: &crate::video::PrepDrawData) {
+        let (width, height) = (prep_draw.pos_px.2 as u32, prep_draw.pos_px.3 as u32);
+        if let Some(img) = self.get_img(width, height) {
+            crate::video::composite_images(image, img, prep_draw);
+        }
+    }
+}
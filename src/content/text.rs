@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     default::Default,
     io::{self, Read},
     path::PathBuf,
@@ -14,6 +15,15 @@ pub struct Text {
     text: TextType,
     font: Option<rusttype::Font<'static>>,
     color: crate::types::Color,
+    layout: TextLayout,
+    style: TextStyle,
+    animator: Option<TextAnimator>,
+    /// If true, the text is parsed as markup (see text_markup.rs).
+    markup: bool,
+    /// The fonts of markup spans (<font=...>) by name, so they are only looked up once. None if the font wasn't found.
+    span_fonts: HashMap<String, Option<rusttype::Font<'static>>>,
+    /// Fonts for characters that are missing in the other fonts. None until a character is missing.
+    fallbacks: Option<Vec<rusttype::Font<'static>>>,
     generic_content_data: crate::content::content::GenericContentData,
     pub as_content_changes: TextChanges,
 }
@@ -23,6 +33,194 @@ pub struct TextChanges {
     pub text: Option<TextType>,
     pub font: Option<rusttype::Font<'static>>,
     pub color: Option<crate::types::Color>,
+    pub layout: Option<TextLayout>,
+    pub style: Option<TextStyle>,
+    pub markup: Option<bool>,
+    pub animator: Option<Option<TextAnimator>>,
+}
+
+/// Reveals the text piece by piece (characters, words or lines) and animates each piece while it appears.
+#[derive(Clone)]
+pub struct TextAnimator {
+    pub unit: TextAnimationUnit,
+    /// How many units are revealed. A unit starts to appear when this reaches its index (the first one is 0) and is fully revealed when this reaches its index + 1. Units which haven't started to appear are hidden.
+    pub reveal: crate::curve::Curve,
+    /// The following curves are evaluated for every glyph, but not at the video's progress: they get how far the glyph's unit has been revealed (0 = just starting to appear, 1 = fully revealed). Offsets are relative to the font size.
+    pub offset_x: crate::curve::Curve,
+    pub offset_y: crate::curve::Curve,
+    pub scale: crate::curve::Curve,
+    pub opacity: crate::curve::Curve,
+}
+#[derive(Clone, Copy)]
+pub enum TextAnimationUnit {
+    Characters,
+    Words,
+    Lines,
+}
+impl TextAnimator {
+    /// One transform for every glyph in the layout.
+    pub fn glyph_transforms(
+        &self,
+        layout: &super::text_layout::Layout,
+        progress: f64,
+    ) -> Vec<super::text_layout::GlyphTransform> {
+        let reveal = self.reveal.get_value(progress);
+        let size = layout.size as f64;
+        let mut unit_index = 0;
+        let mut started = false;
+        let mut prev: Option<&super::text_layout::PlacedGlyph> = None;
+        layout
+            .glyphs
+            .iter()
+            .map(|glyph| {
+                let new_line = prev.map_or(true, |p| p.line != glyph.line);
+                let starts_unit = match self.unit {
+                    TextAnimationUnit::Characters => glyph.ch != ' ',
+                    TextAnimationUnit::Words => {
+                        glyph.ch != ' ' && (new_line || prev.map_or(true, |p| p.ch == ' '))
+                    }
+                    TextAnimationUnit::Lines => new_line,
+                };
+                if starts_unit {
+                    if started {
+                        unit_index += 1;
+                    }
+                    started = true;
+                }
+                prev = Some(glyph);
+                let local = reveal - unit_index as f64;
+                if local <= 0.0 {
+                    return super::text_layout::GlyphTransform {
+                        opacity: 0.0,
+                        ..Default::default()
+                    };
+                }
+                let local = local.min(1.0);
+                super::text_layout::GlyphTransform {
+                    offset: (
+                        (self.offset_x.get_value(local) * size) as f32,
+                        (self.offset_y.get_value(local) * size) as f32,
+                    ),
+                    scale: self.scale.get_value(local).max(0.0) as f32,
+                    opacity: self.opacity.get_value(local).clamp(0.0, 1.0) as f32,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Everything that is drawn around the text itself. All sizes are relative to the font size.
+#[derive(Clone, Default)]
+pub struct TextStyle {
+    pub outline: Option<TextOutline>,
+    pub shadow: Option<TextShadow>,
+    pub background: Option<TextBackground>,
+}
+#[derive(Clone)]
+pub struct TextOutline {
+    pub width: crate::curve::Curve,
+    pub color: crate::types::Color,
+}
+/// The shadow has the shape of the text (including the outline, if there is one).
+#[derive(Clone)]
+pub struct TextShadow {
+    pub offset_x: crate::curve::Curve,
+    pub offset_y: crate::curve::Curve,
+    pub blur: crate::curve::Curve,
+    pub color: crate::types::Color,
+}
+/// A rectangle behind the text. It covers the area taken up by the text plus the padding on every side.
+#[derive(Clone)]
+pub struct TextBackground {
+    pub padding: crate::curve::Curve,
+    pub corner_radius: crate::curve::Curve,
+    pub color: crate::types::Color,
+}
+
+/// How the text is arranged inside the video's box.
+#[derive(Clone)]
+pub struct TextLayout {
+    /// None: use the video's position alignment (left for TopLeft, center for Top, ...).
+    pub align: Option<TextAlign>,
+    /// None: use the video's position alignment (top for TopLeft, center for Left, ...).
+    pub vertical_align: Option<TextVerticalAlign>,
+    /// Whether lines that are too long should be broken between words. Off by default, so text without a layout is drawn as one line that is scaled down to fit into the box (like before layouts existed).
+    pub wrap: bool,
+    /// Distance between two lines, relative to the font size (the height of one line, so 1.0 means no gap).
+    pub line_height: crate::curve::Curve,
+    /// Extra space after each character, relative to the font size.
+    pub letter_spacing: crate::curve::Curve,
+    pub fit: TextFit,
+}
+#[derive(Clone, Copy)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+    Justify,
+}
+#[derive(Clone, Copy)]
+pub enum TextVerticalAlign {
+    Top,
+    Center,
+    Bottom,
+}
+#[derive(Clone)]
+pub enum TextFit {
+    /// Use the largest font size at which the text fits into the box (at most the box's height).
+    Shrink,
+    /// The font size (relative to the box's height) is given by the curve. Text outside of the box is cut off.
+    Fixed(crate::curve::Curve),
+    /// Like Fixed, but text outside of the box is still drawn.
+    Overflow(crate::curve::Curve),
+}
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self {
+            align: None,
+            vertical_align: None,
+            wrap: false,
+            line_height: crate::curve::CurveData::Constant(1.0).into(),
+            letter_spacing: crate::curve::CurveData::Constant(0.0).into(),
+            fit: TextFit::Shrink,
+        }
+    }
+}
+impl TextLayout {
+    /// Evaluates all curves. align_anchor is the video's position alignment, used if align or vertical_align is None.
+    pub fn get_settings(
+        &self,
+        progress: f64,
+        box_height: f64,
+        align_anchor: (f64, f64),
+    ) -> super::text_layout::LayoutSettings {
+        use super::text_layout::{LayoutAlign, LayoutFit};
+        super::text_layout::LayoutSettings {
+            align: match self.align {
+                None => LayoutAlign::Anchor(align_anchor.0 as f32),
+                Some(TextAlign::Left) => LayoutAlign::Anchor(0.0),
+                Some(TextAlign::Center) => LayoutAlign::Anchor(0.5),
+                Some(TextAlign::Right) => LayoutAlign::Anchor(1.0),
+                Some(TextAlign::Justify) => LayoutAlign::Justify,
+            },
+            vertical_align: match self.vertical_align {
+                None => align_anchor.1 as f32,
+                Some(TextVerticalAlign::Top) => 0.0,
+                Some(TextVerticalAlign::Center) => 0.5,
+                Some(TextVerticalAlign::Bottom) => 1.0,
+            },
+            wrap: self.wrap,
+            line_height: self.line_height.get_value(progress) as f32,
+            letter_spacing: self.letter_spacing.get_value(progress) as f32,
+            fit: match &self.fit {
+                TextFit::Shrink => LayoutFit::Shrink,
+                TextFit::Fixed(size) => LayoutFit::Fixed((size.get_value(progress) * box_height) as f32),
+                TextFit::Overflow(size) => {
+                    LayoutFit::Overflow((size.get_value(progress) * box_height) as f32)
+                }
+            },
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -33,7 +231,12 @@ pub enum TextType {
 
 impl Content for Text {
     fn clone_no_caching(&self) -> Self {
-        Self::new(self.text.clone(), self.generic_content_data.reset())
+        let mut new = Self::new(self.text.clone(), self.generic_content_data.reset());
+        new.layout = self.layout.clone();
+        new.style = self.style.clone();
+        new.markup = self.markup;
+        new.animator = self.animator.clone();
+        new
     }
 
     fn children(&self) -> Vec<&Self> {
@@ -47,6 +250,10 @@ impl Content for Text {
         self.as_content_changes.text.is_some()
             | self.as_content_changes.font.is_some()
             | self.as_content_changes.color.is_some()
+            | self.as_content_changes.layout.is_some()
+            | self.as_content_changes.style.is_some()
+            | self.as_content_changes.markup.is_some()
+            | self.as_content_changes.animator.is_some()
     }
     fn apply_changes(&mut self) -> bool {
         let mut o = false;
@@ -62,6 +269,22 @@ impl Content for Text {
             self.color = color;
             o = true;
         }
+        if let Some(layout) = self.as_content_changes.layout.take() {
+            self.layout = layout;
+            o = true;
+        }
+        if let Some(style) = self.as_content_changes.style.take() {
+            self.style = style;
+            o = true;
+        }
+        if let Some(markup) = self.as_content_changes.markup.take() {
+            self.markup = markup;
+            o = true;
+        }
+        if let Some(animator) = self.as_content_changes.animator.take() {
+            self.animator = animator;
+            o = true;
+        }
         o
     }
 
@@ -80,6 +303,12 @@ impl Text {
                 crate::curve::CurveData::Constant(1.0).into(),
                 crate::curve::CurveData::Constant(1.0).into(),
             ),
+            layout: TextLayout::default(),
+            style: TextStyle::default(),
+            animator: None,
+            markup: false,
+            span_fonts: HashMap::new(),
+            fallbacks: None,
             as_content_changes: TextChanges::default(),
             generic_content_data,
         }
@@ -95,6 +324,31 @@ impl Text {
     pub fn set_color(&mut self, new: crate::types::Color) {
         self.color = new;
     }
+    pub fn set_layout(&mut self, new: TextLayout) {
+        self.layout = new;
+    }
+    pub fn layout(&self) -> &TextLayout {
+        &self.layout
+    }
+    pub fn set_style(&mut self, new: TextStyle) {
+        self.style = new;
+    }
+    pub fn style(&self) -> &TextStyle {
+        &self.style
+    }
+    /// If enabled, the text (also the output of TextType::Program) can contain tags like <b> or <color=1,0,0> (see text_markup.rs).
+    pub fn set_markup(&mut self, new: bool) {
+        self.markup = new;
+    }
+    pub fn markup(&self) -> bool {
+        self.markup
+    }
+    pub fn set_animator(&mut self, new: Option<TextAnimator>) {
+        self.animator = new;
+    }
+    pub fn animator(&self) -> Option<&TextAnimator> {
+        self.animator.as_ref()
+    }
     pub fn text(&self) -> &TextType {
         &self.text
     }
@@ -118,125 +372,255 @@ impl Text {
     ) {
         let position = prep_draw.pos_px;
         let text = self.get_text(prep_draw.progress);
+        let spans = if self.markup {
+            super::text_markup::parse(&text)
+        } else {
+            vec![super::text_markup::Span {
+                text,
+                style: Default::default(),
+            }]
+        };
+        self.resolve_span_fonts(&spans);
         if let Some(font) = &self.font {
-            let c = self.color.get_rgba(prep_draw.progress); // TODO: replace image.height()/width() with position.2/3!
-            let dimensions = imageproc::drawing::text_size(
-                rusttype::Scale::uniform(position.3 as f32),
-                font,
-                &text,
+            let span_fonts: Vec<_> = spans
+                .iter()
+                .map(|span| {
+                    span.style
+                        .font
+                        .as_ref()
+                        .and_then(|name| self.span_fonts.get(name).cloned().flatten())
+                        .unwrap_or_else(|| font.clone())
+                })
+                .collect();
+            // only look for fallback fonts if they are actually needed
+            if self.fallbacks.is_none()
+                && spans.iter().zip(span_fonts.iter()).any(|(span, font)| {
+                    span.text
+                        .chars()
+                        .any(|ch| !ch.is_whitespace() && font.glyph(ch).id().0 == 0)
+                })
+            {
+                self.fallbacks = Some(crate::font_manager::get().fallbacks());
+            }
+            let no_fallbacks = Vec::new();
+            let fallbacks = self.fallbacks.as_ref().unwrap_or(&no_fallbacks);
+            let c = self.color.get_rgba(prep_draw.progress);
+            let settings = self
+                .layout
+                .get_settings(prep_draw.progress, position.3, align_anchor);
+            let layout_spans: Vec<_> = spans
+                .iter()
+                .zip(span_fonts.iter())
+                .map(|(span, font)| super::text_layout::LayoutSpan {
+                    text: &span.text,
+                    font,
+                    fallbacks,
+                    scale: span.style.scale(),
+                })
+                .collect();
+            let layout = super::text_layout::layout(
+                &layout_spans,
+                (position.2 as f32, position.3 as f32),
+                &settings,
             );
-            let (factor, offset_x, offset_y) = if dimensions.0 as f64 > position.2 {
-                let f = position.2 / dimensions.0 as f64;
-                (
-                    f,
-                    0,
-                    ((position.3 - dimensions.1 as f64 * f) * align_anchor.1).round() as _,
+            let progress = prep_draw.progress;
+            let size = layout.size as f64;
+            let origin = (position.0 as f32, position.1 as f32);
+            let mut layer = image::RgbaImage::new(image.width(), image.height());
+            if let Some(background) = &self.style.background {
+                let padding = (background.padding.get_value(progress) * size) as f32;
+                let (x, y, w, h) = layout.bounds;
+                super::text_layout::rounded_rect_mask(
+                    (
+                        origin.0 + x - padding,
+                        origin.1 + y - padding,
+                        w + 2.0 * padding,
+                        h + 2.0 * padding,
+                    ),
+                    (background.corner_radius.get_value(progress) * size) as f32,
                 )
+                .paint(&mut layer, background.color.get_rgba(progress), (0, 0));
+            }
+            let outline_width = match &self.style.outline {
+                Some(outline) => (outline.width.get_value(progress) * size).max(0.0) as f32,
+                None => 0.0,
+            };
+            let shadow_blur = match &self.style.shadow {
+                Some(shadow) => (shadow.blur.get_value(progress) * size).max(0.0) as f32,
+                None => 0.0,
+            };
+            let transforms = match &self.animator {
+                Some(animator) => animator.glyph_transforms(&layout, progress),
+                None => Vec::new(),
+            };
+            let max_scale = layout_spans.iter().map(|s| s.scale).fold(1.0f32, f32::max);
+            // animated glyphs may be moved or scaled out of the text's bounds
+            let animation_margin = transforms
+                .iter()
+                .filter(|t| t.opacity > 0.0)
+                .map(|t| {
+                    t.offset.0.abs().max(t.offset.1.abs())
+                        + (t.scale - 1.0).max(0.0) * layout.size * max_scale
+                })
+                .fold(0.0f32, f32::max);
+            let fill_spans = super::text_layout::glyph_masks(
+                &layout_spans,
+                &layout,
+                &transforms,
+                origin,
+                if layout.clip {
+                    Some((
+                        position.0.round() as i64,
+                        position.1.round() as i64,
+                        position.2.round() as i64,
+                        position.3.round() as i64,
+                    ))
+                } else {
+                    None
+                },
+                (outline_width
+                    + shadow_blur
+                    + animation_margin
+                    + layout.size * max_scale * Self::BOLD_WIDTH)
+                    .ceil()
+                    as i64
+                    + 1,
+            );
+            // faux bold: the glyphs are made a bit wider
+            let fill_spans: Vec<_> = fill_spans
+                .into_iter()
+                .zip(spans.iter())
+                .map(|(mask, span)| {
+                    if span.style.bold {
+                        mask.dilate(layout.size * span.style.scale() * Self::BOLD_WIDTH)
+                    } else {
+                        mask
+                    }
+                })
+                .collect();
+            // there is always at least one span
+            let mut fill = fill_spans[0].clone();
+            for mask in fill_spans[1..].iter() {
+                fill.union(mask);
+            }
+            let outlined = if outline_width > 0.0 {
+                Some(fill.dilate(outline_width))
             } else {
-                (
-                    1.0,
-                    ((position.2 - dimensions.0 as f64/* free space */) * align_anchor.0).round()
-                        as _,
-                    0,
-                )
+                None
             };
-            let height = (position.3 * factor) as _;
-            match &prep_draw.compositing {
-                crate::video::CompositingMethod::Ignore => (),
-                crate::video::CompositingMethod::Opaque => {
-                    imageproc::drawing::draw_text_mut(
-                        image,
-                        image::Rgba {
-                            0: [
-                                (255.0 * c.0).round() as u8,
-                                (255.0 * c.1).round() as u8,
-                                (255.0 * c.2).round() as u8,
-                                255,
-                            ],
-                        },
-                        position.0.round() as i32 + offset_x,
-                        position.1.round() as i32 + offset_y,
-                        rusttype::Scale::uniform(height),
-                        font,
-                        &text,
-                    );
+            if let Some(shadow) = &self.style.shadow {
+                let offset = (
+                    (shadow.offset_x.get_value(progress) * size).round() as i64,
+                    (shadow.offset_y.get_value(progress) * size).round() as i64,
+                );
+                outlined
+                    .as_ref()
+                    .unwrap_or(&fill)
+                    .blur(shadow_blur)
+                    .paint(&mut layer, shadow.color.get_rgba(progress), offset);
+            }
+            if let (Some(outlined), Some(outline)) = (&outlined, &self.style.outline) {
+                outlined.paint(&mut layer, outline.color.get_rgba(progress), (0, 0));
+            }
+            for (mask, span) in fill_spans.iter().zip(spans.iter()) {
+                mask.paint(&mut layer, span.style.color.unwrap_or(c), (0, 0));
+            }
+            if !prep_draw.transform.is_identity() {
+                let (width, height) = layer.dimensions();
+                let transformed = crate::transform::resample(
+                    &layer,
+                    &prep_draw.transform,
+                    width,
+                    height,
+                    prep_draw
+                        .sub_pixel
+                        .unwrap_or(crate::transform::ResampleFilter::Bilinear),
+                );
+                layer = image::RgbaImage::new(width, height);
+                if let Some((transformed, x, y)) = transformed {
+                    image::imageops::replace(&mut layer, &transformed, x as i64, y as i64);
                 }
-                crate::video::CompositingMethod::Direct => {
-                    imageproc::drawing::draw_text_mut(
-                        image,
-                        image::Rgba {
-                            0: [
-                                (255.0 * c.0).round() as u8,
-                                (255.0 * c.1).round() as u8,
-                                (255.0 * c.2).round() as u8,
-                                (255.0 * c.3).round() as u8,
-                            ],
-                        },
-                        position.0.round() as i32 + offset_x,
-                        position.1.round() as i32 + offset_y,
-                        rusttype::Scale::uniform(height),
-                        font,
-                        &text,
+            }
+            Self::composite_layer(
+                image,
+                &layer,
+                &prep_draw.compositing,
+                prep_draw.opacity,
+                prep_draw.linear_light,
+            );
+        } else {
+            println!("Cannot draw text: No font specified.");
+        }
+    }
+
+    /// Looks up the fonts of spans whose font hasn't been looked up before. Fonts that aren't found are reported once.
+    fn resolve_span_fonts(&mut self, spans: &[super::text_markup::Span]) {
+        for name in spans.iter().filter_map(|span| span.style.font.as_ref()) {
+            if !self.span_fonts.contains_key(name) {
+                let font = crate::font_manager::get().get_font(name, 0);
+                if font.is_none() {
+                    self.generic_content_data.project.diagnostics.report(
+                        name.clone(),
+                        "Font not found, the text's font is used instead.".to_string(),
                     );
                 }
-                crate::video::CompositingMethod::TransparencySupport => {
-                    let max_width = image.width() as i32 - position.0.ceil() as i32;
-                    if max_width > 0 {
-                        let max_height = image.height() as i32 - position.1.ceil() as i32;
-                        if max_height > 0 {
-                            let mut img = DynamicImage::new_rgba8(
-                                (image.width() as f64 * factor).ceil() as _,
-                                (image.height() as f64 * factor).ceil() as _,
-                            );
-                            imageproc::drawing::draw_text_mut(
-                                &mut img,
-                                image::Rgba {
-                                    0: [
-                                        (255.0 * c.0).round() as u8,
-                                        (255.0 * c.1).round() as u8,
-                                        (255.0 * c.2).round() as u8,
-                                        (255.0 * c.3).round() as u8,
-                                    ],
-                                },
-                                offset_x,
-                                offset_y,
-                                rusttype::Scale::uniform(height),
-                                font,
-                                &text,
-                            );
-                            let img = img.as_rgba8().unwrap();
-                            let image = image.as_mut_rgba8().unwrap();
-                            let width = (img.width() as i32).min(max_width);
-                            let height = (img.height() as i32).min(max_height);
-                            let x0 = position.0 as i32;
-                            let y0 = position.1 as i32;
-                            let x1 = if position.0 < 0.0 {
-                                (-position.0) as _
-                            } else {
-                                0
-                            };
-                            let y1 = if position.1 < 0.0 {
-                                (-position.1) as _
-                            } else {
-                                0
-                            };
-                            for y in y1..height {
-                                for x in x1..width {
-                                    crate::video::composite_pixels_transparency_support(
-                                        &mut image.get_pixel_mut((x0 + x) as _, (y0 + y) as _).0,
-                                        &img.get_pixel(x as _, y as _).0,
-                                    );
-                                }
-                            }
+                self.span_fonts.insert(name.clone(), font);
+            }
+        }
+    }
+
+    /// How much wider bold glyphs are on each side, relative to the font size.
+    const BOLD_WIDTH: f32 = 0.025;
+
+    /// Draws a layer that has the same size as the image onto the image. Unlike composite_images, fully transparent pixels are always skipped, so that only the text itself is drawn, even when the compositing method is Opaque.
+    fn composite_layer(
+        image: &mut DynamicImage,
+        layer: &image::RgbaImage,
+        compositing: &crate::video::CompositingMethod,
+        opacity: f64,
+        linear_light: bool,
+    ) {
+        let image = image.as_mut_rgba8().unwrap();
+        match compositing {
+            crate::video::CompositingMethod::Ignore => (),
+            crate::video::CompositingMethod::Opaque => {
+                for (old, new) in image.pixels_mut().zip(layer.pixels()) {
+                    if new.0[3] != 0 {
+                        // antialiased edges are mixed with what was there before
+                        let factor = crate::video::apply_opacity(new.0[3], opacity) as u16;
+                        for i in 0..3 {
+                            old.0[i] = ((old.0[i] as u16 * (255 - factor) + new.0[i] as u16 * factor) / 255) as u8;
                         }
+                        old.0[3] = 255;
                     }
                 }
-                crate::video::CompositingMethod::Manual(_) => {
-                    todo!("custom compositing not yet available for text.")
+            }
+            crate::video::CompositingMethod::Direct => {
+                for (old, new) in image.pixels_mut().zip(layer.pixels()) {
+                    if new.0[3] != 0 {
+                        *old = *new;
+                        old.0[3] = crate::video::apply_opacity(new.0[3], opacity);
+                    }
                 }
             }
-        } else {
-            println!("Cannot draw text: No font specified.");
+            crate::video::CompositingMethod::TransparencySupport
+            | crate::video::CompositingMethod::Blend(_) => {
+                for (old, new) in image.pixels_mut().zip(layer.pixels()) {
+                    if new.0[3] != 0 {
+                        let mut new = *new;
+                        new.0[3] = crate::video::apply_opacity(new.0[3], opacity);
+                        crate::video::composite_pixels(
+                            &mut old.0,
+                            &new.0,
+                            compositing,
+                            linear_light,
+                        );
+                    }
+                }
+            }
+            crate::video::CompositingMethod::Manual(_) => {
+                todo!("custom compositing not yet available for text.")
+            }
         }
     }
 }
@@ -0,0 +1,561 @@
+//! Paragraph layout for Text: line breaks, word wrapping, alignment and fitting the text into its box.
+
+use rusttype::{point, Font, Scale};
+
+/// TextLayout (see text.rs), but with all curves evaluated and alignment resolved.
+pub struct LayoutSettings {
+    pub align: LayoutAlign,
+    /// 0.0 = top, 0.5 = center, 1.0 = bottom
+    pub vertical_align: f32,
+    pub wrap: bool,
+    /// Distance between two baselines, relative to the font size.
+    pub line_height: f32,
+    /// Extra space after every character, relative to the font size.
+    pub letter_spacing: f32,
+    pub fit: LayoutFit,
+}
+pub enum LayoutAlign {
+    /// 0.0 = left, 0.5 = center, 1.0 = right
+    Anchor(f32),
+    /// Stretches the spaces so that every line except the last one of a paragraph fills the entire width.
+    Justify,
+}
+pub enum LayoutFit {
+    /// The largest font size (but at most the box's height) at which the text fits into the box.
+    Shrink,
+    /// A fixed font size in pixels. Text that doesn't fit into the box is cut off.
+    Fixed(f32),
+    /// A fixed font size in pixels. Text that doesn't fit into the box is drawn outside of it.
+    Overflow(f32),
+}
+
+/// A part of the text that uses one font and font size.
+pub struct LayoutSpan<'a> {
+    pub text: &'a str,
+    pub font: &'a Font<'static>,
+    /// Used for characters that font doesn't have.
+    pub fallbacks: &'a [Font<'static>],
+    /// The font size, relative to the layout's size (1.0 for normal text).
+    pub scale: f32,
+}
+
+impl<'a> LayoutSpan<'a> {
+    /// The span's font, or the first fallback font that has the character.
+    pub fn font_for(&self, ch: char) -> &'a Font<'static> {
+        if ch.is_whitespace() || self.font.glyph(ch).id().0 != 0 {
+            return self.font;
+        }
+        self.fallbacks
+            .iter()
+            .find(|f| f.glyph(ch).id().0 != 0)
+            .unwrap_or(self.font)
+    }
+}
+
+pub struct PlacedGlyph {
+    pub ch: char,
+    /// Relative to the top left corner of the box.
+    pub x: f32,
+    /// The baseline, relative to the top left corner of the box.
+    pub y: f32,
+    /// The index of the span this glyph is part of.
+    pub span: usize,
+    /// The index of the line this glyph is in, starting at 0.
+    pub line: usize,
+}
+
+/// Changes how a single glyph is drawn, without changing the layout.
+#[derive(Clone, Copy)]
+pub struct GlyphTransform {
+    /// In pixels
+    pub offset: (f32, f32),
+    /// Scales the glyph around its center.
+    pub scale: f32,
+    /// 0.0 hides the glyph.
+    pub opacity: f32,
+}
+impl Default for GlyphTransform {
+    fn default() -> Self {
+        Self {
+            offset: (0.0, 0.0),
+            scale: 1.0,
+            opacity: 1.0,
+        }
+    }
+}
+pub struct Layout {
+    pub glyphs: Vec<PlacedGlyph>,
+    /// The font size in pixels (of a span with scale 1.0).
+    pub size: f32,
+    /// If true, nothing outside of the box should be drawn.
+    pub clip: bool,
+    /// The area (x, y, w, h) covered by the text, relative to the top left corner of the box.
+    pub bounds: (f32, f32, f32, f32),
+}
+
+/// One line of text, before alignment. x positions start at 0.
+struct Line {
+    glyphs: Vec<PlacedGlyph>,
+    width: f32,
+    /// The last line of a paragraph is never justified.
+    ends_paragraph: bool,
+    /// The largest font size (in pixels) in this line.
+    size: f32,
+    ascent: f32,
+    /// Negative
+    descent: f32,
+}
+impl Line {
+    fn new() -> Self {
+        Self {
+            glyphs: Vec::new(),
+            width: 0.0,
+            ends_paragraph: false,
+            size: 0.0,
+            ascent: 0.0,
+            descent: 0.0,
+        }
+    }
+    /// Calculates size, ascent and descent from the glyphs. Empty lines use the first span's font at the normal size.
+    fn finish(mut self, spans: &[LayoutSpan], size: f32) -> Self {
+        let mut metrics = self.glyphs.iter().map(|g| {
+            let span = &spans[g.span];
+            (span.font, size * span.scale)
+        });
+        let first = metrics.next().or_else(|| spans.first().map(|s| (s.font, size)));
+        for (font, glyph_size) in first.into_iter().chain(metrics) {
+            let v_metrics = font.v_metrics(Scale::uniform(glyph_size));
+            self.size = self.size.max(glyph_size);
+            self.ascent = self.ascent.max(v_metrics.ascent);
+            self.descent = self.descent.min(v_metrics.descent);
+        }
+        self
+    }
+}
+
+pub fn layout(spans: &[LayoutSpan], box_size: (f32, f32), settings: &LayoutSettings) -> Layout {
+    let (size, clip) = match settings.fit {
+        LayoutFit::Fixed(size) => (size, true),
+        LayoutFit::Overflow(size) => (size, false),
+        LayoutFit::Shrink => {
+            // binary search for the largest size that fits
+            let (mut low, mut high) = (0.0f32, box_size.1.max(0.0));
+            if fits(spans, box_size, settings, high) {
+                low = high;
+            } else {
+                for _ in 0..16 {
+                    let mid = (low + high) / 2.0;
+                    if fits(spans, box_size, settings, mid) {
+                        low = mid;
+                    } else {
+                        high = mid;
+                    }
+                }
+            }
+            (low, true)
+        }
+    };
+    let lines = break_lines(spans, box_size.0, settings, size);
+    let total_height = text_height(&lines, settings);
+    let top = (box_size.1 - total_height) * settings.vertical_align;
+    let mut y = top;
+    let mut glyphs = Vec::new();
+    let (mut left, mut right) = (f32::INFINITY, f32::NEG_INFINITY);
+    for (index, line) in lines.into_iter().enumerate() {
+        y += if index == 0 {
+            line.ascent
+        } else {
+            line.size * settings.line_height
+        };
+        let free_space = box_size.0 - line.width;
+        // for justify: the number of places where a word starts after a space
+        let gaps = line
+            .glyphs
+            .windows(2)
+            .filter(|g| g[0].ch == ' ' && g[1].ch != ' ')
+            .count();
+        let (mut x_offset, space_extra) = match settings.align {
+            LayoutAlign::Anchor(anchor) => (free_space * anchor, 0.0),
+            LayoutAlign::Justify if !line.ends_paragraph && gaps > 0 && free_space > 0.0 => {
+                (0.0, free_space / gaps as f32)
+            }
+            LayoutAlign::Justify => (0.0, 0.0),
+        };
+        left = left.min(x_offset);
+        right = right.max(x_offset + line.width + space_extra * gaps as f32);
+        let mut prev_was_space = false;
+        for glyph in line.glyphs {
+            // stretch the space at the start of every word after the first one
+            if prev_was_space && glyph.ch != ' ' {
+                x_offset += space_extra;
+            }
+            prev_was_space = glyph.ch == ' ';
+            glyphs.push(PlacedGlyph {
+                x: glyph.x + x_offset,
+                y,
+                line: index,
+                ..glyph
+            });
+        }
+    }
+    if left > right {
+        (left, right) = (0.0, 0.0);
+    }
+    Layout {
+        glyphs,
+        size,
+        clip,
+        bounds: (left, top, right - left, total_height),
+    }
+}
+
+fn fits(spans: &[LayoutSpan], box_size: (f32, f32), settings: &LayoutSettings, size: f32) -> bool {
+    let lines = break_lines(spans, box_size.0, settings, size);
+    lines.iter().all(|l| l.width <= box_size.0) && text_height(&lines, settings) <= box_size.1
+}
+
+/// The height of the lines, from the top of the first line to the bottom of the last one.
+fn text_height(lines: &[Line], settings: &LayoutSettings) -> f32 {
+    match (lines.first(), lines.last()) {
+        (Some(first), Some(last)) => {
+            first.ascent
+                + lines[1..]
+                    .iter()
+                    .map(|l| l.size * settings.line_height)
+                    .sum::<f32>()
+                - last.descent
+        }
+        _ => 0.0,
+    }
+}
+
+/// Splits the text at newlines and, if wrapping is enabled, wherever a line would become wider than max_width. Words that are too long for one line are split between characters.
+fn break_lines(spans: &[LayoutSpan], max_width: f32, settings: &LayoutSettings, size: f32) -> Vec<Line> {
+    let chars: Vec<(char, usize)> = spans
+        .iter()
+        .enumerate()
+        .flat_map(|(index, span)| span.text.chars().map(move |ch| (ch, index)))
+        .collect();
+    let letter_spacing = |span: usize| settings.letter_spacing * size * spans[span].scale;
+    let advance = |prev: Option<(char, usize)>, (ch, span): (char, usize)| {
+        let font = spans[span].font_for(ch);
+        let scale = Scale::uniform(size * spans[span].scale);
+        let kerning = match prev {
+            // only if both characters use the same font
+            Some((prev, prev_span))
+                if prev_span == span && std::ptr::eq(spans[span].font_for(prev), font) =>
+            {
+                font.pair_kerning(scale, prev, ch)
+            }
+            _ => 0.0,
+        };
+        kerning + font.glyph(ch).scaled(scale).h_metrics().advance_width + letter_spacing(span)
+    };
+    let mut lines = Vec::new();
+    for paragraph in chars.split(|(ch, _)| *ch == '\n') {
+        let mut line = Line::new();
+        // the line's width including any trailing spaces, which don't count towards its actual width
+        let mut x = 0.0;
+        let mut prev = None;
+        for word in paragraph.split_inclusive(|(ch, _)| *ch == ' ') {
+            let word_width: f32 = {
+                let mut p = prev;
+                word.iter()
+                    .filter(|(ch, _)| *ch != ' ')
+                    .map(|&c| {
+                        let a = advance(p, c);
+                        p = Some(c);
+                        a
+                    })
+                    .sum()
+            };
+            if settings.wrap && !line.glyphs.is_empty() && x + word_width > max_width {
+                lines.push(std::mem::replace(&mut line, Line::new()).finish(spans, size));
+                x = 0.0;
+                prev = None;
+            }
+            for &(ch, span) in word {
+                let a = advance(prev, (ch, span));
+                if settings.wrap
+                    && ch != ' '
+                    && word_width > max_width
+                    && !line.glyphs.is_empty()
+                    && x + a > max_width
+                {
+                    // the word doesn't fit on a line by itself
+                    lines.push(std::mem::replace(&mut line, Line::new()).finish(spans, size));
+                    x = 0.0;
+                }
+                line.glyphs.push(PlacedGlyph {
+                    ch,
+                    x,
+                    y: 0.0,
+                    span,
+                    line: 0,
+                });
+                x += a;
+                // trailing spaces don't count towards the line's width
+                if ch != ' ' {
+                    line.width = x - letter_spacing(span);
+                }
+                prev = Some((ch, span));
+            }
+        }
+        line.ends_paragraph = true;
+        lines.push(line.finish(spans, size));
+    }
+    lines
+}
+
+/// How much of each pixel in an area of the layer is covered by something, from 0 to 1. Used to build the text's fill, outline and shadow.
+#[derive(Clone)]
+pub struct Mask {
+    /// The position of the area on the layer. The area may exceed the layer.
+    pub x: i64,
+    pub y: i64,
+    pub w: usize,
+    pub h: usize,
+    pub data: Vec<f32>,
+}
+impl Mask {
+    pub fn new(x: i64, y: i64, w: usize, h: usize) -> Self {
+        Self {
+            x,
+            y,
+            w,
+            h,
+            data: vec![0.0; w * h],
+        }
+    }
+    /// Coordinates are on the layer. Outside of the area, this is 0.
+    pub fn get(&self, x: i64, y: i64) -> f32 {
+        let (x, y) = (x - self.x, y - self.y);
+        if x >= 0 && y >= 0 && (x as usize) < self.w && (y as usize) < self.h {
+            self.data[x as usize + y as usize * self.w]
+        } else {
+            0.0
+        }
+    }
+    /// Grows everything by radius pixels in all directions (with antialiasing), for outlines.
+    pub fn dilate(&self, radius: f32) -> Self {
+        if radius <= 0.0 {
+            return self.clone();
+        }
+        let r = radius.ceil() as i64 + 1;
+        // offsets and their weight (1 inside the circle, fading out at its edge)
+        let mut kernel = Vec::new();
+        for dy in -r..=r {
+            for dx in -r..=r {
+                let weight = (radius + 1.0 - ((dx * dx + dy * dy) as f32).sqrt()).min(1.0);
+                if weight > 0.0 {
+                    kernel.push((dx, dy, weight));
+                }
+            }
+        }
+        let mut out = Self::new(self.x, self.y, self.w, self.h);
+        for y in 0..self.h as i64 {
+            for x in 0..self.w as i64 {
+                let mut v = 0.0f32;
+                for (dx, dy, weight) in kernel.iter() {
+                    v = v.max(self.get(self.x + x + dx, self.y + y + dy) * weight);
+                    if v >= 1.0 {
+                        break;
+                    }
+                }
+                out.data[x as usize + y as usize * self.w] = v;
+            }
+        }
+        out
+    }
+    /// Blurs the mask (three box blurs, which is close to a gaussian blur with the given radius), for shadows.
+    pub fn blur(&self, radius: f32) -> Self {
+        let mut out = self.clone();
+        let r = (radius / 3f32.sqrt()).round() as i64;
+        if r <= 0 {
+            return out;
+        }
+        for _ in 0..3 {
+            for horizontal in [true, false] {
+                let (len, lines) = if horizontal {
+                    (self.w, self.h)
+                } else {
+                    (self.h, self.w)
+                };
+                let index = |line: usize, i: usize| {
+                    if horizontal {
+                        i + line * self.w
+                    } else {
+                        line + i * self.w
+                    }
+                };
+                let mut buf = vec![0.0f32; len];
+                for line in 0..lines {
+                    // running sum over the window [i - r, i + r]
+                    let mut sum = 0.0;
+                    for i in 0..(r as usize).min(len) {
+                        sum += out.data[index(line, i)];
+                    }
+                    for i in 0..len {
+                        if i + (r as usize) < len {
+                            sum += out.data[index(line, i + r as usize)];
+                        }
+                        if i > r as usize {
+                            sum -= out.data[index(line, i - r as usize - 1)];
+                        }
+                        buf[i] = sum / (2 * r + 1) as f32;
+                    }
+                    for i in 0..len {
+                        out.data[index(line, i)] = buf[i];
+                    }
+                }
+            }
+        }
+        out
+    }
+    /// Combines two masks which cover the same area (like the ones from glyph_masks), so that the result covers everything either of them covers.
+    pub fn union(&mut self, other: &Self) {
+        for (a, b) in self.data.iter_mut().zip(other.data.iter()) {
+            *a = a.max(*b);
+        }
+    }
+    /// Draws the color onto the layer wherever the mask covers it. The mask is moved by offset first.
+    pub fn paint(&self, layer: &mut image::RgbaImage, color: (f64, f64, f64, f64), offset: (i64, i64)) {
+        let (lw, lh) = (layer.width() as i64, layer.height() as i64);
+        for my in 0..self.h {
+            let y = self.y + offset.1 + my as i64;
+            if y < 0 || y >= lh {
+                continue;
+            }
+            for mx in 0..self.w {
+                let x = self.x + offset.0 + mx as i64;
+                if x < 0 || x >= lw {
+                    continue;
+                }
+                let coverage = self.data[mx + my * self.w];
+                if coverage > 0.0 {
+                    blend_into(
+                        &mut layer.get_pixel_mut(x as _, y as _).0,
+                        (color.0, color.1, color.2, color.3 * coverage as f64),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Renders the glyphs into one mask per span, each covering the text's bounds plus margin pixels on every side. transforms is either empty or has one entry per glyph. origin is where the box's top left corner is on the layer. If clip_rect (x, y, w, h) is set, glyphs are cut off outside of it.
+pub fn glyph_masks(
+    spans: &[LayoutSpan],
+    layout: &Layout,
+    transforms: &[GlyphTransform],
+    origin: (f32, f32),
+    clip_rect: Option<(i64, i64, i64, i64)>,
+    margin: i64,
+) -> Vec<Mask> {
+    // glyphs can reach a bit outside of their advance width, so add some extra space
+    let max_scale = spans.iter().map(|s| s.scale).fold(1.0f32, f32::max);
+    let extra = (layout.size * max_scale * 0.5).ceil() as i64;
+    let new_mask = || {
+        Mask::new(
+            (origin.0 + layout.bounds.0).floor() as i64 - margin - extra,
+            (origin.1 + layout.bounds.1).floor() as i64 - margin - extra,
+            (layout.bounds.2.ceil() as i64 + 2 * (margin + extra) + 1).max(0) as usize,
+            (layout.bounds.3.ceil() as i64 + 2 * (margin + extra) + 1).max(0) as usize,
+        )
+    };
+    let mut masks: Vec<Mask> = spans.iter().map(|_| new_mask()).collect();
+    let (x_min, y_min, x_max, y_max) = match clip_rect {
+        Some((x, y, cw, ch)) => (x, y, x + cw, y + ch),
+        None => (i64::MIN, i64::MIN, i64::MAX, i64::MAX),
+    };
+    for (index, glyph) in layout.glyphs.iter().enumerate() {
+        let transform = transforms.get(index).copied().unwrap_or_default();
+        if glyph.ch == ' ' || transform.opacity <= 0.0 || transform.scale <= 0.0 {
+            continue;
+        }
+        let span = &spans[glyph.span];
+        let mask = &mut masks[glyph.span];
+        let font = span.font_for(glyph.ch);
+        let glyph_size = layout.size * span.scale;
+        let mut position = point(
+            origin.0 + glyph.x + transform.offset.0,
+            origin.1 + glyph.y + transform.offset.1,
+        );
+        if transform.scale != 1.0 {
+            // keep the glyph's center where it would be without scaling
+            if let Some(bb) = font
+                .glyph(glyph.ch)
+                .scaled(Scale::uniform(glyph_size))
+                .exact_bounding_box()
+            {
+                let center = ((bb.min.x + bb.max.x) / 2.0, (bb.min.y + bb.max.y) / 2.0);
+                position.x += center.0 * (1.0 - transform.scale);
+                position.y += center.1 * (1.0 - transform.scale);
+            }
+        }
+        let positioned = font
+            .glyph(glyph.ch)
+            .scaled(Scale::uniform(glyph_size * transform.scale))
+            .positioned(position);
+        if let Some(bb) = positioned.pixel_bounding_box() {
+            positioned.draw(|gx, gy, coverage| {
+                let (x, y) = (bb.min.x as i64 + gx as i64, bb.min.y as i64 + gy as i64);
+                let (mx, my) = (x - mask.x, y - mask.y);
+                if x >= x_min
+                    && x < x_max
+                    && y >= y_min
+                    && y < y_max
+                    && mx >= 0
+                    && my >= 0
+                    && (mx as usize) < mask.w
+                    && (my as usize) < mask.h
+                {
+                    let v = &mut mask.data[mx as usize + my as usize * mask.w];
+                    *v = (*v + coverage * transform.opacity).min(1.0);
+                }
+            });
+        }
+    }
+    masks
+}
+
+/// A rectangle (x, y, w, h) with rounded corners, antialiased.
+pub fn rounded_rect_mask(rect: (f32, f32, f32, f32), radius: f32) -> Mask {
+    let radius = radius.max(0.0).min(rect.2 / 2.0).min(rect.3 / 2.0);
+    let mut mask = Mask::new(
+        rect.0.floor() as i64,
+        rect.1.floor() as i64,
+        (rect.2.ceil() as i64 + 2).max(0) as usize,
+        (rect.3.ceil() as i64 + 2).max(0) as usize,
+    );
+    let (cx, cy) = (rect.0 + rect.2 / 2.0, rect.1 + rect.3 / 2.0);
+    let (hw, hh) = (rect.2 / 2.0 - radius, rect.3 / 2.0 - radius);
+    for my in 0..mask.h {
+        for mx in 0..mask.w {
+            // signed distance from the pixel's center to the rounded rectangle
+            let px = (mask.x + mx as i64) as f32 + 0.5 - cx;
+            let py = (mask.y + my as i64) as f32 + 0.5 - cy;
+            let (qx, qy) = (px.abs() - hw, py.abs() - hh);
+            let outside = (qx.max(0.0).powi(2) + qy.max(0.0).powi(2)).sqrt();
+            let distance = outside + qx.max(qy).min(0.0) - radius;
+            mask.data[mx + my * mask.w] = (0.5 - distance).max(0.0).min(1.0);
+        }
+    }
+    mask
+}
+
+/// Draws a color with straight alpha over a pixel on a (transparent) layer.
+pub fn blend_into(px: &mut [u8; 4], color: (f64, f64, f64, f64)) {
+    let src_a = color.3.max(0.0).min(1.0);
+    let dst_a = px[3] as f64 / 255.0;
+    let out_a = src_a + dst_a * (1.0 - src_a);
+    if out_a <= 0.0 {
+        return;
+    }
+    for (i, c) in [color.0, color.1, color.2].into_iter().enumerate() {
+        let dst = px[i] as f64 / 255.0;
+        let out = (c.max(0.0).min(1.0) * src_a + dst * dst_a * (1.0 - src_a)) / out_a;
+        px[i] = (out * 255.0).round() as u8;
+    }
+    px[3] = (out_a * 255.0).round() as u8;
+}
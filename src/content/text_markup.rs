@@ -0,0 +1,166 @@
+//! A small markup language for styling parts of a Text:
+//!
+//! - `<b>`...`</b>`: bold
+//! - `<color=r,g,b>` or `<color=r,g,b,a>` (0-1) or `<color=#rrggbb>` / `<color=#rrggbbaa>`...`</color>`
+//! - `<size=1.5>`...`</size>`: font size, relative to the text's normal size
+//! - `<font=path>` or `<font=@Family:Style>`...`</font>`: a different font (see font_manager.rs)
+//!
+//! Tags can be nested, a closing tag ends the innermost tag with that name. `&lt;`, `&gt;` and `&amp;` can be used for <, > and &. Anything that looks like a tag but isn't one of these is kept as normal text.
+
+#[derive(Clone, Default, PartialEq)]
+pub struct SpanStyle {
+    pub bold: bool,
+    /// None: the text's color
+    pub color: Option<(f64, f64, f64, f64)>,
+    /// Relative to the text's font size. None: 1.0
+    pub size: Option<f32>,
+    /// A path to a font file or @Family:Style (see font_manager.rs). None: the text's font
+    pub font: Option<String>,
+}
+impl SpanStyle {
+    pub fn scale(&self) -> f32 {
+        self.size.unwrap_or(1.0)
+    }
+}
+
+pub struct Span {
+    pub text: String,
+    pub style: SpanStyle,
+}
+
+enum Tag {
+    Bold,
+    Color((f64, f64, f64, f64)),
+    Size(f32),
+    Font(String),
+}
+impl Tag {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Bold => "b",
+            Self::Color(_) => "color",
+            Self::Size(_) => "size",
+            Self::Font(_) => "font",
+        }
+    }
+    fn parse(tag: &str) -> Option<Self> {
+        let (name, value) = match tag.split_once('=') {
+            Some((name, value)) => (name.trim(), Some(value.trim())),
+            None => (tag.trim(), None),
+        };
+        Some(match (name, value) {
+            ("b", None) => Self::Bold,
+            ("color", Some(value)) => Self::Color(parse_color(value)?),
+            ("size", Some(value)) => Self::Size(value.parse().ok()?),
+            ("font", Some(value)) if !value.is_empty() => Self::Font(value.to_string()),
+            _ => return None,
+        })
+    }
+}
+
+/// Splits the text into spans with the same style. The result is never empty.
+pub fn parse(text: &str) -> Vec<Span> {
+    let mut spans = Vec::new();
+    let mut tags: Vec<Tag> = Vec::new();
+    let mut current = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(|c| c == '<' || c == '&') {
+        current.push_str(&rest[..start]);
+        rest = &rest[start..];
+        if rest.starts_with('&') {
+            let (entity, ch) = if rest.starts_with("&lt;") {
+                ("&lt;", '<')
+            } else if rest.starts_with("&gt;") {
+                ("&gt;", '>')
+            } else if rest.starts_with("&amp;") {
+                ("&amp;", '&')
+            } else {
+                ("&", '&')
+            };
+            current.push(ch);
+            rest = &rest[entity.len()..];
+            continue;
+        }
+        let end = match rest.find('>') {
+            Some(end) => end,
+            None => break,
+        };
+        let inner = &rest[1..end];
+        let changed = if let Some(name) = inner.strip_prefix('/') {
+            match tags.iter().rposition(|t| t.name() == name.trim()) {
+                Some(index) => {
+                    push_span(&mut spans, &mut current, &tags);
+                    tags.remove(index);
+                    true
+                }
+                None => false,
+            }
+        } else if let Some(tag) = Tag::parse(inner) {
+            push_span(&mut spans, &mut current, &tags);
+            tags.push(tag);
+            true
+        } else {
+            false
+        };
+        if changed {
+            rest = &rest[end + 1..];
+        } else {
+            // not a tag, keep the '<'
+            current.push('<');
+            rest = &rest[1..];
+        }
+    }
+    current.push_str(rest);
+    push_span(&mut spans, &mut current, &tags);
+    if spans.is_empty() {
+        spans.push(Span {
+            text: String::new(),
+            style: SpanStyle::default(),
+        });
+    }
+    spans
+}
+
+fn push_span(spans: &mut Vec<Span>, text: &mut String, tags: &[Tag]) {
+    if text.is_empty() {
+        return;
+    }
+    let mut style = SpanStyle::default();
+    for tag in tags {
+        match tag {
+            Tag::Bold => style.bold = true,
+            Tag::Color(c) => style.color = Some(*c),
+            Tag::Size(s) => style.size = Some(*s),
+            Tag::Font(f) => style.font = Some(f.clone()),
+        }
+    }
+    let text = std::mem::take(text);
+    match spans.last_mut() {
+        Some(last) if last.style == style => last.text.push_str(&text),
+        _ => spans.push(Span { text, style }),
+    }
+}
+
+/// r,g,b or r,g,b,a (0-1), #rrggbb or #rrggbbaa
+pub fn parse_color(value: &str) -> Option<(f64, f64, f64, f64)> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let channel = |i: usize| -> Option<f64> {
+            Some(u8::from_str_radix(hex.get(i * 2..i * 2 + 2)?, 16).ok()? as f64 / 255.0)
+        };
+        match hex.len() {
+            6 => Some((channel(0)?, channel(1)?, channel(2)?, 1.0)),
+            8 => Some((channel(0)?, channel(1)?, channel(2)?, channel(3)?)),
+            _ => None,
+        }
+    } else {
+        let values = value
+            .split(',')
+            .map(|v| v.trim().parse().ok())
+            .collect::<Option<Vec<f64>>>()?;
+        match values[..] {
+            [r, g, b] => Some((r, g, b, 1.0)),
+            [r, g, b, a] => Some((r, g, b, a)),
+            _ => None,
+        }
+    }
+}
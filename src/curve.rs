@@ -54,6 +54,8 @@ pub enum CurveData {
     Linear(Curve, Curve),
     /// Smoothly go from one point to another, where f(0.0) = self.0, f(1.0) = self.1, and f'(0.0) = f'(1.0) = 0.0 (flattened out at both ends)
     SmoothFlat(Curve, Curve),
+    /// Bezier(a, b, (x1, y1), (x2, y2)): A cubic Bezier curve from a to b. The first control point is at time x1 with a value of a + y1, the second one at time x2 with a value of b + y2. x1 and x2 should be between 0 and 1 (they are clamped to that range).
+    Bezier(Curve, Curve, (f64, f64), (f64, f64)),
     /// Chains multiple Curves together. Obviously, the curve's values should be the same at the points where they meet, but this is not strictly necessary. The f64 values in the tuple are the length for the corresponding curve. If their sum is less than 1, the end will use the value the final curve returned for 1.
     Chain(Vec<(Curve, f64)>),
     Program(
@@ -61,6 +63,35 @@ pub enum CurveData {
         CurveExternalProgramMode,
     ),
     // ProgramPersistent((), ), // TODO!
+    /// The sum of all curves' values. If the vec is empty, this is 0.
+    Sum(Vec<Curve>),
+    /// The product of all curves' values. If the vec is empty, this is 1.
+    Product(Vec<Curve>),
+    /// The smallest of all curves' values. If the vec is empty, this is 0.
+    Min(Vec<Curve>),
+    /// The largest of all curves' values. If the vec is empty, this is 0.
+    Max(Vec<Curve>),
+    /// Clamp(value, min, max): The first curve's value, but never less than min or more than max.
+    Clamp(Curve, Curve, Curve),
+    /// Remap(offset, scale, curve): Evaluates the inner curve at offset + scale * progress instead of at progress. Can be used to shift, stretch or reverse (scale -1, offset 1) a curve in time.
+    Remap(Curve, Curve, Curve),
+    /// Plays the curve n times within the 0-1 range. n does not have to be a whole number.
+    Loop(f64, Curve),
+    /// Like Loop, but every second repetition is played backwards, so there are no jumps where two repetitions meet.
+    PingPong(f64, Curve),
+    /// Splits the 0-1 range into n steps and holds the curve's value from the start of each step until the next one begins.
+    Hold(f64, Curve),
+    /// Quantize(step, curve): Rounds the curve's value to the nearest multiple of step. If step is 0, the value is not changed.
+    Quantize(Curve, Curve),
+    /// A table of keyframes, sorted by time. Between two keyframes, the interpolation of the first one is used. Before the first and after the last keyframe, the value of that keyframe is used. If there are no keyframes, this is 0.
+    Keyframes(Vec<Keyframe>),
+    /// Smooth pseudo-random values between min and max. With the same seed, the values will always be the same. The frequency is how many random points are placed within the 0-1 range.
+    Noise {
+        seed: u64,
+        frequency: f64,
+        min: Curve,
+        max: Curve,
+    },
 }
 impl Clone for CurveData {
     fn clone(&self) -> Self {
@@ -68,6 +99,7 @@ impl Clone for CurveData {
             Self::Constant(a) => Self::Constant(a.clone()),
             Self::Linear(a, b) => Self::Linear(a.clone(), b.clone()),
             Self::SmoothFlat(a, b) => Self::SmoothFlat(a.clone(), b.clone()),
+            Self::Bezier(a, b, h1, h2) => Self::Bezier(a.clone(), b.clone(), *h1, *h2),
             Self::Chain(a) => Self::Chain({
                 let mut nvec = Vec::with_capacity(a.len());
                 for b in a {
@@ -76,6 +108,28 @@ impl Clone for CurveData {
                 nvec
             }),
             Self::Program(p, m) => Self::Program(p.clone(), *m),
+            Self::Sum(a) => Self::Sum(a.clone()),
+            Self::Product(a) => Self::Product(a.clone()),
+            Self::Min(a) => Self::Min(a.clone()),
+            Self::Max(a) => Self::Max(a.clone()),
+            Self::Clamp(v, min, max) => Self::Clamp(v.clone(), min.clone(), max.clone()),
+            Self::Remap(offset, scale, c) => Self::Remap(offset.clone(), scale.clone(), c.clone()),
+            Self::Loop(n, c) => Self::Loop(*n, c.clone()),
+            Self::PingPong(n, c) => Self::PingPong(*n, c.clone()),
+            Self::Hold(n, c) => Self::Hold(*n, c.clone()),
+            Self::Quantize(step, c) => Self::Quantize(step.clone(), c.clone()),
+            Self::Keyframes(k) => Self::Keyframes(k.clone()),
+            Self::Noise {
+                seed,
+                frequency,
+                min,
+                max,
+            } => Self::Noise {
+                seed: *seed,
+                frequency: *frequency,
+                min: min.clone(),
+                max: max.clone(),
+            },
         }
     }
 }
@@ -113,6 +167,15 @@ impl CurveData {
                 let factor = -2.0 * progress * progress * progress + 3.0 * progress * progress;
                 x1 + (x2 - x1) * factor
             }
+            Self::Bezier(a, b, (x1, y1), (x2, y2)) => {
+                let (a, b) = (a.get_value(progress), b.get_value(progress));
+                let t = Self::bezier_t_for_x(progress, x1.max(0.0).min(1.0), x2.max(0.0).min(1.0));
+                let mt = 1.0 - t;
+                mt * mt * mt * a
+                    + 3.0 * mt * mt * t * (a + y1)
+                    + 3.0 * mt * t * t * (b + y2)
+                    + t * t * t * b
+            }
             Self::Program(p, m) => {
                 let txt =
                     String::from_utf8(p.get_next(format!("{}", progress).as_bytes()).unwrap())
@@ -122,6 +185,359 @@ impl CurveData {
                     format!("Program output could not be parsed into a float: '{}'", txt).as_str(),
                 )
             }
+            Self::Sum(curves) => curves.iter().map(|c| c.get_value(progress)).sum(),
+            Self::Product(curves) => curves.iter().map(|c| c.get_value(progress)).product(),
+            Self::Min(curves) => curves
+                .iter()
+                .map(|c| c.get_value(progress))
+                .reduce(f64::min)
+                .unwrap_or(0.0),
+            Self::Max(curves) => curves
+                .iter()
+                .map(|c| c.get_value(progress))
+                .reduce(f64::max)
+                .unwrap_or(0.0),
+            Self::Clamp(v, min, max) => {
+                let (min, max) = (min.get_value(progress), max.get_value(progress));
+                // not f64::clamp, because that panics if min > max.
+                v.get_value(progress).min(max).max(min)
+            }
+            Self::Remap(offset, scale, c) => {
+                c.get_value(offset.get_value(progress) + scale.get_value(progress) * progress)
+            }
+            Self::Loop(n, c) => c.get_value(Self::repetition(progress, *n).0),
+            Self::PingPong(n, c) => {
+                let (p, i) = Self::repetition(progress, *n);
+                c.get_value(if i % 2 == 0 { p } else { 1.0 - p })
+            }
+            Self::Hold(n, c) => {
+                if *n > 0.0 {
+                    c.get_value((progress * n).floor() / n)
+                } else {
+                    c.get_value(progress)
+                }
+            }
+            Self::Quantize(step, c) => {
+                let step = step.get_value(progress);
+                let v = c.get_value(progress);
+                if step != 0.0 {
+                    (v / step).round() * step
+                } else {
+                    v
+                }
+            }
+            Self::Keyframes(keyframes) => {
+                let next = keyframes.partition_point(|k| k.time <= progress);
+                match (next.checked_sub(1).map(|i| &keyframes[i]), keyframes.get(next)) {
+                    (Some(a), Some(b)) => {
+                        let factor = (progress - a.time) / (b.time - a.time);
+                        a.value + (b.value - a.value) * a.interpolation.get_factor(factor)
+                    }
+                    (Some(k), None) | (None, Some(k)) => k.value,
+                    (None, None) => 0.0,
+                }
+            }
+            Self::Noise {
+                seed,
+                frequency,
+                min,
+                max,
+            } => {
+                let pos = progress * frequency.max(0.0);
+                let index = pos.floor();
+                let (a, b) = (
+                    Self::noise_value(*seed, index as i64),
+                    Self::noise_value(*seed, index as i64 + 1),
+                );
+                let factor = pos - index;
+                let factor = -2.0 * factor * factor * factor + 3.0 * factor * factor; // same as SmoothFlat
+                let min = min.get_value(progress);
+                min + (max.get_value(progress) - min) * (a + (b - a) * factor)
+            }
+        }
+    }
+
+    /// For Loop and PingPong: Splits the 0-1 range into n repetitions and returns the progress within the current repetition and that repetition's index. The very end (progress 1.0) is treated as the end of the last repetition, not the start of a new one.
+    fn repetition(progress: f64, n: f64) -> (f64, i64) {
+        if n <= 0.0 {
+            return (progress, 0);
+        }
+        let pos = progress * n;
+        let index = pos.floor();
+        if index > 0.0 && pos == index && progress >= 1.0 {
+            (1.0, index as i64 - 1)
+        } else {
+            (pos - index, index as i64)
+        }
+    }
+
+    /// For Bezier: finds t so that the curve's x (time) is the given progress. With x1 and x2 in the 0-1 range, x(t) can't decrease, so bisection always works.
+    fn bezier_t_for_x(progress: f64, x1: f64, x2: f64) -> f64 {
+        if progress <= 0.0 {
+            return 0.0;
+        }
+        if progress >= 1.0 {
+            return 1.0;
+        }
+        let (mut low, mut high) = (0.0, 1.0);
+        for _ in 0..40 {
+            let t = (low + high) / 2.0;
+            let mt = 1.0 - t;
+            let x = 3.0 * mt * mt * t * x1 + 3.0 * mt * t * t * x2 + t * t * t;
+            if x < progress {
+                low = t;
+            } else {
+                high = t;
+            }
+        }
+        (low + high) / 2.0
+    }
+
+    /// A pseudo-random value from 0 to 1 that only depends on the seed and the index (splitmix64).
+    fn noise_value(seed: u64, index: i64) -> f64 {
+        let mut z = seed
+            .wrapping_add((index as u64).wrapping_mul(0x9E3779B97F4A7C15))
+            .wrapping_add(0x9E3779B97F4A7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z = z ^ (z >> 31);
+        (z >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+impl Curve {
+    /// Samples the curve at (samples) evenly spaced points from 0 to 1 (both inclusive), so that it can be exported or cached as a table of keyframes. Useful for curves that are slow to evaluate, like Program.
+    pub fn bake(&self, samples: usize) -> Vec<Keyframe> {
+        let samples = samples.max(2);
+        (0..samples)
+            .map(|i| {
+                let time = i as f64 / (samples - 1) as f64;
+                Keyframe {
+                    time,
+                    value: self.get_value(time),
+                    interpolation: KeyframeInterpolation::Linear,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Curve {
+    /// If this curve is a Constant, returns its value.
+    pub fn constant_value(&self) -> Option<f64> {
+        match self {
+            Self::Owned(data) | Self::Shared(_, data) => match data.as_ref() {
+                CurveData::Constant(v) => Some(*v),
+                _ => None,
+            },
+        }
+    }
+
+    /// Converts the curve into a list of points for graphical editing, if it only consists of simple segments (constants, linear, smooth and Bezier between constant values, either on their own, as a Chain or as Keyframes). The first point is always at time 0 and the last one at time 1.
+    pub fn to_chain_points(&self) -> Option<Vec<ChainPoint>> {
+        let data = match self {
+            Self::Owned(data) | Self::Shared(_, data) => data.as_ref(),
+        };
+        let mut points = Vec::new();
+        match data {
+            CurveData::Chain(chain) => {
+                for (i, (curve, start)) in chain.iter().enumerate() {
+                    let (segment, a, b) = Self::chain_segment(curve)?;
+                    points.push(ChainPoint {
+                        time: if i == 0 { 0.0 } else { *start },
+                        value: a,
+                        segment,
+                    });
+                    if i + 1 == chain.len() {
+                        points.push(ChainPoint {
+                            time: 1.0,
+                            value: b,
+                            segment: ChainSegment::Hold,
+                        });
+                    }
+                }
+            }
+            CurveData::Keyframes(keyframes) => {
+                for k in keyframes {
+                    if k.time < 0.0 || k.time > 1.0 {
+                        return None;
+                    }
+                    points.push(ChainPoint {
+                        time: k.time,
+                        value: k.value,
+                        segment: match k.interpolation {
+                            KeyframeInterpolation::Hold => ChainSegment::Hold,
+                            KeyframeInterpolation::Linear => ChainSegment::Linear,
+                            KeyframeInterpolation::Smooth => ChainSegment::Smooth,
+                        },
+                    });
+                }
+                match (points.first(), points.last()) {
+                    (Some(first), Some(last)) => {
+                        let (first, last) = (*first, *last);
+                        if first.time > 0.0 {
+                            points.insert(
+                                0,
+                                ChainPoint {
+                                    time: 0.0,
+                                    segment: ChainSegment::Hold,
+                                    ..first
+                                },
+                            );
+                        }
+                        if last.time < 1.0 {
+                            points.push(ChainPoint {
+                                time: 1.0,
+                                segment: ChainSegment::Hold,
+                                ..last
+                            });
+                        }
+                    }
+                    _ => return None,
+                }
+            }
+            _ => {
+                let (segment, a, b) = Self::chain_segment(self)?;
+                points.push(ChainPoint {
+                    time: 0.0,
+                    value: a,
+                    segment,
+                });
+                points.push(ChainPoint {
+                    time: 1.0,
+                    value: b,
+                    segment: ChainSegment::Hold,
+                });
+            }
+        }
+        Some(points)
+    }
+
+    /// The inverse of to_chain_points: Creates a Chain from the points. Each point's segment describes how to get from that point to the next one.
+    pub fn from_chain_points(points: &[ChainPoint]) -> Self {
+        match points {
+            [] => CurveData::Constant(0.0).into(),
+            [point] => CurveData::Constant(point.value).into(),
+            points => {
+                let mut chain = Vec::with_capacity(points.len());
+                if points[0].time > 0.0 {
+                    chain.push((CurveData::Constant(points[0].value).into(), 0.0));
+                }
+                for pair in points.windows(2) {
+                    let (a, b) = (&pair[0], &pair[1]);
+                    let (va, vb) = (
+                        CurveData::Constant(a.value).into(),
+                        CurveData::Constant(b.value).into(),
+                    );
+                    chain.push((
+                        match a.segment {
+                            ChainSegment::Hold => CurveData::Constant(a.value),
+                            ChainSegment::Linear => CurveData::Linear(va, vb),
+                            ChainSegment::Smooth => CurveData::SmoothFlat(va, vb),
+                            ChainSegment::Bezier(h1, h2) => CurveData::Bezier(va, vb, h1, h2),
+                        }
+                        .into(),
+                        a.time,
+                    ));
+                }
+                let last = points.last().unwrap();
+                if last.time < 1.0 {
+                    chain.push((CurveData::Constant(last.value).into(), last.time));
+                }
+                CurveData::Chain(chain).into()
+            }
+        }
+    }
+
+    /// For to_chain_points: The segment type, start value and end value of a simple curve.
+    fn chain_segment(curve: &Curve) -> Option<(ChainSegment, f64, f64)> {
+        let data = match curve {
+            Self::Owned(data) | Self::Shared(_, data) => data.as_ref(),
+        };
+        Some(match data {
+            CurveData::Constant(v) => (ChainSegment::Hold, *v, *v),
+            CurveData::Linear(a, b) => (ChainSegment::Linear, a.constant_value()?, b.constant_value()?),
+            CurveData::SmoothFlat(a, b) => (ChainSegment::Smooth, a.constant_value()?, b.constant_value()?),
+            CurveData::Bezier(a, b, h1, h2) => (
+                ChainSegment::Bezier(*h1, *h2),
+                a.constant_value()?,
+                b.constant_value()?,
+            ),
+            _ => return None,
+        })
+    }
+}
+
+/// A point in the graphical curve editor. See Curve::to_chain_points.
+#[derive(Clone, Copy)]
+pub struct ChainPoint {
+    pub time: f64,
+    pub value: f64,
+    /// How to get from this point to the next one.
+    pub segment: ChainSegment,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ChainSegment {
+    /// Keep this point's value until the next point is reached.
+    Hold,
+    Linear,
+    Smooth,
+    /// The two control points, like in CurveData::Bezier.
+    Bezier((f64, f64), (f64, f64)),
+}
+impl ChainSegment {
+    /// The control points a new Bezier segment starts with. This looks similar to Smooth.
+    pub const DEFAULT_BEZIER: Self = Self::Bezier((1.0 / 3.0, 0.0), (2.0 / 3.0, 0.0));
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hold => "hold",
+            Self::Linear => "linear",
+            Self::Smooth => "smooth",
+            Self::Bezier(..) => "bezier",
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Keyframe {
+    pub time: f64,
+    pub value: f64,
+    /// How to get from this keyframe to the next one.
+    pub interpolation: KeyframeInterpolation,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum KeyframeInterpolation {
+    /// Keep this keyframe's value until the next keyframe is reached.
+    Hold,
+    Linear,
+    /// Like CurveData::SmoothFlat.
+    Smooth,
+}
+impl KeyframeInterpolation {
+    /// Turns a factor from 0 to 1 (the distance between two keyframes) into how much of the second keyframe's value should be used.
+    pub fn get_factor(&self, factor: f64) -> f64 {
+        match self {
+            Self::Hold => 0.0,
+            Self::Linear => factor,
+            Self::Smooth => -2.0 * factor * factor * factor + 3.0 * factor * factor,
+        }
+    }
+    /// The name used in csv/json keyframe files.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hold => "hold",
+            Self::Linear => "linear",
+            Self::Smooth => "smooth",
+        }
+    }
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "hold" | "step" | "constant" => Some(Self::Hold),
+            "linear" => Some(Self::Linear),
+            "smooth" => Some(Self::Smooth),
+            _ => None,
         }
     }
 }
@@ -79,6 +79,52 @@ pub mod effects {
             self
         }
     }
+    impl EffectsEnum {
+        /// The effect's curves with their names, for things that work on any curve (like exporting one).
+        pub fn curves(&self) -> Vec<(&'static str, &Curve)> {
+            match self {
+                EffectsEnum::Nothing(_) | EffectsEnum::BlackWhite(_) | EffectsEnum::Shake(_) | EffectsEnum::ColorKey(_) => vec![],
+                EffectsEnum::Rotate(v) => {
+                    let mut curves = vec![("angle", &v.angle), ("rotation_x", &v.rotation_point.0), ("rotation_y", &v.rotation_point.1)];
+                    if let Rotate_Mode::RotatePointSpiral { out, far } = &v.rotate_mode {
+                        curves.push(("out", out));
+                        curves.push(("far", far));
+                    }
+                    curves
+                },
+                EffectsEnum::ChangeTime(v) => vec![("time", &v.time)],
+                EffectsEnum::ColorAdjust(v) => match &v.mode {
+                    ColorAdjust_Mode::Rgba(r, g, b, a) => vec![("r", r), ("g", g), ("b", b), ("a", a)],
+                },
+                EffectsEnum::Blur(v) => match &v.mode {
+                    Blur_Mode::Square { radius } => vec![("radius", radius)],
+                    Blur_Mode::Downscale { width, height } => vec![("width", width), ("height", height)],
+                },
+            }
+        }
+        /// Like curves, but mutable.
+        pub fn curves_mut(&mut self) -> Vec<(&'static str, &mut Curve)> {
+            match self {
+                EffectsEnum::Nothing(_) | EffectsEnum::BlackWhite(_) | EffectsEnum::Shake(_) | EffectsEnum::ColorKey(_) => vec![],
+                EffectsEnum::Rotate(v) => {
+                    let mut curves = vec![("angle", &mut v.angle), ("rotation_x", &mut v.rotation_point.0), ("rotation_y", &mut v.rotation_point.1)];
+                    if let Rotate_Mode::RotatePointSpiral { out, far } = &mut v.rotate_mode {
+                        curves.push(("out", out));
+                        curves.push(("far", far));
+                    }
+                    curves
+                },
+                EffectsEnum::ChangeTime(v) => vec![("time", &mut v.time)],
+                EffectsEnum::ColorAdjust(v) => match &mut v.mode {
+                    ColorAdjust_Mode::Rgba(r, g, b, a) => vec![("r", r), ("g", g), ("b", b), ("a", a)],
+                },
+                EffectsEnum::Blur(v) => match &mut v.mode {
+                    Blur_Mode::Square { radius } => vec![("radius", radius)],
+                    Blur_Mode::Downscale { width, height } => vec![("width", width), ("height", height)],
+                },
+            }
+        }
+    }
 
 
 
@@ -193,19 +239,173 @@ pub mod effects {
 
     pub struct ChangeTime {
         pub time: Curve,
+        pub mode: ChangeTime_Mode,
+        /// Set once the user was told that Blend/MotionInterpolate can't find out how many frames the video has, so the warning isn't repeated for every frame.
+        warned_frame_count: bool,
     }
-    impl ChangeTime { pub fn new(time: Curve) -> Self {
-        Self { time, }
+    impl ChangeTime { pub fn new(time: Curve, mode: ChangeTime_Mode) -> Self {
+        Self { time, mode, warned_frame_count: false, }
     } }
+    impl ChangeTime {
+        /// Plays the video backwards.
+        pub fn reverse() -> Self { Self::new(crate::curve::CurveData::Linear(crate::curve::CurveData::Constant(1.0).into(), crate::curve::CurveData::Constant(0.0).into()).into(), ChangeTime_Mode::Nearest) }
+        /// Always shows the video as it would look at the given progress.
+        pub fn freeze(time: f64) -> Self { Self::new(crate::curve::CurveData::Constant(time).into(), ChangeTime_Mode::Nearest) }
+        /// The highest progress that is still drawn. prep_draw doesn't draw videos at progress 1.0, but reversing or freezing a video at its end should still show its last frame.
+        const LAST_PROGRESS: f64 = 1.0 - 1e-7;
+        /// Converts the video's own progress (0-1) to the progress that has to be passed to prep_draw.
+        fn outer_progress(vid: &crate::video::Video, local: f64) -> f64 { vid.set_start_frame + vid.set_length * local.min(Self::LAST_PROGRESS) }
+        /// If frames is 0, uses the number of frames if the video is a VidFromImagesInDirectory or an FfmpegVid. Err while an FfmpegVid's frames are still being counted (see FfmpegVid::get_frame_count), Ok(None) if there aren't at least two frames to blend between.
+        fn frame_count(frames: f64, vid: &mut crate::video::Video, wait: bool) -> Result<Option<f64>, ()> {
+            let frames = if frames > 0.0 { frames } else { match &mut vid.video.vt {
+                crate::video::VideoTypeEnum::Raw(v) => v.get_length() as f64,
+                crate::video::VideoTypeEnum::Ffmpeg(v) => v.get_frame_count(wait).ok_or(())?.unwrap_or(0) as f64,
+                _ => 0.0,
+            } };
+            Ok(if frames >= 2.0 { Some(frames) } else { None })
+        }
+        fn draw_frame(vid: &mut crate::video::Video, local: f64, size: (u32, u32), render_settings: &mut VideoRenderSettings, parent_prep_draw_data: &crate::video::PrepDrawData) -> image::RgbaImage {
+            let mut frame = super::DynamicImage::new_rgba8(size.0, size.1);
+            if let Some(prep_data) = vid.prep_draw(Self::outer_progress(vid, local), Some(parent_prep_draw_data)) {
+                vid.draw(&mut frame, prep_data, render_settings);
+            };
+            frame.into_rgba8()
+        }
+    }
     impl EffectT for ChangeTime {
         fn process_image(&mut self, progress: f64, vid: &mut crate::video::Video, img: &mut image::DynamicImage, render_settings: &mut VideoRenderSettings, parent_prep_draw_data: &crate::video::PrepDrawData) {
-            if let Some(prep_data) = vid.prep_draw(self.time.get_value(progress), Some(parent_prep_draw_data)) {
-                vid.draw(img, prep_data, render_settings);
+            let time = self.time.get_value(progress);
+            let local = (time - vid.set_start_frame) / vid.set_length;
+            if !(0.0..=1.0).contains(&local) { return; }
+            let (frames, block_size) = match &self.mode {
+                ChangeTime_Mode::Nearest => (Err(()), None),
+                ChangeTime_Mode::Blend { frames } => (Self::frame_count(*frames, vid, render_settings.wait_for_external_commands), None),
+                ChangeTime_Mode::MotionInterpolate { frames, block_size } => (Self::frame_count(*frames, vid, render_settings.wait_for_external_commands), Some(*block_size)),
+            };
+            let frames = match frames {
+                Ok(Some(v)) => v,
+                // Err: Nearest, or the frames are still being counted
+                frames => {
+                    if frames.is_ok() && !self.warned_frame_count {
+                        self.warned_frame_count = true;
+                        use crate::content::content::Content;
+                        vid.generic_content_data().project.diagnostics.report(
+                            "ChangeTime".to_string(),
+                            format!("Could not determine how many frames the video has, so {} mode draws it like {} mode. Set frames to the video's frame count.", self.mode.name(), ChangeTime_Mode::Nearest.name()),
+                        );
+                    }
+                    if let Some(prep_data) = vid.prep_draw(Self::outer_progress(vid, local), Some(parent_prep_draw_data)) {
+                        vid.draw(img, prep_data, render_settings);
+                    };
+                    return;
+                },
+            };
+            let pos = local * (frames - 1.0);
+            let index = pos.floor();
+            let factor = pos - index;
+            let size = (img.width(), img.height());
+            let frame = Self::draw_frame(vid, index / (frames - 1.0), size, render_settings, parent_prep_draw_data);
+            let frame = if factor > 1e-6 && index + 1.0 <= frames - 1.0 {
+                let next = Self::draw_frame(vid, (index + 1.0) / (frames - 1.0), size, render_settings, parent_prep_draw_data);
+                match block_size {
+                    None => blend_frames(&frame, &next, factor),
+                    Some(block_size) => motion_interpolate(&frame, &next, factor, block_size),
+                }
+            } else { frame };
+            let img = img.as_mut_rgba8().unwrap();
+            for (old, new) in img.pixels_mut().zip(frame.pixels()) {
+                if new.0[3] != 0 { crate::alpha_compositing::over(&mut old.0, &new.0, parent_prep_draw_data.linear_light); }
             };
         }
-        fn clone_no_caching(&self) -> super::Effect { super::Effect::new(Self::new(self.time.clone())) }
+        fn clone_no_caching(&self) -> super::Effect { super::Effect::new(Self::new(self.time.clone(), self.mode.clone())) }
         fn as_enum(self) -> EffectsEnum { EffectsEnum::ChangeTime(self) }
     }
+    #[allow(non_camel_case_types)]
+    #[derive(Clone)]
+    pub enum ChangeTime_Mode {
+        /// Draws the video at the remapped time. If the video has fewer frames than the output, frames are repeated, which looks choppy in slow motion.
+        Nearest,
+        /// Draws the two source frames closest to the remapped time and blends between them. frames is how many frames the video has (VidFromImagesInDirectory and FfmpegVid: 0 = detect automatically).
+        Blend { frames: f64, },
+        /// Like Blend, but first estimates how things moved between the two frames (in blocks of block_size pixels) and moves the pixels accordingly, so moving objects don't show up twice.
+        MotionInterpolate { frames: f64, block_size: u32, },
+    }
+    impl ChangeTime_Mode {
+        pub fn name(&self) -> &'static str {
+            match self { Self::Nearest => "nearest", Self::Blend { .. } => "blend", Self::MotionInterpolate { .. } => "motion", }
+        }
+    }
+
+    /// Mixes the two images: factor 0 returns a, factor 1 returns b.
+    fn blend_frames(a: &image::RgbaImage, b: &image::RgbaImage, factor: f64) -> image::RgbaImage {
+        let factor = factor as f32;
+        let mut out = a.clone();
+        for (o, b) in out.pixels_mut().zip(b.pixels()) {
+            for i in 0..4 { o.0[i] = (o.0[i] as f32 + (b.0[i] as f32 - o.0[i] as f32) * factor).round() as u8; }
+        };
+        out
+    }
+
+    /// A very simple optical flow: Finds a motion vector for each block by comparing a downscaled grayscale version of both frames, then samples a backwards and b forwards along that vector and blends the results.
+    fn motion_interpolate(a: &image::RgbaImage, b: &image::RgbaImage, factor: f64, block_size: u32) -> image::RgbaImage {
+        /// The motion search happens on images this many times smaller than the frames.
+        const SCALE: u32 = 4;
+        let (w, h) = a.dimensions();
+        let (sw, sh) = ((w / SCALE).max(1), (h / SCALE).max(1));
+        let small = |img: &image::RgbaImage| {
+            let mut out = vec![0.0f32; (sw * sh) as usize];
+            for y in 0..sh { for x in 0..sw {
+                let mut sum = 0.0;
+                let mut count = 0.0;
+                for py in (y * SCALE)..((y + 1) * SCALE).min(h) { for px in (x * SCALE)..((x + 1) * SCALE).min(w) {
+                    let [r, g, b, a] = img.get_pixel(px, py).0;
+                    sum += (0.299 * r as f32 + 0.587 * g as f32 + 0.114 * b as f32) * a as f32 / 255.0;
+                    count += 1.0;
+                } }
+                out[(x + y * sw) as usize] = if count > 0.0 { sum / count } else { 0.0 };
+            } }
+            out
+        };
+        let (small_a, small_b) = (small(a), small(b));
+        let block = (block_size / SCALE).max(2);
+        let radius = block as i32;
+        let (blocks_x, blocks_y) = ((sw + block - 1) / block, (sh + block - 1) / block);
+        let mut vectors = vec![(0.0f64, 0.0f64); (blocks_x * blocks_y) as usize];
+        for by in 0..blocks_y { for bx in 0..blocks_x {
+            // mean absolute difference between the block in a and the block moved by (dx, dy) in b
+            let difference = |dx: i32, dy: i32| {
+                let mut sum = 0.0;
+                let mut count = 0.0;
+                for y in (by * block)..((by + 1) * block).min(sh) { for x in (bx * block)..((bx + 1) * block).min(sw) {
+                    let (x2, y2) = (x as i32 + dx, y as i32 + dy);
+                    if x2 >= 0 && y2 >= 0 && (x2 as u32) < sw && (y2 as u32) < sh {
+                        sum += (small_a[(x + y * sw) as usize] - small_b[(x2 as u32 + y2 as u32 * sw) as usize]).abs();
+                        count += 1.0;
+                    }
+                } }
+                if count > 0.0 { sum / count } else { f32::INFINITY }
+            };
+            let mut best = (difference(0, 0), 0, 0);
+            for dy in -radius..=radius { for dx in -radius..=radius {
+                let d = difference(dx, dy);
+                // strictly less, so that not moving is preferred if it's just as good
+                if d < best.0 { best = (d, dx, dy); }
+            } }
+            vectors[(bx + by * blocks_x) as usize] = ((best.1 * SCALE as i32) as f64, (best.2 * SCALE as i32) as f64);
+        } }
+        let sample = |img: &image::RgbaImage, x: f64, y: f64| img.get_pixel(x.round().max(0.0).min((w - 1) as f64) as u32, y.round().max(0.0).min((h - 1) as f64) as u32).0;
+        let inv_factor = 1.0 - factor;
+        let factor_f32 = factor as f32;
+        let mut out = image::RgbaImage::new(w, h);
+        for (x, y, px) in out.enumerate_pixels_mut() {
+            let (bx, by) = ((x / SCALE / block).min(blocks_x - 1), (y / SCALE / block).min(blocks_y - 1));
+            let (vx, vy) = vectors[(bx + by * blocks_x) as usize];
+            let pa = sample(a, x as f64 - factor * vx, y as f64 - factor * vy);
+            let pb = sample(b, x as f64 + inv_factor * vx, y as f64 + inv_factor * vy);
+            for i in 0..4 { px.0[i] = (pa[i] as f32 + (pb[i] as f32 - pa[i] as f32) * factor_f32).round() as u8; }
+        };
+        out
+    }
 
 
 
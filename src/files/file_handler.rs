@@ -6,15 +6,23 @@ const VERSION_CURRENT_MAJOR: u32 = 0;
 const VERSION_CURRENT_MINOR: u32 = 0;
 
 pub fn read_from_file(file_name: &PathBuf) -> io::Result<Result<Project, CreateVideoFromFileError>> {
-    let file_contents = std::fs::read_to_string(&file_name)?;
+    Ok(read_from_string(&std::fs::read_to_string(&file_name)?, file_name))
+}
+
+/// Like read_from_file, but every {{name}} or {{name=default}} in the file is replaced with the value of the parameter with that name (or with default, if there is no such parameter). This is how projects that are used inside other projects can be customized. Values are escaped, so they always end up as plain text.
+pub fn read_from_file_with_parameters(file_name: &PathBuf, parameters: &[(String, String)]) -> io::Result<Result<Project, CreateVideoFromFileError>> {
+    Ok(read_from_string(&apply_parameters(&std::fs::read_to_string(&file_name)?, parameters), file_name))
+}
+
+fn read_from_string(file_contents: &str, file_name: &PathBuf) -> Result<Project, CreateVideoFromFileError> {
     let mut file_content_lines = file_contents.lines();
     if let Some(first_line) = file_content_lines.next() {
         let first_line_split = Vec::from_iter(first_line.splitn(2, '.'));
-        if first_line_split.len() < 2 { return Ok(Err(CreateVideoFromFileError::CouldNotDecodeVersion)); };
-        let version_major = match first_line_split[0].parse::<u32>() { Ok(v) => v, Err(err) => return Ok(Err(CreateVideoFromFileError::CouldNotDecodeMajorVersion(err))),};
-        let version_minor = match first_line_split[1].parse::<u32>() { Ok(v) => v, Err(err) => return Ok(Err(CreateVideoFromFileError::CouldNotDecodeMinorVersion(err))),};
-        if version_major > VERSION_CURRENT_MAJOR { return Ok(Err(CreateVideoFromFileError::VersionTooNewMajor)); };
-        if version_major == VERSION_CURRENT_MAJOR && version_minor > VERSION_CURRENT_MINOR { return Ok(Err(CreateVideoFromFileError::VersionTooNewMinor)); };
+        if first_line_split.len() < 2 { return Err(CreateVideoFromFileError::CouldNotDecodeVersion); };
+        let version_major = match first_line_split[0].parse::<u32>() { Ok(v) => v, Err(err) => return Err(CreateVideoFromFileError::CouldNotDecodeMajorVersion(err)),};
+        let version_minor = match first_line_split[1].parse::<u32>() { Ok(v) => v, Err(err) => return Err(CreateVideoFromFileError::CouldNotDecodeMinorVersion(err)),};
+        if version_major > VERSION_CURRENT_MAJOR { return Err(CreateVideoFromFileError::VersionTooNewMajor); };
+        if version_major == VERSION_CURRENT_MAJOR && version_minor > VERSION_CURRENT_MINOR { return Err(CreateVideoFromFileError::VersionTooNewMinor); };
         //
         let mut file_content_final = String::new();
         for line in file_content_lines {
@@ -44,17 +52,37 @@ pub fn read_from_file(file_name: &PathBuf) -> io::Result<Result<Project, CreateV
         //
         if version_major == VERSION_CURRENT_MAJOR {
             match super::parser_v0::parse(file_content_final.as_str(), file_name) {
-                Ok(v) => Ok(Ok(v)),
-                Err(err) => Ok(Err(CreateVideoFromFileError::ParseError { file_version: (version_major, version_minor), parser_version: (VERSION_CURRENT_MAJOR, VERSION_CURRENT_MINOR), parser_error: err, })),
+                Ok(v) => Ok(v),
+                Err(err) => Err(CreateVideoFromFileError::ParseError { file_version: (version_major, version_minor), parser_version: (VERSION_CURRENT_MAJOR, VERSION_CURRENT_MINOR), parser_error: err, }),
             }
         } else {
-            Ok(Err(CreateVideoFromFileError::NoParserForVersion((version_major, version_minor))))
+            Err(CreateVideoFromFileError::NoParserForVersion((version_major, version_minor)))
         }
     } else {
-        Ok(Err(CreateVideoFromFileError::NoFirstLine))
+        Err(CreateVideoFromFileError::NoFirstLine)
     }
 }
 
+/// Replaces {{name}} and {{name=default}}. Placeholders without a matching parameter or default are left unchanged. Parameter values are escaped like parse_string expects them, so a value can't end a string early or add other syntax.
+fn apply_parameters(file_contents: &str, parameters: &[(String, String)]) -> String {
+    let mut out = String::with_capacity(file_contents.len());
+    let mut rest = file_contents;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find("}}") { Some(v) => v, None => break, };
+        let placeholder = &rest[2..end];
+        let (name, default) = match placeholder.split_once('=') { Some((n, d)) => (n, Some(d)), None => (placeholder, None), };
+        match parameters.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str()).or(default) {
+            Some(value) => out.push_str(&super::parser_v0::escape_string(value)),
+            None => out.push_str(&rest[..end + 2]),
+        }
+        rest = &rest[end + 2..];
+    }
+    out.push_str(rest);
+    out
+}
+
 pub enum CreateVideoFromFileError {
     NoFirstLine,
     /// The first line did not contain the . required by the major.minor version format
@@ -94,4 +122,22 @@ impl Debug for CreateVideoFromFileError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(self.to_string().as_str())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parameters_are_escaped() {
+        let parameters = vec![("text".to_string(), "a\\!b;\nc".to_string())];
+        assert_eq!(
+            apply_parameters("s{{text}}\\!{{size=0.5}};{{missing}}", &parameters),
+            "sa\\\\!b;\\nc\\!0.5;{{missing}}"
+        );
+        let mut chars = "a\\\\!b;\\nc\\!".chars();
+        match super::super::parser_v0::parse_string(&mut chars) {
+            Ok(v) => assert_eq!(v, "a\\!b;\nc"),
+            Err(e) => panic!("{e}"),
+        }
+    }
+}
@@ -0,0 +1,383 @@
+use std::{fmt::Display, io, path::PathBuf};
+
+use crate::curve::{Keyframe, KeyframeInterpolation};
+
+/// Reads keyframes from a .csv or .json file. The returned keyframes are sorted by time.
+///
+/// csv: one keyframe per line, "time,value" or "time,value,interpolation". A first line that doesn't start with a number is treated as a header and skipped.
+///
+/// json: an array of keyframes, either as objects ({"time": 0.5, "value": 1, "interpolation": "smooth"}, interpolation is optional) or as [time, value] arrays. The array may also be wrapped in an object: {"keyframes": [...]}.
+///
+/// If no interpolation is specified, linear is used.
+pub fn import(path: &PathBuf) -> Result<Vec<Keyframe>, KeyframesError> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut keyframes = match Format::from_path(path)? {
+        Format::Csv => parse_csv(&contents)?,
+        Format::Json => parse_json(&contents)?,
+    };
+    keyframes.sort_by(|a, b| a.time.total_cmp(&b.time));
+    Ok(keyframes)
+}
+
+/// Writes keyframes to a .csv or .json file (in the format import() expects).
+pub fn export(path: &PathBuf, keyframes: &[Keyframe]) -> Result<(), KeyframesError> {
+    let contents = match Format::from_path(path)? {
+        Format::Csv => to_csv(keyframes),
+        Format::Json => to_json(keyframes),
+    };
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+enum Format {
+    Csv,
+    Json,
+}
+impl Format {
+    fn from_path(path: &PathBuf) -> Result<Self, KeyframesError> {
+        match path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("csv") => Ok(Self::Csv),
+            Some("json") => Ok(Self::Json),
+            _ => Err(KeyframesError::UnknownFormat(path.clone())),
+        }
+    }
+}
+
+pub fn parse_csv(str: &str) -> Result<Vec<Keyframe>, KeyframesError> {
+    let mut keyframes = Vec::new();
+    for (line_index, line) in str.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let mut fields = line.split(',').map(|f| f.trim());
+        let time = fields.next().unwrap_or("");
+        let time = match time.parse() {
+            Ok(v) => v,
+            // header
+            Err(_) if line_index == 0 => continue,
+            Err(_) => {
+                return Err(KeyframesError::Csv(
+                    line_index + 1,
+                    format!("time '{time}' is not a number"),
+                ))
+            }
+        };
+        let value = match fields.next() {
+            Some(value) => match value.parse() {
+                Ok(v) => v,
+                Err(_) => {
+                    return Err(KeyframesError::Csv(
+                        line_index + 1,
+                        format!("value '{value}' is not a number"),
+                    ))
+                }
+            },
+            None => {
+                return Err(KeyframesError::Csv(
+                    line_index + 1,
+                    format!("expected time,value[,interpolation]"),
+                ))
+            }
+        };
+        let interpolation = match fields.next() {
+            Some(name) if !name.is_empty() => match KeyframeInterpolation::from_name(name) {
+                Some(v) => v,
+                None => {
+                    return Err(KeyframesError::Csv(
+                        line_index + 1,
+                        format!("'{name}' is not an interpolation mode (hold, linear, smooth)"),
+                    ))
+                }
+            },
+            _ => KeyframeInterpolation::Linear,
+        };
+        keyframes.push(Keyframe {
+            time,
+            value,
+            interpolation,
+        });
+    }
+    Ok(keyframes)
+}
+
+pub fn parse_json(str: &str) -> Result<Vec<Keyframe>, KeyframesError> {
+    let json: serde_json::Value = match serde_json::from_str(str) {
+        Ok(v) => v,
+        Err(e) => return Err(KeyframesError::Json(e.to_string())),
+    };
+    let array = match json.get("keyframes").unwrap_or(&json).as_array() {
+        Some(v) => v,
+        None => {
+            return Err(KeyframesError::Json(format!(
+                "expected an array of keyframes or an object with a 'keyframes' array"
+            )))
+        }
+    };
+    let mut keyframes = Vec::with_capacity(array.len());
+    for (index, keyframe) in array.iter().enumerate() {
+        let (time, value, interpolation) = match keyframe {
+            serde_json::Value::Array(a) => (a.get(0), a.get(1), a.get(2)),
+            serde_json::Value::Object(o) => (o.get("time"), o.get("value"), o.get("interpolation")),
+            _ => {
+                return Err(KeyframesError::Json(format!(
+                    "keyframe {index} is neither an object nor an array"
+                )))
+            }
+        };
+        let (time, value) = match (
+            time.and_then(|v| v.as_f64()),
+            value.and_then(|v| v.as_f64()),
+        ) {
+            (Some(t), Some(v)) => (t, v),
+            _ => {
+                return Err(KeyframesError::Json(format!(
+                    "keyframe {index} needs a numeric time and value"
+                )))
+            }
+        };
+        let interpolation = match interpolation {
+            None | Some(serde_json::Value::Null) => KeyframeInterpolation::Linear,
+            Some(name) => match name.as_str().and_then(KeyframeInterpolation::from_name) {
+                Some(v) => v,
+                None => {
+                    return Err(KeyframesError::Json(format!(
+                        "keyframe {index}: {name} is not an interpolation mode (hold, linear, smooth)"
+                    )))
+                }
+            },
+        };
+        keyframes.push(Keyframe {
+            time,
+            value,
+            interpolation,
+        });
+    }
+    Ok(keyframes)
+}
+
+pub fn to_csv(keyframes: &[Keyframe]) -> String {
+    let mut out = String::from("time,value,interpolation\n");
+    for k in keyframes {
+        out.push_str(&format!("{},{},{}\n", k.time, k.value, k.interpolation.name()));
+    }
+    out
+}
+
+pub fn to_json(keyframes: &[Keyframe]) -> String {
+    let array = keyframes
+        .iter()
+        .map(|k| {
+            serde_json::json!({
+                "time": k.time,
+                "value": k.value,
+                "interpolation": k.interpolation.name(),
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::json!({ "keyframes": serde_json::Value::Array(array) }))
+        .unwrap()
+}
+
+pub enum KeyframesError {
+    Io(io::Error),
+    /// The file's extension was neither .csv nor .json
+    UnknownFormat(PathBuf),
+    /// Line number (starting at 1) and message
+    Csv(usize, String),
+    Json(String),
+}
+impl From<io::Error> for KeyframesError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl Display for KeyframesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "IO error: {e}"),
+            Self::UnknownFormat(p) => write!(
+                f,
+                "Can't tell the format of '{}'. Use a file ending in .csv or .json.",
+                p.display()
+            ),
+            Self::Csv(line, e) => write!(f, "csv, line {line}: {e}"),
+            Self::Json(e) => write!(f, "json: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn simple(keyframes: &[Keyframe]) -> Vec<(f64, f64, &'static str)> {
+        keyframes
+            .iter()
+            .map(|k| (k.time, k.value, k.interpolation.name()))
+            .collect()
+    }
+
+    #[test]
+    fn csv_with_header_and_default_interpolation() {
+        let csv = "time,value,interpolation\n0, 1\n0.5,2,hold\n\n1,-3.5,smooth\n";
+        let keyframes = match parse_csv(csv) {
+            Ok(v) => v,
+            Err(e) => panic!("{e}"),
+        };
+        assert_eq!(
+            simple(&keyframes),
+            vec![
+                (0.0, 1.0, "linear"),
+                (0.5, 2.0, "hold"),
+                (1.0, -3.5, "smooth")
+            ]
+        );
+    }
+
+    #[test]
+    fn csv_errors_have_line_numbers() {
+        match parse_csv("0,1\nabc,2\n") {
+            Err(KeyframesError::Csv(2, _)) => (),
+            _ => panic!("expected an error on line 2"),
+        }
+        match parse_csv("0,1\n1,2,bounce\n") {
+            Err(KeyframesError::Csv(2, _)) => (),
+            _ => panic!("expected an error on line 2"),
+        }
+        match parse_csv("0\n") {
+            Err(KeyframesError::Csv(1, _)) => (),
+            _ => panic!("expected an error on line 1"),
+        }
+    }
+
+    #[test]
+    fn json_objects_arrays_and_wrapper() {
+        let expected = vec![(0.0, 1.0, "linear"), (1.0, 0.25, "hold")];
+        for json in [
+            r#"[{"time": 0, "value": 1}, {"time": 1, "value": 0.25, "interpolation": "hold"}]"#,
+            r#"[[0, 1], [1, 0.25, "hold"]]"#,
+            r#"{"keyframes": [[0, 1], {"time": 1, "value": 0.25, "interpolation": "hold"}]}"#,
+        ] {
+            match parse_json(json) {
+                Ok(keyframes) => assert_eq!(simple(&keyframes), expected, "{json}"),
+                Err(e) => panic!("{json}: {e}"),
+            }
+        }
+    }
+
+    #[test]
+    fn json_errors() {
+        assert!(parse_json("not json").is_err());
+        assert!(parse_json(r#"{"frames": []}"#).is_err());
+        assert!(parse_json(r#"[{"time": 0}]"#).is_err());
+        assert!(parse_json(r#"[[0, 1, "bounce"]]"#).is_err());
+    }
+
+    #[test]
+    fn export_then_import_round_trips() {
+        let keyframes = vec![
+            Keyframe {
+                time: 0.0,
+                value: 0.125,
+                interpolation: KeyframeInterpolation::Smooth,
+            },
+            Keyframe {
+                time: 0.3,
+                value: -2.0,
+                interpolation: KeyframeInterpolation::Hold,
+            },
+            Keyframe {
+                time: 1.0,
+                value: 7.5,
+                interpolation: KeyframeInterpolation::Linear,
+            },
+        ];
+        let dir = std::env::temp_dir();
+        for name in ["keyframes_test.csv", "keyframes_test.json"] {
+            let path = dir.join(format!("{}_{name}", std::process::id()));
+            if let Err(e) = export(&path, &keyframes) {
+                panic!("{e}");
+            }
+            let imported = import(&path);
+            _ = std::fs::remove_file(&path);
+            match imported {
+                Ok(imported) => assert_eq!(simple(&imported), simple(&keyframes), "{name}"),
+                Err(e) => panic!("{name}: {e}"),
+            }
+        }
+        assert!(matches!(
+            export(&dir.join("keyframes.txt"), &keyframes),
+            Err(KeyframesError::UnknownFormat(_))
+        ));
+    }
+
+    #[test]
+    fn baked_curve_can_be_exported() {
+        let curve: crate::curve::Curve = crate::curve::CurveData::Linear(
+            crate::curve::CurveData::Constant(0.0).into(),
+            crate::curve::CurveData::Constant(2.0).into(),
+        )
+        .into();
+        let baked = curve.bake(5);
+        assert_eq!(
+            simple(&baked),
+            vec![
+                (0.0, 0.0, "linear"),
+                (0.25, 0.5, "linear"),
+                (0.5, 1.0, "linear"),
+                (0.75, 1.5, "linear"),
+                (1.0, 2.0, "linear"),
+            ]
+        );
+        match parse_csv(&to_csv(&baked)) {
+            Ok(v) => assert_eq!(simple(&v), simple(&baked)),
+            Err(e) => panic!("{e}"),
+        }
+    }
+
+    #[test]
+    fn every_exported_curve_can_be_imported() {
+        let project = crate::project::Project::new(Default::default());
+        let mut vid = match crate::files::parser_v0::parse_vid(
+            &mut "video:Solid:r1;0;0;1;:".chars(),
+            crate::content::content::GenericContentData::new(project.clone()),
+        ) {
+            Ok(v) => v,
+            Err(e) => panic!("{e}"),
+        };
+        let keyframes = |value| {
+            crate::curve::Curve::from(crate::curve::CurveData::Keyframes(vec![Keyframe {
+                time: 0.0,
+                value,
+                interpolation: KeyframeInterpolation::Linear,
+            }]))
+        };
+        let names: Vec<_> = vid.named_curves().iter().map(|(name, _)| *name).collect();
+        for (i, (name, curve)) in vid.named_curves_mut().into_iter().enumerate() {
+            assert_eq!(name, names[i]);
+            *curve = keyframes(i as f64);
+        }
+        for (i, (_, curve)) in vid.named_curves().into_iter().enumerate() {
+            assert_eq!(curve.get_value(0.5), i as f64);
+        }
+        // curves that use a shared curve change when it is replaced
+        vid.opacity.to_shared(&project.shared_curves);
+        let id = match &vid.opacity {
+            crate::curve::Curve::Shared(id, _) => *id,
+            _ => panic!("the curve is not shared"),
+        };
+        assert!(project
+            .shared_curves
+            .set(id, crate::curve::CurveData::Constant(0.25)));
+        assert!(!project
+            .shared_curves
+            .set(id + 1, crate::curve::CurveData::Constant(0.0)));
+        vid.update_shared_curves(&project.shared_curves);
+        assert_eq!(vid.opacity.get_value(0.5), 0.25);
+    }
+}
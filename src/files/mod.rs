@@ -1,6 +1,8 @@
 pub mod file_handler;
 pub mod frames_exporter;
+pub mod keyframes;
 
 pub mod loadsave_abstract;
 pub mod parser_general;
 pub mod parser_v0;
+pub mod subtitles;
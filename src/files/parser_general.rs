@@ -4,7 +4,7 @@ pub enum ParserError {
     /// File ended unexpectedly.
     UnexpectedEOF,
 
-    /// Identifier was not 'proj' or 'vid'.
+    /// Identifier was not 'proj', 'vid' or 'def'.
     InvalidIdentifier(String),
     /// Project- or Video info was defined twice.
     DoubleDefinitionOf(String),
@@ -24,15 +24,39 @@ pub enum ParserError {
 
     /// Attempted to load directory full of image frames, but failed to find/read this directory. Maybe missing an external disk and/or permissions?
     DirectoryWithImagesNotFound(PathBuf, io::Error),
+    /// The frames of an image sequence could not be listed (no frames, gaps in the numbering, ...).
+    InvalidImageSequence(PathBuf, crate::content::input_video::ImageSequenceError),
     
     /// Only =, *, and L are allowed. For None, do not include this option in the save file at all (None is the default value).
     InvalidCompositingMode(char),
+    /// After compositing:b, this character does not identify a blend mode.
+    InvalidBlendMode(char),
+    /// After linear:, only + and - are allowed.
+    InvalidLinearLight(char),
     
     /// Attempted to parse a curve, but found an unexpected character.
     InvalidCurveIdentifier(char),
 
+    /// A keyframe's interpolation was not h (hold), l (linear) or s (smooth).
+    InvalidKeyframeInterpolation(char),
+    /// Failed to import keyframes from a csv or json file.
+    KeyframeImportError(PathBuf, super::keyframes::KeyframesError),
+
     /// Invalid type for a text
     InvalidTextType(char),
+    /// A character in a text's layout options (L...) was not one of the allowed ones.
+    InvalidTextLayout(char),
+    InvalidTextAnimator(char),
+    /// The character before a shape's data was not one of the shape kinds or options.
+    InvalidShapeKind(char),
+    /// A shape's path data (and why it could not be parsed).
+    InvalidShapePath(String, String),
+    /// A gradient's kind was not l (linear), r (radial) or c (conic).
+    InvalidGradientKind(char),
+    /// The fit mode (s, c, v, o or t) or crop (- or C) of an image was invalid.
+    InvalidImageFit(char),
+    /// An animated image's timing was not s (stretch), n (native) or l (native, looping).
+    InvalidAnimationTiming(char),
     VideoFileFailedToParseStartOrEndFrame(String),
 
     /// This name does not identify an effect.
@@ -52,18 +76,30 @@ impl Display for ParserError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(match self {
             Self::UnexpectedEOF => format!("Unexpected EOF (end of file)!"),
-            Self::InvalidIdentifier(i) => format!("Invalid identifier: '{i}' was not 'proj' or 'vid'."),
+            Self::InvalidIdentifier(i) => format!("Invalid identifier: '{i}' was not 'proj', 'vid' or 'def'."),
             Self::DoubleDefinitionOf(i) => format!("Identifier '{i}' was defined twice!"),
             Self::MissingIdentifier(i) => format!("Identifier '{i}' was never defined, but is required!"),
-            Self::InvalidVideoType(t) => format!("Video type '{t}' does not exist! Try List, WithEffect, Image, or VidFromImagesInDirectory"),
-            Self::InvalidVideoInfoKey(k) => format!("VideoInfoKey '{k}' not permitted! Try pos, start, length, video or compositing."),
+            Self::InvalidVideoType(t) => format!("Video type '{t}' does not exist! Try List, WithEffect, Image, AnimatedImage, Svg, Text, Project, Instance, Shape, Solid, Gradient, or VidFromImagesInDirectory"),
+            Self::InvalidVideoInfoKey(k) => format!("VideoInfoKey '{k}' not permitted! Try pos, start, length, video, fit, opacity, compositing, linear, transform or label."),
             Self::MissingVideoInfoKey(k) => format!("VideoInfoKey '{k}' was missing but is required! Consider adding it."),
             Self::DirectoryWithImagesNotFound(d, e) => format!("Directory with images was not found. Dir: \"{}\", Err: \"{e}\"", d.display()),
-            Self::InvalidCompositingMode(i) => format!("Invalid compositing method identifier '{i}'. Only _ (ignore), = (opaque), | (direct) and * (supports transparency) are allowed."),
-            Self::InvalidCurveIdentifier(c) => format!("Found unexpected character '{c}' when parsing Curve. Allowed are only 0-9, '.', '/', 's', and '#'."),
-            Self::InvalidTextType(c) => format!("Found unexpected text type character '{c}'. Use 's' for static text."),
+            Self::InvalidImageSequence(d, e) => format!("Invalid image sequence \"{}\": {e}", d.display()),
+            Self::InvalidCompositingMode(i) => format!("Invalid compositing method identifier '{i}'. Only _ (ignore), = (opaque), | (direct), * (supports transparency) and b[blend mode] are allowed."),
+            Self::InvalidLinearLight(i) => format!("Invalid value '{i}' for linear. Only + (mix colors in linear light) and - (mix colors in sRGB) are allowed."),
+            Self::InvalidBlendMode(i) => format!("Invalid blend mode '{i}'. Try m, s, o, d, l, D, B, h, S, x, e, a, u, H, T, C or L."),
+            Self::InvalidCurveIdentifier(c) => format!("Found unexpected character '{c}' when parsing Curve. Allowed are only 0-9, '-', '.', '/', 's', 'b', '#', '!', 'a', 'm', '<', '>', 'c', 'r', 'l', 'p', 'h', 'q', '~', 'k' and 'K'."),
+            Self::InvalidKeyframeInterpolation(c) => format!("Found unexpected keyframe interpolation '{c}'. Use h (hold), l (linear) or s (smooth)."),
+            Self::KeyframeImportError(p, e) => format!("Failed to import keyframes from \"{}\": {e}", p.display()),
+            Self::InvalidTextType(c) => format!("Found unexpected text type character '{c}'. Use 's' for static text, '!' for text from a program, or one of the options 'L' (layout), 'O' (outline), 'S' (shadow), 'B' (background), 'M' (markup) or 'A' (animation)."),
+            Self::InvalidTextLayout(c) => format!("Found unexpected character '{c}' in text layout. Expected L[align: -lcrj][vertical align: -tcb][wrap: w/n][fit: s / f[curve] / o[curve]][line height][letter spacing]."),
+            Self::InvalidTextAnimator(c) => format!("Found unexpected text animation unit '{c}'. Use 'c' for characters, 'w' for words or 'l' for lines."),
+            Self::InvalidShapeKind(c) => format!("Found unexpected shape kind character '{c}'. Use 'r' (rectangle), 'e' (ellipse), 'p' (polygon), 'd' (path), or one of the options 'F' (fill) or 'S' (stroke)."),
+            Self::InvalidShapePath(d, e) => format!("Invalid path data \"{d}\": {e}"),
+            Self::InvalidGradientKind(c) => format!("Found unexpected gradient kind '{c}'. Use 'l' (linear), 'r' (radial) or 'c' (conic)."),
+            Self::InvalidImageFit(c) => format!("Found unexpected character '{c}' in fit. Expected fit:[mode: s (stretch), c (contain), v (cover), o (original size), t (tile)][crop: - (none) or C[x][y][w][h]]."),
+            Self::InvalidAnimationTiming(c) => format!("Found unexpected animation timing '{c}'. Use 's' (stretch to the video's length), 'n' (native speed) or 'l' (native speed, looping)."),
             Self::VideoFileFailedToParseStartOrEndFrame(t) => format!("Failed to parse a video's start and end frames (crop): {t}"),
-            Self::UnknownEffect(e) => format!("Effect '{e}' does not exist! Try None (placeholder), BlackWhite, Shake, ChangeTime, Blur, ColorAdjust or ColorKey."),
+            Self::UnknownEffect(e) => format!("Effect '{e}' does not exist! Try None (placeholder), BlackWhite, Shake, ChangeTime, ChangeTimeBlend, ChangeTimeMotion, Reverse, Freeze, Blur, ColorAdjust or ColorKey."),
             Self::EffectParseError { effect_identifier, custom_error } => format!("Failed to parse effect '{effect_identifier}', Err: \"{custom_error}\""),
             Self::ParseIntError(i, e) => format!("Failed to parse '{i}' into an int. Err: {e}"),
             Self::ParseFloatError(i, e) => format!("Failed to parse '{i}' into a float. Err: {e}"),
@@ -56,6 +56,7 @@ pub fn parse(str: &str, path: &PathBuf) -> Result<Project, ParserError> {
     // return
     match (proj, vid) {
         (Some(mut proj), Some(vid)) => {
+            proj.shared_videos.sync(&vid);
             proj.add_vid(Arc::new(Mutex::new(vid)));
             Ok(proj)
         }
@@ -83,6 +84,11 @@ pub fn parse_vid(
     let mut length = None;
     let mut video = None;
     let mut compositing = None;
+    let mut fit = None;
+    let mut opacity = None;
+    let mut linear_light = None;
+    let mut transform = None;
+    let mut label = None;
     'before_return: loop {
         let mut identifier = String::new();
         loop {
@@ -131,10 +137,39 @@ pub fn parse_vid(
                     Some('=') => CompositingMethod::Opaque,
                     Some('|') => CompositingMethod::Direct, // "Pipe"
                     Some('*') => CompositingMethod::TransparencySupport,
+                    Some('b') => match chars.next() {
+                        Some(ch) => match crate::blend_mode::BlendMode::from_char(ch) {
+                            Some(mode) => CompositingMethod::Blend(mode),
+                            None => return Err(ParserError::InvalidBlendMode(ch)),
+                        },
+                        None => return Err(ParserError::UnexpectedEOF),
+                    },
                     Some(ch) => return Err(ParserError::InvalidCompositingMode(ch)),
                     None => return Err(ParserError::UnexpectedEOF),
                 })
             }
+            "fit" => fit = Some(parse_image_fit(chars)?),
+            "opacity" => opacity = Some(parse_vid_curve(chars)?),
+            "linear" => {
+                linear_light = Some(match chars.next() {
+                    Some('+') => true,
+                    Some('-') => false,
+                    Some(ch) => return Err(ParserError::InvalidLinearLight(ch)),
+                    None => return Err(ParserError::UnexpectedEOF),
+                })
+            }
+            "transform" => {
+                transform = Some(crate::transform::Transform {
+                    rotation: parse_vid_curve(chars)?,
+                    scale_x: parse_vid_curve(chars)?,
+                    scale_y: parse_vid_curve(chars)?,
+                    anchor_x: parse_vid_curve(chars)?,
+                    anchor_y: parse_vid_curve(chars)?,
+                    skew_x: parse_vid_curve(chars)?,
+                    skew_y: parse_vid_curve(chars)?,
+                })
+            }
+            "label" => label = Some(parse_string(chars)?),
             _ => return Err(ParserError::InvalidVideoInfoKey(identifier)),
         };
     }
@@ -162,6 +197,23 @@ pub fn parse_vid(
         (/*Some(*/ pos, /*Some(*/ start, /*Some(*/ length, Some(video)) => Ok({
             let mut vid = Video::new(pos, start, length, video);
             vid.compositing = compositing;
+            vid.linear_light = linear_light;
+            vid.label = label;
+            if let Some(opacity) = opacity {
+                vid.opacity = opacity;
+            }
+            if let Some(transform) = transform {
+                vid.transform = transform;
+            }
+            if let Some(fit) = fit {
+                match &mut vid.video.vt {
+                    VideoTypeEnum::Image(img) => img.fit = fit,
+                    VideoTypeEnum::Raw(vid) => vid.fit = fit,
+                    VideoTypeEnum::Ffmpeg(vid) => vid.fit = fit,
+                    VideoTypeEnum::AnimatedImage(img) => img.fit = fit,
+                    _ => (), // other videos don't have a fit mode
+                }
+            }
             vid
         }),
         // (None, _, _, _) => Err(ParserError::MissingVideoInfoKey(format!("pos"))),
@@ -236,9 +288,20 @@ pub fn parse_vid_video(
                             shakes_count_x: parse_vid_f64(chars)?,
                             shakes_count_y: parse_vid_f64(chars)?,
                         }),
-                        "ChangeTime" => Effect::new(effects::ChangeTime {
-                            time: parse_vid_curve(chars)?,
-                        }),
+                        "ChangeTime" => Effect::new(effects::ChangeTime::new(
+                            parse_vid_curve(chars)?,
+                            effects::ChangeTime_Mode::Nearest,
+                        )),
+                        "ChangeTimeBlend" => {
+                            let mode = effects::ChangeTime_Mode::Blend { frames: parse_vid_f64(chars)?, };
+                            Effect::new(effects::ChangeTime::new(parse_vid_curve(chars)?, mode))
+                        },
+                        "ChangeTimeMotion" => {
+                            let mode = effects::ChangeTime_Mode::MotionInterpolate { frames: parse_vid_f64(chars)?, block_size: parse_vid_int(chars)?, };
+                            Effect::new(effects::ChangeTime::new(parse_vid_curve(chars)?, mode))
+                        },
+                        "Reverse" => Effect::new(effects::ChangeTime::reverse()),
+                        "Freeze" => Effect::new(effects::ChangeTime::freeze(parse_vid_f64(chars)?)),
                         "Blur" => Effect::new(effects::Blur {
                             mode: {
                                 let mut identifier = String::new();
@@ -297,8 +360,12 @@ pub fn parse_vid_video(
                 let font_path = parse_path(chars)?;
                 let font_index = parse_vid_int(chars)?;
                 let color = crate::types::Color::parse(chars)?;
-                let mut text = crate::content::text::Text::new(
-                    match chars.next() {
+                let mut layout = None;
+                let mut style = crate::content::text::TextStyle::default();
+                let mut markup = false;
+                let mut animator = None;
+                let text_type = loop {
+                    break match chars.next() {
                         Some('s') => crate::content::text::TextType::Static(parse_string(chars)?),
                         Some('!') => {
                             match chars.next() {
@@ -315,27 +382,78 @@ pub fn parse_vid_video(
                                 None => return Err(ParserError::UnexpectedEOF),
                             }
                         }
+                        // options (before the text type)
+                        Some('L') => {
+                            layout = Some(parse_text_layout(chars)?);
+                            continue;
+                        }
+                        Some('O') => {
+                            style.outline = Some(crate::content::text::TextOutline {
+                                width: parse_vid_curve(chars)?,
+                                color: crate::types::Color::parse(chars)?,
+                            });
+                            continue;
+                        }
+                        Some('S') => {
+                            style.shadow = Some(crate::content::text::TextShadow {
+                                offset_x: parse_vid_curve(chars)?,
+                                offset_y: parse_vid_curve(chars)?,
+                                blur: parse_vid_curve(chars)?,
+                                color: crate::types::Color::parse(chars)?,
+                            });
+                            continue;
+                        }
+                        Some('A') => {
+                            animator = Some(parse_text_animator(chars)?);
+                            continue;
+                        }
+                        Some('M') => {
+                            markup = true;
+                            continue;
+                        }
+                        Some('B') => {
+                            style.background = Some(crate::content::text::TextBackground {
+                                padding: parse_vid_curve(chars)?,
+                                corner_radius: parse_vid_curve(chars)?,
+                                color: crate::types::Color::parse(chars)?,
+                            });
+                            continue;
+                        }
                         Some(c) => return Err(ParserError::InvalidTextType(c)),
                         None => return Err(ParserError::UnexpectedEOF),
-                    },
-                    generic_content_data.reset(),
-                );
+                    };
+                };
+                let mut text =
+                    crate::content::text::Text::new(text_type, generic_content_data.reset());
+                if let Some(layout) = layout {
+                    text.set_layout(layout);
+                }
+                text.set_style(style);
+                text.set_markup(markup);
+                text.set_animator(animator);
                 text.set_color(color);
-                if let Ok(file) = std::fs::read(&font_path) {
-                    if let Some(font) = rusttype::Font::try_from_vec_and_index(file, font_index) {
-                        text.set_font(font.into());
-                    } else {
-                        println!(
-                            "Font '{}' could not be parsed (using the ttf_parser crate)",
-                            font_path.to_string_lossy().as_ref()
-                        );
-                    }
+                let font_path = font_path.to_string_lossy();
+                // parse_path always starts with a /, which isn't part of a font's @name
+                let font_name = match font_path.strip_prefix('/') {
+                    Some(name) if name.starts_with('@') => name,
+                    _ => font_path.as_ref(),
+                };
+                let mut font_manager = crate::font_manager::get();
+                if let Some(font) = font_manager.get_font(font_name, font_index) {
+                    text.set_font(font);
+                } else if let Some(font) = font_manager.default_font() {
+                    generic_content_data.project.diagnostics.report(
+                        font_name.to_string(),
+                        "Font not found, the default font is used instead.".to_string(),
+                    );
+                    text.set_font(font);
                 } else {
-                    println!(
-                        "Font file '{}' does not exist!",
-                        font_path.to_string_lossy().as_ref()
+                    generic_content_data.project.diagnostics.report(
+                        font_name.to_string(),
+                        "Font not found.".to_string(),
                     );
-                };
+                }
+                drop(font_manager);
                 VideoTypeEnum::Text(text)
             }
             "Image" => {
@@ -344,21 +462,38 @@ pub fn parse_vid_video(
                     generic_content_data.reset(),
                 );
                 if let Some('<') = chars.next() {
-                    let cmd = parse_string(chars)?;
-                    let mut args = vec![];
-                    while let Some('+') = chars.next() {
-                        args.push(parse_string(chars)?);
+                    use crate::content::external_image::{
+                        ExternalImageCommand, ExternalImageOutput,
+                    };
+                    let mut cmd = ExternalImageCommand::new(parse_string(chars)?, vec![]);
+                    loop {
+                        match chars.next() {
+                            Some('+') => cmd.args.push(parse_string(chars)?),
+                            Some('o') => cmd.output = ExternalImageOutput::Stdout,
+                            Some('t') => {
+                                cmd.timeout = std::time::Duration::from_secs_f64(
+                                    parse_vid_f64(chars)?.max(0.0).min(86400.0),
+                                )
+                            }
+                            _ => break,
+                        }
                     }
                     eprintln!(
                         "PARSER: External img command: {:?} with args {:?}",
-                        cmd, args
+                        cmd.command, cmd.args
                     );
-                    img.external_command = Some((cmd, args));
+                    img.external_command = Some(cmd);
                 }
                 VideoTypeEnum::Image(img)
             }
             "VidFromImagesInDirectory" => {
+                use crate::content::{
+                    animated_image::AnimationTiming, input_video::ImageSequenceError,
+                };
                 let directory = parse_path(chars)?;
+                let mut frame_rate = None;
+                let mut timing = None;
+                let mut cache_size = None;
                 let crop = {
                     let mut first = String::new();
                     let mut second = String::new();
@@ -376,6 +511,21 @@ pub fn parse_vid_video(
                                 }
                             }
                             Some(';') => break,
+                            // options (can't be confused with the crop's numbers)
+                            Some('r') => frame_rate = Some(parse_vid_f64(chars)?),
+                            Some('n') => {
+                                timing = Some(AnimationTiming::Native {
+                                    length: parse_vid_f64(chars)?,
+                                    looping: false,
+                                })
+                            }
+                            Some('l') => {
+                                timing = Some(AnimationTiming::Native {
+                                    length: parse_vid_f64(chars)?,
+                                    looping: true,
+                                })
+                            }
+                            Some('c') => cache_size = Some(parse_vid_int(chars)?),
                             Some(c) => match rev {
                                 None => first.push(c),
                                 Some(_) => second.push(c),
@@ -401,18 +551,154 @@ pub fn parse_vid_video(
                     ));
                     }
                 };
-                VideoTypeEnum::Raw(
-                    match InputVideo::new_from_directory_full_of_frames(
-                        directory.clone(),
-                        crop,
-                        generic_content_data.reset(),
-                    ) {
-                        Ok(v) => v,
-                        Err(err) => {
-                            return Err(ParserError::DirectoryWithImagesNotFound(directory, err))
+                let mut vid = match InputVideo::new_from_directory_full_of_frames(
+                    directory.clone(),
+                    crop,
+                    generic_content_data.reset(),
+                ) {
+                    Ok(v) => v,
+                    Err(ImageSequenceError::Io(err)) => {
+                        return Err(ParserError::DirectoryWithImagesNotFound(directory, err))
+                    }
+                    Err(err) => return Err(ParserError::InvalidImageSequence(directory, err)),
+                };
+                if let Some(frame_rate) = frame_rate {
+                    vid.frame_rate = frame_rate;
+                }
+                if let Some(timing) = timing {
+                    vid.timing = timing;
+                }
+                if let Some(cache_size) = cache_size {
+                    vid.set_cache_size(cache_size);
+                }
+                VideoTypeEnum::Raw(vid)
+            }
+            "Shape" => {
+                let mut fill = None;
+                let mut stroke = None;
+                let kind = loop {
+                    break match chars.next() {
+                        Some('r') => crate::content::shape::ShapeKind::Rectangle {
+                            corner_radius: parse_vid_curve(chars)?,
+                        },
+                        Some('e') => crate::content::shape::ShapeKind::Ellipse,
+                        Some('p') => crate::content::shape::ShapeKind::Polygon {
+                            sides: parse_vid_curve(chars)?,
+                            rotation: parse_vid_curve(chars)?,
+                        },
+                        Some('d') => {
+                            let data = parse_string(chars)?;
+                            match crate::content::shape::ShapePath::parse(&data) {
+                                Ok(v) => crate::content::shape::ShapeKind::Path(v),
+                                Err(e) => return Err(ParserError::InvalidShapePath(data, e)),
+                            }
+                        }
+                        // options (before the kind)
+                        Some('F') => {
+                            fill = Some(crate::types::Color::parse(chars)?);
+                            continue;
                         }
+                        Some('S') => {
+                            stroke = Some(crate::content::shape::ShapeStroke {
+                                width: parse_vid_curve(chars)?,
+                                color: crate::types::Color::parse(chars)?,
+                            });
+                            continue;
+                        }
+                        Some(c) => return Err(ParserError::InvalidShapeKind(c)),
+                        None => return Err(ParserError::UnexpectedEOF),
+                    };
+                };
+                let mut shape =
+                    crate::content::shape::Shape::new(kind, generic_content_data.reset());
+                // without any options, the shape is filled with white
+                if fill.is_some() || stroke.is_some() {
+                    shape.set_fill(fill);
+                    shape.set_stroke(stroke);
+                }
+                VideoTypeEnum::Shape(shape)
+            }
+            "Svg" => VideoTypeEnum::Svg(crate::content::svg::Svg::new(
+                parse_path(chars)?,
+                generic_content_data.reset(),
+            )),
+            "AnimatedImage" => {
+                use crate::content::animated_image::AnimationTiming;
+                let path = parse_path(chars)?;
+                let timing = match chars.next() {
+                    Some('s') => AnimationTiming::Stretch,
+                    Some('n') => AnimationTiming::Native {
+                        length: parse_vid_f64(chars)?,
+                        looping: false,
                     },
-                )
+                    Some('l') => AnimationTiming::Native {
+                        length: parse_vid_f64(chars)?,
+                        looping: true,
+                    },
+                    Some(c) => return Err(ParserError::InvalidAnimationTiming(c)),
+                    None => return Err(ParserError::UnexpectedEOF),
+                };
+                VideoTypeEnum::AnimatedImage(crate::content::animated_image::AnimatedImage::new(
+                    path,
+                    timing,
+                    generic_content_data.reset(),
+                ))
+            }
+            "Project" => {
+                let path = parse_path(chars)?;
+                let mut parameters = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some('+') => parameters.push((parse_string(chars)?, parse_string(chars)?)),
+                        Some(_) => break,
+                        None => return Err(ParserError::UnexpectedEOF),
+                    }
+                }
+                VideoTypeEnum::Project(crate::content::nested_project::NestedProject::new(
+                    path,
+                    parameters,
+                    generic_content_data.reset(),
+                ))
+            }
+            "Instance" => VideoTypeEnum::Instance(crate::content::instance::Instance::new(
+                parse_string(chars)?,
+                generic_content_data.reset(),
+            )),
+            "Solid" => VideoTypeEnum::Solid(crate::types::Color::parse(chars)?),
+            "Gradient" => {
+                use crate::content::gradient::GradientKind;
+                let kind = match chars.next() {
+                    Some('l') => GradientKind::Linear {
+                        start: (parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+                        end: (parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+                    },
+                    Some('r') => GradientKind::Radial {
+                        center: (parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+                        radius: parse_vid_curve(chars)?,
+                    },
+                    Some('c') => GradientKind::Conic {
+                        center: (parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+                        angle: parse_vid_curve(chars)?,
+                    },
+                    Some(c) => return Err(ParserError::InvalidGradientKind(c)),
+                    None => return Err(ParserError::UnexpectedEOF),
+                };
+                let mut stops = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some('+') => stops.push(crate::content::gradient::GradientStop {
+                            position: parse_vid_curve(chars)?,
+                            color: crate::types::Color::parse(chars)?,
+                        }),
+                        Some(_ /* ; */) => break,
+                        None => return Err(ParserError::UnexpectedEOF),
+                    }
+                }
+                VideoTypeEnum::Gradient(crate::content::gradient::Gradient::new(
+                    kind,
+                    stops,
+                    generic_content_data.reset(),
+                ))
             }
             "VidUsingFfmpeg" => VideoTypeEnum::Ffmpeg(crate::content::ffmpeg_vid::FfmpegVid::new(
                 parse_path(chars)?,
@@ -424,6 +710,97 @@ pub fn parse_vid_video(
     ));
 }
 
+/// [mode][crop: - or C[x][y][w][h]], see parser_v0.md
+pub fn parse_image_fit(
+    chars: &mut Chars,
+) -> Result<crate::content::image_fit::ImageFitSettings, ParserError> {
+    use crate::content::image_fit::{ImageCrop, ImageFit, ImageFitSettings};
+    Ok(ImageFitSettings {
+        fit: match chars.next() {
+            Some('s') => ImageFit::Stretch,
+            Some('c') => ImageFit::Contain,
+            Some('v') => ImageFit::Cover,
+            Some('o') => ImageFit::Original,
+            Some('t') => ImageFit::Tile,
+            Some(c) => return Err(ParserError::InvalidImageFit(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+        crop: match chars.next() {
+            Some('-') => None,
+            Some('C') => Some(ImageCrop {
+                x: parse_vid_curve(chars)?,
+                y: parse_vid_curve(chars)?,
+                w: parse_vid_curve(chars)?,
+                h: parse_vid_curve(chars)?,
+            }),
+            Some(c) => return Err(ParserError::InvalidImageFit(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+    })
+}
+
+/// L[align][vertical align][wrap][fit][line height][letter spacing], see parser_v0.md
+pub fn parse_text_layout(
+    chars: &mut Chars,
+) -> Result<crate::content::text::TextLayout, ParserError> {
+    use crate::content::text::{TextAlign, TextFit, TextVerticalAlign};
+    Ok(crate::content::text::TextLayout {
+        align: match chars.next() {
+            Some('-') => None,
+            Some('l') => Some(TextAlign::Left),
+            Some('c') => Some(TextAlign::Center),
+            Some('r') => Some(TextAlign::Right),
+            Some('j') => Some(TextAlign::Justify),
+            Some(c) => return Err(ParserError::InvalidTextLayout(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+        vertical_align: match chars.next() {
+            Some('-') => None,
+            Some('t') => Some(TextVerticalAlign::Top),
+            Some('c') => Some(TextVerticalAlign::Center),
+            Some('b') => Some(TextVerticalAlign::Bottom),
+            Some(c) => return Err(ParserError::InvalidTextLayout(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+        wrap: match chars.next() {
+            Some('w') => true,
+            Some('n') => false,
+            Some(c) => return Err(ParserError::InvalidTextLayout(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+        fit: match chars.next() {
+            Some('s') => TextFit::Shrink,
+            Some('f') => TextFit::Fixed(parse_vid_curve(chars)?),
+            Some('o') => TextFit::Overflow(parse_vid_curve(chars)?),
+            Some(c) => return Err(ParserError::InvalidTextLayout(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+        line_height: parse_vid_curve(chars)?,
+        letter_spacing: parse_vid_curve(chars)?,
+    })
+}
+
+/// A[unit][reveal][offset x][offset y][scale][opacity], see parser_v0.md
+pub fn parse_text_animator(
+    chars: &mut Chars,
+) -> Result<crate::content::text::TextAnimator, ParserError> {
+    use crate::content::text::TextAnimationUnit;
+    Ok(crate::content::text::TextAnimator {
+        unit: match chars.next() {
+            Some('c') => TextAnimationUnit::Characters,
+            Some('w') => TextAnimationUnit::Words,
+            Some('l') => TextAnimationUnit::Lines,
+            Some(c) => return Err(ParserError::InvalidTextAnimator(c)),
+            None => return Err(ParserError::UnexpectedEOF),
+        },
+        reveal: parse_vid_curve(chars)?,
+        offset_x: parse_vid_curve(chars)?,
+        offset_y: parse_vid_curve(chars)?,
+        scale: parse_vid_curve(chars)?,
+        opacity: parse_vid_curve(chars)?,
+    })
+}
+
 pub fn parse_vid_curve(chars: &mut Chars) -> Result<Curve, ParserError> {
     Ok(loop { break match chars.next() {
         Some(char) => match char {
@@ -431,6 +808,11 @@ pub fn parse_vid_curve(chars: &mut Chars) -> Result<Curve, ParserError> {
             '-' | '0' | '1' | '2' | '3' | '4' | '5' | '6' | '7' | '8' | '9' | '.' => CurveData::Constant(parse_vid_f64_prepend(String::from(char), chars)?),
             '/' => CurveData::Linear(parse_vid_curve(chars)?, parse_vid_curve(chars)?),
             's' => CurveData::SmoothFlat(parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+            'b' => {
+                let h1 = (parse_vid_f64(chars)?, parse_vid_f64(chars)?);
+                let h2 = (parse_vid_f64(chars)?, parse_vid_f64(chars)?);
+                CurveData::Bezier(parse_vid_curve(chars)?, parse_vid_curve(chars)?, h1, h2)
+            },
             '#' => CurveData::Chain(
                 {
                     let mut vec = Vec::new();
@@ -448,12 +830,65 @@ pub fn parse_vid_curve(chars: &mut Chars) -> Result<Curve, ParserError> {
                 }
             ),
             '!' => CurveData::Program(crate::external_program::ExternalProgram::new(parse_path(chars)?, crate::external_program::ExternalProgramMode::RunOnceArg), crate::curve::CurveExternalProgramMode::String), // TODO: Make this more flexible
+            'a' => CurveData::Sum(parse_vid_curves(chars)?),
+            'm' => CurveData::Product(parse_vid_curves(chars)?),
+            '<' => CurveData::Min(parse_vid_curves(chars)?),
+            '>' => CurveData::Max(parse_vid_curves(chars)?),
+            'c' => CurveData::Clamp(parse_vid_curve(chars)?, parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+            'r' => CurveData::Remap(parse_vid_curve(chars)?, parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+            'l' => CurveData::Loop(parse_vid_f64(chars)?, parse_vid_curve(chars)?),
+            'p' => CurveData::PingPong(parse_vid_f64(chars)?, parse_vid_curve(chars)?),
+            'h' => CurveData::Hold(parse_vid_f64(chars)?, parse_vid_curve(chars)?),
+            'q' => CurveData::Quantize(parse_vid_curve(chars)?, parse_vid_curve(chars)?),
+            'k' => CurveData::Keyframes({
+                let mut vec = Vec::new();
+                loop {
+                    match chars.next() {
+                        Some('+') => vec.push(crate::curve::Keyframe {
+                            time: parse_vid_f64(chars)?,
+                            value: parse_vid_f64(chars)?,
+                            interpolation: match chars.next() {
+                                Some('h') => crate::curve::KeyframeInterpolation::Hold,
+                                Some('l') => crate::curve::KeyframeInterpolation::Linear,
+                                Some('s') => crate::curve::KeyframeInterpolation::Smooth,
+                                Some(c) => return Err(ParserError::InvalidKeyframeInterpolation(c)),
+                                None => return Err(ParserError::UnexpectedEOF),
+                            },
+                        }),
+                        None => return Err(ParserError::UnexpectedEOF),
+                        Some(_ /* preferrably k for clarity */) => break,
+                    };
+                };
+                vec.sort_by(|a, b| a.time.total_cmp(&b.time));
+                vec
+            }),
+            'K' => {
+                let path = parse_path(chars)?;
+                match super::keyframes::import(&path) {
+                    Ok(v) => CurveData::Keyframes(v),
+                    Err(e) => return Err(ParserError::KeyframeImportError(path, e)),
+                }
+            },
+            '~' => CurveData::Noise { seed: parse_vid_int(chars)?, frequency: parse_vid_f64(chars)?, min: parse_vid_curve(chars)?, max: parse_vid_curve(chars)?, },
             _ => return Err(ParserError::InvalidCurveIdentifier(char)),
         },
         None => return Err(ParserError::UnexpectedEOF),
     }; }.into())
 }
 
+/// Parses curves until the first char that isn't '+'. Each curve must be prefixed with '+'. (like Chain, but without the lengths)
+pub fn parse_vid_curves(chars: &mut Chars) -> Result<Vec<Curve>, ParserError> {
+    let mut vec = Vec::new();
+    loop {
+        match chars.next() {
+            Some('+') => vec.push(parse_vid_curve(chars)?),
+            Some(_ /* preferrably the char that started the list, for clarity. */) => break,
+            None => return Err(ParserError::UnexpectedEOF),
+        };
+    }
+    Ok(vec)
+}
+
 /// Parses an integer in the form "(int);"
 pub fn parse_vid_int<T>(chars: &mut Chars) -> Result<T, ParserError>
 where
@@ -513,6 +948,13 @@ pub fn parse_vid_to_next_semicolon(
 }
 
 /// Reads all chars into a buffer, stopping at '\!', and interpreting '\\' as '\', '\n' as newline, etc. \x with an unknown x will be interpreted litterally, but this is unreliable, so please remember to replace all '\'s with '\\' when saving!
+/// Escapes a string so that parse_string will read it.
+pub fn escape_string(str: &str) -> String {
+    str.replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\t', "\\t")
+}
+
 pub fn parse_string(chars: &mut Chars) -> Result<String, ParserError> {
     let mut buf = String::new();
     let mut backslash = false;
@@ -534,6 +976,7 @@ pub fn parse_string(chars: &mut Chars) -> Result<String, ParserError> {
                         buf.push(c);
                     }
                 }
+                backslash = false;
             }
         } else {
             return Err(ParserError::UnexpectedEOF);
@@ -0,0 +1,443 @@
+use std::{fmt::Display, io, path::PathBuf};
+
+use crate::{content::content::GenericContentData, project::Project, video::Video};
+
+use super::parser_general::ParserError;
+
+/// One subtitle. Times are in seconds.
+pub struct Cue {
+    pub start: f64,
+    pub end: f64,
+    pub text: String,
+}
+
+/// How imported captions look: one video in the project file syntax (see parser_v0.md), where {start}, {length} and {text} are replaced for every caption. The video has to end with the : that ends every video's data.
+pub const DEFAULT_TEMPLATE: &str = "pos:v0.5;0.96;0.9;0.2;start:{start};length:{length};video:Text:@\\0;r1;1;1;1;Lcbwf0.3;1.1;0;O0.08;r0;0;0;1;Ms{text}\\!:";
+
+/// Reads cues from a .srt or .vtt file. The returned cues are sorted by their start time.
+pub fn import(path: &PathBuf) -> Result<Vec<Cue>, SubtitlesError> {
+    let contents = std::fs::read_to_string(path)?;
+    let mut cues = match Format::from_path(path)? {
+        Format::Srt => parse_srt(&contents)?,
+        Format::Vtt => parse_vtt(&contents)?,
+    };
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    Ok(cues)
+}
+
+enum Format {
+    Srt,
+    Vtt,
+}
+impl Format {
+    fn from_path(path: &PathBuf) -> Result<Self, SubtitlesError> {
+        match path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .as_deref()
+        {
+            Some("srt") => Ok(Self::Srt),
+            Some("vtt") => Ok(Self::Vtt),
+            _ => Err(SubtitlesError::UnknownFormat(path.clone())),
+        }
+    }
+}
+
+/// Splits the file into blocks separated by empty lines. Each block is returned with the line number (starting at 1) of its first line.
+fn blocks(str: &str) -> Vec<(usize, Vec<&str>)> {
+    let mut blocks = Vec::new();
+    let mut current: Option<(usize, Vec<&str>)> = None;
+    for (index, line) in str.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() {
+            if let Some(block) = current.take() {
+                blocks.push(block);
+            }
+        } else {
+            current.get_or_insert_with(|| (index + 1, Vec::new())).1.push(line);
+        }
+    }
+    if let Some(block) = current {
+        blocks.push(block);
+    }
+    blocks
+}
+
+pub fn parse_srt(str: &str) -> Result<Vec<Cue>, SubtitlesError> {
+    let str = str.trim_start_matches('\u{feff}');
+    let mut cues = Vec::new();
+    for (line, lines) in blocks(str) {
+        // the first line is the cue's number, but it may be missing
+        let timing_index = match lines.iter().position(|l| l.contains("-->")) {
+            Some(v) => v,
+            None => {
+                return Err(SubtitlesError::Parse(
+                    line,
+                    format!("expected a line like '00:00:01,000 --> 00:00:02,500'"),
+                ))
+            }
+        };
+        let (start, end) = parse_timing(lines[timing_index], line + timing_index)?;
+        cues.push(Cue {
+            start,
+            end,
+            text: clean_text(&lines[timing_index + 1..].join("\n")),
+        });
+    }
+    Ok(cues)
+}
+
+pub fn parse_vtt(str: &str) -> Result<Vec<Cue>, SubtitlesError> {
+    let str = str.trim_start_matches('\u{feff}');
+    if !str.starts_with("WEBVTT") {
+        return Err(SubtitlesError::Parse(
+            1,
+            format!("a WebVTT file has to start with 'WEBVTT'"),
+        ));
+    }
+    let mut cues = Vec::new();
+    // the first block is the header
+    for (line, lines) in blocks(str).into_iter().skip(1) {
+        let first = lines[0];
+        if first.starts_with("NOTE") || first == "STYLE" || first == "REGION" {
+            continue;
+        }
+        // an optional cue identifier before the timing
+        let timing_index = match lines.iter().take(2).position(|l| l.contains("-->")) {
+            Some(v) => v,
+            None => {
+                return Err(SubtitlesError::Parse(
+                    line,
+                    format!("expected a line like '00:01.000 --> 00:02.500'"),
+                ))
+            }
+        };
+        let (start, end) = parse_timing(lines[timing_index], line + timing_index)?;
+        cues.push(Cue {
+            start,
+            end,
+            text: clean_text(&lines[timing_index + 1..].join("\n")),
+        });
+    }
+    Ok(cues)
+}
+
+/// "[start] --> [end]", anything after the end time (WebVTT cue settings) is ignored.
+fn parse_timing(timing: &str, line: usize) -> Result<(f64, f64), SubtitlesError> {
+    let (start, end) = timing.split_once("-->").unwrap();
+    let end = end.split_whitespace().next().unwrap_or("");
+    match (parse_timestamp(start.trim()), parse_timestamp(end)) {
+        (Some(start), Some(end)) => Ok((start, end.max(start))),
+        (None, _) => Err(SubtitlesError::Parse(
+            line,
+            format!("'{}' is not a timestamp", start.trim()),
+        )),
+        (_, None) => Err(SubtitlesError::Parse(
+            line,
+            format!("'{end}' is not a timestamp"),
+        )),
+    }
+}
+
+/// [hh:]mm:ss[,.]mmm -> seconds
+fn parse_timestamp(str: &str) -> Option<f64> {
+    let str = str.replace(',', ".");
+    let mut parts = str.rsplit(':');
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let hours: f64 = match parts.next() {
+        Some(h) => h.parse().ok()?,
+        None => 0.0,
+    };
+    if parts.next().is_some() {
+        return None;
+    }
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Removes all tags except for <b> and </b>, which text markup (see text_markup.rs) understands, and turns a literal < that doesn't start a tag into &lt;.
+fn clean_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('<') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest.find('>') {
+            Some(end) if !rest[1..end].contains('<') => {
+                let tag = rest[1..end].trim();
+                if tag == "b" || tag == "/b" {
+                    out.push_str(&rest[..=end]);
+                }
+                rest = &rest[end + 1..];
+            }
+            _ => {
+                out.push_str("&lt;");
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Creates a List video (in project file syntax) which contains one video per cue, made from the template (see DEFAULT_TEMPLATE). The cues' times are divided by duration (in seconds), so that they are relative to the project's length. If duration is None, the end of the last cue is used.
+pub fn to_project_syntax(cues: &[Cue], duration: Option<f64>, template: &str) -> String {
+    let duration = duration
+        .unwrap_or_else(|| cues.iter().map(|c| c.end).fold(0.0, f64::max))
+        .max(f64::MIN_POSITIVE);
+    let mut out = String::from("video:List:");
+    for cue in cues {
+        out.push('+');
+        out.push_str(
+            &template
+                .replace("{start}", &(cue.start / duration).to_string())
+                .replace("{length}", &((cue.end - cue.start) / duration).to_string())
+                .replace("{text}", &super::parser_v0::escape_string(&cue.text)),
+        );
+    }
+    out.push_str(";:");
+    out
+}
+
+/// Reads a template (see DEFAULT_TEMPLATE) from a file. Like the project file's reader, this removes newlines and indentation.
+pub fn read_template(path: &PathBuf) -> io::Result<String> {
+    Ok(std::fs::read_to_string(path)?
+        .lines()
+        .map(|l| l.trim_start())
+        .collect())
+}
+
+/// Creates the List video from to_project_syntax.
+pub fn to_video(
+    cues: &[Cue],
+    duration: Option<f64>,
+    template: &str,
+    project: &Project,
+) -> Result<Video, ParserError> {
+    super::parser_v0::parse_vid(
+        &mut to_project_syntax(cues, duration, template).chars(),
+        GenericContentData::new(project.clone()),
+    )
+}
+
+/// Writes cues to a .srt or .vtt file.
+pub fn export(path: &PathBuf, cues: &[Cue]) -> Result<(), SubtitlesError> {
+    let contents = match Format::from_path(path)? {
+        Format::Srt => to_srt(cues),
+        Format::Vtt => to_vtt(cues),
+    };
+    std::fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn to_srt(cues: &[Cue]) -> String {
+    let mut out = String::new();
+    for (index, cue) in cues.iter().enumerate() {
+        out.push_str(&format!(
+            "{}\n{} --> {}\n{}\n\n",
+            index + 1,
+            format_timestamp(cue.start, ','),
+            format_timestamp(cue.end, ','),
+            cue.text
+        ));
+    }
+    out
+}
+
+pub fn to_vtt(cues: &[Cue]) -> String {
+    let mut out = String::from("WEBVTT\n\n");
+    for cue in cues {
+        // keep the <b> tags, but escape everything else
+        let text = cue
+            .text
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace("&lt;b&gt;", "<b>")
+            .replace("&lt;/b&gt;", "</b>");
+        out.push_str(&format!(
+            "{} --> {}\n{}\n\n",
+            format_timestamp(cue.start, '.'),
+            format_timestamp(cue.end, '.'),
+            text
+        ));
+    }
+    out
+}
+
+/// seconds -> hh:mm:ss[separator]mmm
+fn format_timestamp(seconds: f64, separator: char) -> String {
+    let ms = (seconds.max(0.0) * 1000.0).round() as u64;
+    format!(
+        "{:02}:{:02}:{:02}{separator}{:03}",
+        ms / 3_600_000,
+        ms / 60_000 % 60,
+        ms / 1000 % 60,
+        ms % 1000
+    )
+}
+
+/// Finds all Text videos with static text in the video and its children and creates one cue for each of them. timing is the video's (start, length), relative to the project's length, which is duration seconds long. Effects that change the time (like ChangeTime) are ignored. If a Text uses markup, only <b> is kept.
+pub fn cues_from_video(vid: &Video, timing: (f64, f64), duration: f64) -> Vec<Cue> {
+    let mut cues = Vec::new();
+    collect_cues(vid, timing, (timing.0, timing.0 + timing.1), duration, &mut cues);
+    cues.sort_by(|a, b| a.start.total_cmp(&b.start));
+    cues
+}
+/// Finds the first video with this label (see Video::label) in vid and its children. Also returns its (start, length), relative to the project, if timing is vid's.
+pub fn find_labeled<'a>(
+    vid: &'a Video,
+    label: &str,
+    timing: (f64, f64),
+) -> Option<(&'a Video, (f64, f64))> {
+    if vid.label.as_deref() == Some(label) {
+        return Some((vid, timing));
+    }
+    crate::content::content::Content::children(vid)
+        .into_iter()
+        .find_map(|child| {
+            find_labeled(
+                child,
+                label,
+                (
+                    timing.0 + child.set_start_frame * timing.1,
+                    child.set_length * timing.1,
+                ),
+            )
+        })
+}
+/// visible is the part of the project (start, end) where all of the video's parents are visible.
+fn collect_cues(
+    vid: &Video,
+    timing: (f64, f64),
+    visible: (f64, f64),
+    duration: f64,
+    cues: &mut Vec<Cue>,
+) {
+    let visible = (visible.0.max(timing.0), visible.1.min(timing.0 + timing.1));
+    if visible.1 <= visible.0 {
+        return;
+    }
+    if let crate::video::VideoTypeEnum::Text(text) = &vid.video.vt {
+        if let crate::content::text::TextType::Static(str) = text.text() {
+            let str = if text.markup() {
+                crate::content::text_markup::parse(str)
+                    .into_iter()
+                    .map(|span| {
+                        if span.style.bold {
+                            format!("<b>{}</b>", span.text)
+                        } else {
+                            span.text
+                        }
+                    })
+                    .collect()
+            } else {
+                str.clone()
+            };
+            cues.push(Cue {
+                start: visible.0 * duration,
+                end: visible.1 * duration,
+                text: str,
+            });
+        }
+    }
+    for child in crate::content::content::Content::children(vid) {
+        collect_cues(
+            child,
+            (
+                timing.0 + child.set_start_frame * timing.1,
+                child.set_length * timing.1,
+            ),
+            visible,
+            duration,
+            cues,
+        );
+    }
+}
+
+pub enum SubtitlesError {
+    Io(io::Error),
+    /// The file's extension was neither .srt nor .vtt
+    UnknownFormat(PathBuf),
+    /// Line number (starting at 1) and message
+    Parse(usize, String),
+}
+impl From<io::Error> for SubtitlesError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+impl Display for SubtitlesError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "IO error: {e}"),
+            Self::UnknownFormat(p) => write!(
+                f,
+                "Can't tell the format of '{}'. Use a file ending in .srt or .vtt.",
+                p.display()
+            ),
+            Self::Parse(line, e) => write!(f, "line {line}: {e}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_line_cue_survives_round_trip() {
+        let srt = "1\n00:00:01,000 --> 00:00:03,000\nSecond & line\nwith \\ two\trows\n";
+        let cues = match parse_srt(srt) {
+            Ok(v) => v,
+            Err(e) => panic!("{e}"),
+        };
+        assert_eq!(cues[0].text, "Second & line\nwith \\ two\trows");
+        let project = Project::new(Default::default());
+        let syntax = to_project_syntax(&cues, Some(4.0), DEFAULT_TEMPLATE);
+        let vid = match super::super::parser_v0::parse_vid(
+            &mut syntax.chars(),
+            GenericContentData::new(project),
+        ) {
+            Ok(v) => v,
+            Err(e) => panic!("{e}"),
+        };
+        let cues = cues_from_video(&vid, (0.0, 1.0), 4.0);
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].text, "Second & line\nwith \\ two\trows");
+        assert!((cues[0].start - 1.0).abs() < 1e-9);
+        assert!((cues[0].end - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn export_from_labeled_container() {
+        let cues = vec![Cue {
+            start: 1.0,
+            end: 3.0,
+            text: "inside".to_string(),
+        }];
+        // the subtitles are in the second half of the project
+        let syntax = format!(
+            "video:List:+start:0.5;length:0.5;label:subs\\!{};:",
+            to_project_syntax(&cues, Some(4.0), DEFAULT_TEMPLATE)
+        );
+        let vid = match super::super::parser_v0::parse_vid(
+            &mut syntax.chars(),
+            GenericContentData::new(Project::new(Default::default())),
+        ) {
+            Ok(v) => v,
+            Err(e) => panic!("{e}"),
+        };
+        assert!(find_labeled(&vid, "other", (0.0, 1.0)).is_none());
+        let (root, timing) = match find_labeled(&vid, "subs", (0.0, 1.0)) {
+            Some(v) => v,
+            None => panic!("the labeled list was not found"),
+        };
+        assert_eq!(timing, (0.5, 0.5));
+        let cues = cues_from_video(root, timing, 4.0);
+        assert_eq!(cues.len(), 1);
+        assert_eq!(cues[0].text, "inside");
+        assert!((cues[0].start - 2.5).abs() < 1e-9);
+        assert!((cues[0].end - 3.5).abs() < 1e-9);
+    }
+}
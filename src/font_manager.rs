@@ -0,0 +1,307 @@
+//! Finds fonts by family and style name and keeps every font file that was loaded, so that Text videos using the same font share it.
+
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard, OnceLock},
+};
+
+use rusttype::Font;
+
+static FONT_MANAGER: OnceLock<Mutex<FontManager>> = OnceLock::new();
+
+/// The font manager used for all projects.
+pub fn get() -> MutexGuard<'static, FontManager> {
+    FONT_MANAGER
+        .get_or_init(|| Mutex::new(FontManager::new(FontManager::default_directories())))
+        .lock()
+        .unwrap()
+}
+
+pub struct FontManager {
+    directories: Vec<PathBuf>,
+    /// None until the directories have been scanned (this only happens once a font is looked up by its name)
+    faces: Option<Vec<FontFace>>,
+    /// All fonts that were loaded, by path and index. None if the file could not be loaded.
+    loaded: HashMap<(PathBuf, u32), Option<Font<'static>>>,
+    /// Families which are searched for characters that a font doesn't have, in this order.
+    fallback_families: Vec<String>,
+    /// The fonts from fallback_families that were found. None until they are needed.
+    fallbacks: Option<Vec<Font<'static>>>,
+}
+
+/// A font (a file, or one font in a .ttc collection) found while scanning the font directories.
+pub struct FontFace {
+    pub path: PathBuf,
+    pub index: u32,
+    pub family: String,
+    /// "Regular", "Bold Italic", ...
+    pub style: String,
+    pub bold: bool,
+    pub italic: bool,
+    /// 100 (thin) to 900 (black), 400 is normal.
+    pub weight: u16,
+}
+
+impl FontManager {
+    pub fn new(directories: Vec<PathBuf>) -> Self {
+        Self {
+            directories,
+            faces: None,
+            loaded: HashMap::new(),
+            fallback_families: vec![
+                "Noto Sans".to_string(),
+                "DejaVu Sans".to_string(),
+                "Noto Sans CJK SC".to_string(),
+                "Noto Sans Symbols".to_string(),
+                "Noto Sans Symbols2".to_string(),
+                "Arial Unicode MS".to_string(),
+                "Segoe UI Symbol".to_string(),
+            ],
+            fallbacks: None,
+        }
+    }
+    /// The usual places where fonts are installed on Linux, Windows and macOS.
+    pub fn default_directories() -> Vec<PathBuf> {
+        let mut dirs: Vec<PathBuf> = vec![
+            "/usr/share/fonts".into(),
+            "/usr/local/share/fonts".into(),
+            "/Library/Fonts".into(),
+            "/System/Library/Fonts".into(),
+            "C:\\Windows\\Fonts".into(),
+        ];
+        if let Some(home) = std::env::var_os("HOME") {
+            let home = PathBuf::from(home);
+            dirs.push(home.join(".fonts"));
+            dirs.push(home.join(".local/share/fonts"));
+            dirs.push(home.join("Library/Fonts"));
+        }
+        dirs
+    }
+
+    /// Directories added later are searched first. They will be scanned the next time a font is looked up by name.
+    pub fn add_directory(&mut self, dir: PathBuf) {
+        self.directories.insert(0, dir);
+        self.faces = None;
+        self.fallbacks = None;
+    }
+
+    /// The directories which are searched for fonts, in order.
+    pub fn directories(&self) -> &Vec<PathBuf> {
+        &self.directories
+    }
+
+    /// All fonts in the font directories. Scans the directories if this hasn't happened yet.
+    pub fn faces(&mut self) -> &Vec<FontFace> {
+        if self.faces.is_none() {
+            let mut faces = Vec::new();
+            let mut visited = HashSet::new();
+            for dir in self.directories.iter() {
+                Self::scan_dir(dir, &mut faces, &mut visited);
+            }
+            self.faces = Some(faces);
+        }
+        self.faces.as_ref().unwrap()
+    }
+    /// visited contains the canonical paths of all directories that were scanned, so symlinks that point back to a parent directory don't cause endless recursion.
+    fn scan_dir(dir: &Path, faces: &mut Vec<FontFace>, visited: &mut HashSet<PathBuf>) {
+        match dir.canonicalize() {
+            Ok(canonical) if !visited.contains(&canonical) => {
+                visited.insert(canonical);
+            }
+            _ => return,
+        }
+        let entries = match std::fs::read_dir(dir) {
+            Ok(v) => v,
+            Err(_) => return,
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let path = entry.path();
+            if path.is_dir() {
+                Self::scan_dir(&path, faces, visited);
+                continue;
+            }
+            match path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .as_deref()
+            {
+                Some("ttf" | "otf" | "ttc" | "otc") => (),
+                _ => continue,
+            }
+            let data = match std::fs::read(&path) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            for index in 0..ttf_parser::fonts_in_collection(&data).unwrap_or(1) {
+                if let Ok(face) = ttf_parser::Face::parse(&data, index) {
+                    let name = |ids: [u16; 2]| {
+                        ids.into_iter().find_map(|id| {
+                            face.names()
+                                .into_iter()
+                                .filter(|n| n.name_id == id)
+                                .find_map(|n| n.to_string())
+                        })
+                    };
+                    let family = match name([
+                        ttf_parser::name_id::TYPOGRAPHIC_FAMILY,
+                        ttf_parser::name_id::FAMILY,
+                    ]) {
+                        Some(v) => v,
+                        None => continue,
+                    };
+                    faces.push(FontFace {
+                        path: path.clone(),
+                        index,
+                        family,
+                        style: name([
+                            ttf_parser::name_id::TYPOGRAPHIC_SUBFAMILY,
+                            ttf_parser::name_id::SUBFAMILY,
+                        ])
+                        .unwrap_or_else(|| "Regular".to_string()),
+                        bold: face.is_bold(),
+                        italic: face.is_italic(),
+                        weight: face.weight().to_number(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Loads a font file, or returns the font if it was loaded before.
+    pub fn load(&mut self, path: &Path, index: u32) -> Option<Font<'static>> {
+        self.loaded
+            .entry((path.to_path_buf(), index))
+            .or_insert_with(|| {
+                let font = std::fs::read(path)
+                    .ok()
+                    .and_then(|file| Font::try_from_vec_and_index(file, index));
+                if font.is_none() {
+                    println!(
+                        "Font '{}' (index {index}) could not be loaded.",
+                        path.to_string_lossy()
+                    );
+                }
+                font
+            })
+            .clone()
+    }
+
+    /// The weight a style name asks for: a number ("600", "Italic 300") or a weight name ("Semibold", "Black Italic"). None if the style doesn't mention a weight.
+    fn style_weight(style: &str) -> Option<u16> {
+        if let Some(weight) = style
+            .split_whitespace()
+            .find_map(|word| word.parse::<u16>().ok())
+        {
+            return Some(weight);
+        }
+        let style = style.to_lowercase().replace(['-', ' '], "");
+        // names containing other names ("semibold" contains "bold") have to come first
+        [
+            ("extralight", 200),
+            ("ultralight", 200),
+            ("thin", 100),
+            ("hairline", 100),
+            ("light", 300),
+            ("regular", 400),
+            ("normal", 400),
+            ("book", 400),
+            ("medium", 500),
+            ("semibold", 600),
+            ("demibold", 600),
+            ("extrabold", 800),
+            ("ultrabold", 800),
+            ("bold", 700),
+            ("black", 900),
+            ("heavy", 900),
+        ]
+        .into_iter()
+        .find(|(name, _)| style.contains(name))
+        .map(|(_, weight)| weight)
+    }
+
+    /// Finds a font by its family name (case insensitive). style can be the exact style name ("Semibold Italic"), something like "Bold", "Italic" or "Bold Italic", or a numeric weight ("600", "300 Italic"). The font with the closest weight is used. Without a style, the regular font is preferred.
+    pub fn find(&mut self, family: &str, style: Option<&str>) -> Option<Font<'static>> {
+        let family = family.trim().to_lowercase();
+        let style = style.map(|s| s.trim().to_lowercase()).unwrap_or_default();
+        let want_weight = Self::style_weight(&style).unwrap_or(400);
+        let want_italic = style.contains("italic") || style.contains("oblique");
+        let best = self
+            .faces()
+            .iter()
+            .filter(|f| f.family.to_lowercase() == family)
+            .min_by_key(|f| {
+                // lower is better
+                if !style.is_empty() && f.style.to_lowercase() == style {
+                    (0, 0, 0, 0)
+                } else {
+                    // some fonts only set the bold flag and leave the weight at 400
+                    let weight = if f.bold { f.weight.max(700) } else { f.weight };
+                    (
+                        1,
+                        weight.abs_diff(want_weight),
+                        (f.italic != want_italic) as u8,
+                        (f.style.to_lowercase() != "regular") as u8,
+                    )
+                }
+            })
+            .map(|f| (f.path.clone(), f.index));
+        let (path, index) = best?;
+        self.load(&path, index)
+    }
+
+    /// Resolves a font description as used in project files: "@Family" or "@Family:Style" looks the font up by name, "@" alone is the default font, everything else is a path to a font file. Callers report it if the font isn't found.
+    pub fn get_font(&mut self, font: &str, index: u32) -> Option<Font<'static>> {
+        match font.strip_prefix('@') {
+            Some("") => self.default_font(),
+            Some(name) => {
+                let (family, style) = match name.split_once(':') {
+                    Some((family, style)) => (family, Some(style)),
+                    None => (name, None),
+                };
+                self.find(family, style)
+            }
+            None => self.load(Path::new(font), index),
+        }
+    }
+
+    /// The first of the fallback fonts that is installed. Used for text whose font wasn't found.
+    pub fn default_font(&mut self) -> Option<Font<'static>> {
+        self.fallbacks().into_iter().next()
+    }
+
+    /// The fonts that should be used for characters which the normal font doesn't have.
+    pub fn fallbacks(&mut self) -> Vec<Font<'static>> {
+        if self.fallbacks.is_none() {
+            let families = self.fallback_families.clone();
+            let fallbacks = families
+                .iter()
+                .filter_map(|family| self.find(family, None))
+                .collect();
+            self.fallbacks = Some(fallbacks);
+        }
+        self.fallbacks.clone().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn symlink_cycle_is_scanned_once() {
+        let dir = std::env::temp_dir().join(format!("{}_font_dir_cycle", std::process::id()));
+        _ = std::fs::remove_dir_all(&dir);
+        if let Err(e) = std::fs::create_dir_all(dir.join("sub")) {
+            panic!("{e}");
+        }
+        if let Err(e) = std::os::unix::fs::symlink(&dir, dir.join("sub").join("loop")) {
+            panic!("{e}");
+        }
+        let mut manager = FontManager::new(vec![dir.clone(), dir.join("sub")]);
+        let faces = manager.faces().len();
+        _ = std::fs::remove_dir_all(&dir);
+        assert_eq!(faces, 0);
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{content::content::GenericContentData, project::Project, video::Video};
+use crate::{
+    content::content::{Content, GenericContentData},
+    project::Project,
+    video::Video,
+};
 use std::sync::{Arc, Mutex};
 
 pub struct QuickCommandsHandler {
@@ -19,6 +23,14 @@ impl QuickCommandsHandler {
         if command == "deselect editing" {
             return Ok(vec![QctCommand::UnsetEditing]);
         }
+        if command == "diagnostics clear" {
+            project.diagnostics.clear();
+            return Ok(vec![]);
+        }
+        if command.starts_with("! ") {
+            // a diagnostic, which is only shown as a suggestion
+            return Ok(vec![]);
+        }
         {
             let mut split = command.split_whitespace();
             if split.next() == Some("edit") {
@@ -33,6 +45,227 @@ impl QuickCommandsHandler {
                 }
             }
         }
+        if command.starts_with("export curve ") {
+            let mut split = command[13..].splitn(3, ' ');
+            let (which, samples, path) = match (split.next(), split.next(), split.next()) {
+                (Some(which), Some(samples), Some(path)) => (which, samples, path),
+                _ => return Err(format!("export curve [curve] [samples] [path]")),
+            };
+            let samples: usize = match samples.parse() {
+                Ok(v) => v,
+                Err(_) => return Err(format!("{} <- samples is not a number", command)),
+            };
+            let keyframes = if let Some(id) = which.strip_prefix("shared:") {
+                // shared curves don't belong to a video, so nothing has to be edited
+                let id = match id.parse() {
+                    Ok(v) => v,
+                    Err(_) => return Err(format!("{} <- shared curve id is not a number", command)),
+                };
+                match project.shared_curves.get(&id) {
+                    Some(data) => crate::curve::Curve::from(data).bake(samples),
+                    None => return Err(format!("{} <- there is no shared curve {}", command, id)),
+                }
+            } else {
+                let vid = match index {
+                    Some(index) => {
+                        crate::useful::get_elem_from_index_recursive(video, &mut index.clone())
+                    }
+                    None => None,
+                };
+                let vid = match vid {
+                    Some(v) => v,
+                    None => return Err(format!("{} <- nothing is being edited", command)),
+                };
+                let curves = vid.named_curves();
+                match curves.iter().find(|(name, _)| *name == which) {
+                    Some((_, curve)) => curve.bake(samples),
+                    None => {
+                        let names: Vec<_> = curves.iter().map(|(name, _)| *name).collect();
+                        return Err(format!(
+                            "{} <- curve must be {} or shared:[id]",
+                            command,
+                            names.join(", ")
+                        ));
+                    }
+                }
+            };
+            return match crate::files::keyframes::export(&path.into(), &keyframes) {
+                Ok(()) => Ok(vec![]),
+                Err(e) => Err(format!("{} <- {}", command, e)),
+            };
+        }
+        if command.starts_with("export subtitles ") {
+            let mut split = command[17..].splitn(2, ' ');
+            let (duration, path) = match (split.next(), split.next()) {
+                (Some(duration), Some(path)) => (duration, path),
+                _ => return Err(format!("export subtitles [project duration in seconds] [path]")),
+            };
+            let duration: f64 = match duration.parse() {
+                Ok(v) => v,
+                Err(_) => return Err(format!("{} <- duration is not a number", command)),
+            };
+            // only the edited video and its children, or everything
+            let root_timing = (video.set_start_frame, video.set_length);
+            let (vid, timing) = match index {
+                Some(index) => match (
+                    crate::useful::get_elem_from_index_recursive(video, &mut index.clone()),
+                    crate::useful::get_timing_from_index_recursive(
+                        video,
+                        &mut index.clone(),
+                        root_timing,
+                    ),
+                ) {
+                    (Some(vid), Some(timing)) => (vid, timing),
+                    _ => return Err(format!("{} <- edited video not found", command)),
+                },
+                None => (&*video, root_timing),
+            };
+            let cues = crate::files::subtitles::cues_from_video(vid, timing, duration);
+            return match crate::files::subtitles::export(&path.into(), &cues) {
+                Ok(()) => Ok(vec![]),
+                Err(e) => Err(format!("{} <- {}", command, e)),
+            };
+        }
+        if command.starts_with("import curve ") {
+            let mut split = command[13..].splitn(2, ' ');
+            let (which, path) = match (split.next(), split.next()) {
+                (Some(which), Some(path)) => (which, path),
+                _ => return Err(format!("import curve [curve] [path]")),
+            };
+            let keyframes = match crate::files::keyframes::import(&path.into()) {
+                Ok(v) => v,
+                Err(e) => return Err(format!("{} <- {}", command, e)),
+            };
+            if let Some(id) = which.strip_prefix("shared:") {
+                let id = match id.parse() {
+                    Ok(v) => v,
+                    Err(_) => return Err(format!("{} <- shared curve id is not a number", command)),
+                };
+                if !project
+                    .shared_curves
+                    .set(id, crate::curve::CurveData::Keyframes(keyframes))
+                {
+                    return Err(format!("{} <- there is no shared curve {}", command, id));
+                }
+                video.update_shared_curves(&project.shared_curves);
+            } else {
+                let vid = match index {
+                    Some(index) => {
+                        crate::useful::get_elem_from_index_recursive_mut(video, &mut index.clone())
+                    }
+                    None => None,
+                };
+                let vid = match vid {
+                    Some(v) => v,
+                    None => return Err(format!("{} <- nothing is being edited", command)),
+                };
+                let mut curves = vid.named_curves_mut();
+                let names: Vec<_> = curves.iter().map(|(name, _)| *name).collect();
+                match curves.iter_mut().find(|(name, _)| *name == which) {
+                    Some((_, curve)) => **curve = crate::curve::CurveData::Keyframes(keyframes).into(),
+                    None => {
+                        return Err(format!(
+                            "{} <- curve must be {} or shared:[id]",
+                            command,
+                            names.join(", ")
+                        ))
+                    }
+                }
+            }
+            return Ok(vec![QctCommand::AppliedChangesToVideo]);
+        }
+        if command.starts_with("fit ") {
+            use crate::content::image_fit::ImageFit;
+            let fit = match command[4..].trim() {
+                "stretch" => ImageFit::Stretch,
+                "contain" => ImageFit::Contain,
+                "cover" => ImageFit::Cover,
+                "original" => ImageFit::Original,
+                "tile" => ImageFit::Tile,
+                _ => return Err(format!("fit [stretch/contain/cover/original/tile]")),
+            };
+            let vid = match index {
+                Some(index) => {
+                    crate::useful::get_elem_from_index_recursive_mut(video, &mut index.clone())
+                }
+                None => None,
+            };
+            let vid = match vid {
+                Some(v) => v,
+                None => return Err(format!("{} <- nothing is being edited", command)),
+            };
+            // the crop stays the same
+            let changes = match &vid.video.vt {
+                crate::video::VideoTypeEnum::Image(img) => {
+                    crate::video::VideoTypeChanges::Image(crate::content::image::ImageChanges {
+                        fit: Some(crate::content::image_fit::ImageFitSettings {
+                            fit,
+                            ..img.fit.clone()
+                        }),
+                        ..Default::default()
+                    })
+                }
+                crate::video::VideoTypeEnum::Raw(raw) => crate::video::VideoTypeChanges::Raw(
+                    crate::content::input_video::InputVideoChanges {
+                        fit: Some(crate::content::image_fit::ImageFitSettings {
+                            fit,
+                            ..raw.fit.clone()
+                        }),
+                        ..Default::default()
+                    },
+                ),
+                crate::video::VideoTypeEnum::Ffmpeg(ffmpeg) => {
+                    crate::video::VideoTypeChanges::Ffmpeg(
+                        crate::content::ffmpeg_vid::FfmpegVidChanges {
+                            fit: Some(crate::content::image_fit::ImageFitSettings {
+                                fit,
+                                ..ffmpeg.fit.clone()
+                            }),
+                            ..Default::default()
+                        },
+                    )
+                }
+                crate::video::VideoTypeEnum::AnimatedImage(img) => {
+                    crate::video::VideoTypeChanges::AnimatedImage(
+                        crate::content::animated_image::AnimatedImageChanges {
+                            fit: Some(crate::content::image_fit::ImageFitSettings {
+                                fit,
+                                ..img.fit.clone()
+                            }),
+                            ..Default::default()
+                        },
+                    )
+                }
+                _ => return Err(format!("{} <- only images and videos can be fitted", command)),
+            };
+            return Ok(vec![QctCommand::ApplyChanges(crate::video::VideoChanges {
+                video: Some(changes),
+                ..Default::default()
+            })]);
+        }
+        if command.starts_with("share ") {
+            let label = command[6..].trim();
+            if label.is_empty() {
+                return Err(format!("share [label]"));
+            }
+            if index.is_none() {
+                return Err(format!("{} <- nothing is being edited", command));
+            }
+            // the edited video stays where it is and can still be edited, instances draw its content
+            return Ok(vec![QctCommand::ApplyChanges(crate::video::VideoChanges {
+                label: Some(Some(label.to_string())),
+                ..Default::default()
+            })]);
+        }
+        if command == "unshare" {
+            if index.is_none() {
+                return Err(format!("{} <- nothing is being edited", command));
+            }
+            return Ok(vec![QctCommand::ApplyChanges(crate::video::VideoChanges {
+                label: Some(None),
+                ..Default::default()
+            })]);
+        }
         if command.starts_with("add ") {
             let command_next = &command[4..];
             let vid_type = command_next
@@ -45,6 +278,51 @@ impl QuickCommandsHandler {
             } else {
                 None
             };
+            if vid_type == "subtitles" {
+                let mut split = command_rest.unwrap_or("").splitn(3, ' ');
+                let (duration, template_path, path) =
+                    match (split.next(), split.next(), split.next()) {
+                        (Some(duration), Some(template_path), Some(path)) => {
+                            (duration, template_path, path)
+                        }
+                        _ => {
+                            return Err(format!(
+                                "add subtitles [project duration in seconds] [template path or -] [path]"
+                            ))
+                        }
+                    };
+                let duration: f64 = match duration.parse() {
+                    Ok(v) => v,
+                    Err(_) => return Err(format!("{} <- duration is not a number", command)),
+                };
+                let template = if template_path == "-" {
+                    crate::files::subtitles::DEFAULT_TEMPLATE.to_string()
+                } else {
+                    match crate::files::subtitles::read_template(&template_path.into()) {
+                        Ok(v) => v,
+                        Err(e) => return Err(format!("{} <- template: {}", command, e)),
+                    }
+                };
+                let cues = match crate::files::subtitles::import(&path.into()) {
+                    Ok(v) => v,
+                    Err(e) => return Err(format!("{} <- {}", command, e)),
+                };
+                let vid = match crate::files::subtitles::to_video(
+                    &cues,
+                    Some(duration),
+                    &template,
+                    project,
+                ) {
+                    Ok(v) => v,
+                    Err(e) => return Err(format!("{} <- template: {}", command, e)),
+                };
+                return Ok(vec![QctCommand::ApplyChanges(crate::video::VideoChanges {
+                    video: Some(crate::video::VideoTypeChanges::List(vec![
+                        crate::video::VideoTypeChanges_List::Insert(0, vid),
+                    ])),
+                    ..Default::default()
+                })]);
+            }
             let gcd = GenericContentData::new(project.clone());
             let vid = match vid_type.as_str() {
                 "list" => crate::video::VideoTypeEnum::List(Vec::new()),
@@ -81,9 +359,153 @@ impl QuickCommandsHandler {
                 } else {
                     crate::content::ffmpeg_vid::FfmpegVid::new(std::path::PathBuf::new(), gcd)
                 }),
+                "shape" => {
+                    let rest = command_rest.unwrap_or("rect").trim();
+                    let (kind, args) = match rest.split_once(' ') {
+                        Some((kind, args)) => (kind, args.trim()),
+                        None => (rest, ""),
+                    };
+                    let kind = match kind {
+                        "rect" => crate::content::shape::ShapeKind::Rectangle {
+                            corner_radius: crate::curve::CurveData::Constant(0.0).into(),
+                        },
+                        "ellipse" => crate::content::shape::ShapeKind::Ellipse,
+                        "polygon" => crate::content::shape::ShapeKind::Polygon {
+                            sides: crate::curve::CurveData::Constant(if args.is_empty() {
+                                6.0
+                            } else {
+                                match args.parse() {
+                                    Ok(v) => v,
+                                    Err(_) => {
+                                        return Err(format!(
+                                            "{} <- '{}' is not a number of sides",
+                                            command, args
+                                        ))
+                                    }
+                                }
+                            })
+                            .into(),
+                            rotation: crate::curve::CurveData::Constant(0.0).into(),
+                        },
+                        "path" => crate::content::shape::ShapeKind::Path(
+                            match crate::content::shape::ShapePath::parse(args) {
+                                Ok(v) => v,
+                                Err(e) => return Err(format!("{} <- {}", command, e)),
+                            },
+                        ),
+                        _ => {
+                            return Err(format!(
+                                "{} <- expected shape rect/ellipse/polygon [sides]/path [path data]",
+                                command
+                            ))
+                        }
+                    };
+                    crate::video::VideoTypeEnum::Shape(crate::content::shape::Shape::new(kind, gcd))
+                }
+                "svg" => crate::video::VideoTypeEnum::Svg(crate::content::svg::Svg::new(
+                    command_rest.unwrap_or("").into(),
+                    gcd,
+                )),
+                "anim" => crate::video::VideoTypeEnum::AnimatedImage(
+                    crate::content::animated_image::AnimatedImage::new(
+                        command_rest.unwrap_or("").into(),
+                        crate::content::animated_image::AnimationTiming::Stretch,
+                        gcd,
+                    ),
+                ),
+                "project" => crate::video::VideoTypeEnum::Project(
+                    crate::content::nested_project::NestedProject::new(
+                        command_rest.unwrap_or("").into(),
+                        Vec::new(),
+                        gcd,
+                    ),
+                ),
+                "instance" => match command_rest.map(|l| l.trim()) {
+                    Some(label) if !label.is_empty() => crate::video::VideoTypeEnum::Instance(
+                        crate::content::instance::Instance::new(label.to_string(), gcd),
+                    ),
+                    _ => return Err(format!("add instance [label]")),
+                },
+                "color" => crate::video::VideoTypeEnum::Solid(crate::types::Color::constant(
+                    match command_rest.map(|c| c.trim()) {
+                        None | Some("") => (1.0, 1.0, 1.0, 1.0),
+                        Some(c) => match crate::content::text_markup::parse_color(c) {
+                            Some(v) => v,
+                            None => {
+                                return Err(format!(
+                                    "{} <- expected color r,g,b[,a] (0-1) or #rrggbb[aa]",
+                                    command
+                                ))
+                            }
+                        },
+                    },
+                )),
+                "gradient" => {
+                    let mut args = command_rest.unwrap_or("").split_whitespace().peekable();
+                    let center = || {
+                        (
+                            crate::curve::CurveData::Constant(0.5).into(),
+                            crate::curve::CurveData::Constant(0.5).into(),
+                        )
+                    };
+                    let kind = match args.peek().copied() {
+                        Some("radial") => crate::content::gradient::GradientKind::Radial {
+                            center: center(),
+                            radius: crate::curve::CurveData::Constant(0.5).into(),
+                        },
+                        Some("conic") => crate::content::gradient::GradientKind::Conic {
+                            center: center(),
+                            angle: crate::curve::CurveData::Constant(0.0).into(),
+                        },
+                        _ => crate::content::gradient::GradientKind::Linear {
+                            start: (
+                                crate::curve::CurveData::Constant(0.5).into(),
+                                crate::curve::CurveData::Constant(0.0).into(),
+                            ),
+                            end: (
+                                crate::curve::CurveData::Constant(0.5).into(),
+                                crate::curve::CurveData::Constant(1.0).into(),
+                            ),
+                        },
+                    };
+                    if let Some("linear" | "radial" | "conic") = args.peek().copied() {
+                        args.next();
+                    }
+                    let mut colors = Vec::new();
+                    for arg in args {
+                        match crate::content::text_markup::parse_color(arg) {
+                            Some(v) => colors.push(v),
+                            None => {
+                                return Err(format!(
+                                    "{} <- '{}' is not a color, expected r,g,b[,a] (0-1) or #rrggbb[aa]",
+                                    command, arg
+                                ))
+                            }
+                        }
+                    }
+                    match colors.len() {
+                        0 => colors = vec![(0.0, 0.0, 0.0, 1.0), (1.0, 1.0, 1.0, 1.0)],
+                        // a single color is used for both ends
+                        1 => colors.push(colors[0]),
+                        _ => {}
+                    }
+                    // spread the colors out evenly
+                    let last = (colors.len() - 1) as f64;
+                    let stops = colors
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, c)| crate::content::gradient::GradientStop {
+                            position: crate::curve::CurveData::Constant(i as f64 / last).into(),
+                            color: crate::types::Color::constant(c),
+                        })
+                        .collect();
+                    crate::video::VideoTypeEnum::Gradient(crate::content::gradient::Gradient::new(
+                        kind, stops, gcd,
+                    ))
+                }
                 _ => {
                     return Err(format!(
-                        "{} <- expected list/effect/img/vid/ffmpeg",
+                        "{} <- expected list/effect/img/anim/svg/project/instance/vid/ffmpeg/shape/color/gradient/subtitles",
                         command
                     ))
                 }
@@ -541,6 +963,238 @@ impl QuickCommandsHandler {
                                             }
                                         }
                                     }
+                                    if "shape".starts_with(whatl) {
+                                        suggestions.push("shape".to_string());
+                                    }
+                                    if whatl == "shape" || whatl == "shape " {
+                                        suggestions.push("shape rect".to_string());
+                                        suggestions.push("shape ellipse".to_string());
+                                        suggestions.push("shape polygon [sides]".to_string());
+                                        suggestions.push("shape path [path data]".to_string());
+                                    } else if whatl.starts_with("shape ") {
+                                        for kind in ["rect", "ellipse", "polygon", "path"] {
+                                            if kind.starts_with(&whatl[6..]) {
+                                                suggestions.push(format!("shape {kind}"));
+                                            }
+                                        }
+                                    }
+                                    if "anim".starts_with(whatl) {
+                                        suggestions.push("anim".to_string());
+                                    }
+                                    if whatl == "anim" {
+                                        suggestions.push("anim [path]".to_string());
+                                    }
+                                    if whatl.starts_with("anim ") {
+                                        let path = &what[5..];
+                                        let last_slash = path.rfind("/").unwrap_or(0);
+                                        let dir = &path[..last_slash];
+                                        let file = if path.len() > last_slash + 1 {
+                                            Some(&path[last_slash + 1..])
+                                        } else {
+                                            None
+                                        };
+                                        if let Ok(dir_entries) =
+                                            std::fs::read_dir(std::path::PathBuf::from(dir))
+                                        {
+                                            for entry in dir_entries.filter_map(|e| e.ok()) {
+                                                let entry_path = entry.path();
+                                                let file_name_ok = match (file, entry_path.file_name()) {
+                                                    (Some(file), Some(file_name)) => {
+                                                        file_name.to_string_lossy().starts_with(file)
+                                                    }
+                                                    (Some(_), None) => false,
+                                                    (None, _) => true,
+                                                };
+                                                // only directories and (possibly) animated images
+                                                let type_ok = entry_path.is_dir()
+                                                    || matches!(
+                                                        entry_path
+                                                            .extension()
+                                                            .map(|e| e.to_string_lossy().to_lowercase())
+                                                            .as_deref(),
+                                                        Some("gif" | "png" | "apng" | "webp")
+                                                    );
+                                                if file_name_ok && type_ok {
+                                                    suggestions.push(format!(
+                                                        "anim {}",
+                                                        entry_path.to_string_lossy()
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if "svg".starts_with(whatl) {
+                                        suggestions.push("svg".to_string());
+                                    }
+                                    if whatl == "svg" {
+                                        suggestions.push("svg [path]".to_string());
+                                    }
+                                    if whatl.starts_with("svg ") {
+                                        let path = &what[4..];
+                                        let last_slash = path.rfind("/").unwrap_or(0);
+                                        let dir = &path[..last_slash];
+                                        let file = if path.len() > last_slash + 1 {
+                                            Some(&path[last_slash + 1..])
+                                        } else {
+                                            None
+                                        };
+                                        if let Ok(dir_entries) =
+                                            std::fs::read_dir(std::path::PathBuf::from(dir))
+                                        {
+                                            for entry in dir_entries.filter_map(|e| e.ok()) {
+                                                let entry_path = entry.path();
+                                                let file_name_ok = match (file, entry_path.file_name()) {
+                                                    (Some(file), Some(file_name)) => {
+                                                        file_name.to_string_lossy().starts_with(file)
+                                                    }
+                                                    (Some(_), None) => false,
+                                                    (None, _) => true,
+                                                };
+                                                // only directories and svg files
+                                                let type_ok = entry_path.is_dir()
+                                                    || matches!(
+                                                        entry_path
+                                                            .extension()
+                                                            .map(|e| e.to_string_lossy().to_lowercase())
+                                                            .as_deref(),
+                                                        Some("svg" | "svgz")
+                                                    );
+                                                if file_name_ok && type_ok {
+                                                    suggestions.push(format!(
+                                                        "svg {}",
+                                                        entry_path.to_string_lossy()
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if "project".starts_with(whatl) {
+                                        suggestions.push("project".to_string());
+                                    }
+                                    if whatl == "project" {
+                                        suggestions.push("project [path]".to_string());
+                                    }
+                                    if whatl.starts_with("project ") {
+                                        let path = &what[8..];
+                                        let last_slash = path.rfind("/").unwrap_or(0);
+                                        let dir = &path[..last_slash];
+                                        let file = if path.len() > last_slash + 1 {
+                                            Some(&path[last_slash + 1..])
+                                        } else {
+                                            None
+                                        };
+                                        if let Ok(dir_entries) =
+                                            std::fs::read_dir(std::path::PathBuf::from(dir))
+                                        {
+                                            for entry in dir_entries.filter_map(|e| e.ok()) {
+                                                let entry_path = entry.path();
+                                                let file_name_ok = match (file, entry_path.file_name()) {
+                                                    (Some(file), Some(file_name)) => {
+                                                        file_name.to_string_lossy().starts_with(file)
+                                                    }
+                                                    (Some(_), None) => false,
+                                                    (None, _) => true,
+                                                };
+                                                if file_name_ok {
+                                                    suggestions.push(format!(
+                                                        "project {}",
+                                                        entry_path.to_string_lossy()
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                    }
+                                    if "instance".starts_with(whatl) {
+                                        suggestions.push("instance".to_string());
+                                    }
+                                    if whatl == "instance" || whatl.starts_with("instance ") {
+                                        let label = what.get(9..).unwrap_or("");
+                                        for l in project.shared_videos.labels() {
+                                            if l.starts_with(label) {
+                                                suggestions.push(format!("instance {l}"));
+                                            }
+                                        }
+                                    }
+                                    if "color".starts_with(whatl) {
+                                        suggestions.push("color".to_string());
+                                    }
+                                    if whatl == "color" {
+                                        suggestions.push("color [r,g,b[,a] or #rrggbb[aa]]".to_string());
+                                    }
+                                    if "gradient".starts_with(whatl) {
+                                        suggestions.push("gradient".to_string());
+                                    }
+                                    if whatl == "gradient" || whatl == "gradient " {
+                                        for kind in ["linear", "radial", "conic"] {
+                                            suggestions.push(format!("gradient {kind} [colors...]"));
+                                        }
+                                    } else if whatl.starts_with("gradient ") {
+                                        for kind in ["linear", "radial", "conic"] {
+                                            if kind.starts_with(&whatl[9..]) {
+                                                suggestions.push(format!("gradient {kind}"));
+                                            }
+                                        }
+                                    }
+                                    if "subtitles".starts_with(whatl) {
+                                        suggestions.push("subtitles".to_string());
+                                    }
+                                    if whatl == "subtitles" {
+                                        suggestions.push(
+                                            "subtitles [duration] [template path or -] [path]"
+                                                .to_string(),
+                                        );
+                                    }
+                                    // complete the path once the duration and template are there
+                                    let subtitles_args = what
+                                        .get(10..)
+                                        .filter(|_| whatl.starts_with("subtitles "))
+                                        .and_then(|args| {
+                                            let mut split = args.splitn(3, ' ');
+                                            match (split.next(), split.next(), split.next()) {
+                                                (Some(duration), Some(template), Some(path)) => {
+                                                    Some((format!("{duration} {template} "), path))
+                                                }
+                                                _ => None,
+                                            }
+                                        });
+                                    if let Some((args, path)) = subtitles_args {
+                                        let last_slash = path.rfind("/").unwrap_or(0);
+                                        let dir = &path[..last_slash];
+                                        let file = if path.len() > last_slash + 1 {
+                                            Some(&path[last_slash + 1..])
+                                        } else {
+                                            None
+                                        };
+                                        if let Ok(dir_entries) =
+                                            std::fs::read_dir(std::path::PathBuf::from(dir))
+                                        {
+                                            for entry in dir_entries.filter_map(|e| e.ok()) {
+                                                let entry_path = entry.path();
+                                                let file_name_ok = match (file, entry_path.file_name()) {
+                                                    (Some(file), Some(file_name)) => {
+                                                        file_name.to_string_lossy().starts_with(file)
+                                                    }
+                                                    (Some(_), None) => false,
+                                                    (None, _) => true,
+                                                };
+                                                // only directories and subtitle files
+                                                let type_ok = entry_path.is_dir()
+                                                    || matches!(
+                                                        entry_path
+                                                            .extension()
+                                                            .map(|e| e.to_string_lossy().to_lowercase())
+                                                            .as_deref(),
+                                                        Some("srt" | "vtt")
+                                                    );
+                                                if file_name_ok && type_ok {
+                                                    suggestions.push(format!(
+                                                        "subtitles {args}{}",
+                                                        entry_path.to_string_lossy()
+                                                    ));
+                                                }
+                                            }
+                                        }
+                                    }
                                     for suggestion in suggestions {
                                         let s = format!("add {}", suggestion);
                                         gen.send(QctCompletions::Set(
@@ -553,6 +1207,57 @@ impl QuickCommandsHandler {
                                 }
                             }
                         }
+                        5 => {
+                            if editing_part_abstract.is_some() {
+                                for (cmd, args) in [
+                                    ("export curve ", "[x/y/w/h/opacity/rotation/.../shared:id] [samples] [path]"),
+                                    ("import curve ", "[x/y/w/h/opacity/rotation/.../shared:id] [path]"),
+                                    ("export subtitles ", "[duration] [path]"),
+                                    ("share ", "[label]"),
+                                    ("unshare", ""),
+                                    ("fit ", "[stretch/contain/cover/original/tile]"),
+                                ] {
+                                    let s = if cmd.starts_with(&query) {
+                                        format!("{}{}", cmd, args)
+                                    } else if query.starts_with(cmd) {
+                                        query.clone()
+                                    } else {
+                                        continue;
+                                    };
+                                    gen.send(QctCompletions::Set(
+                                        possible_commands.len(),
+                                        s.clone(),
+                                    ))
+                                    .unwrap();
+                                    possible_commands.push(s);
+                                }
+                            }
+                        }
+                        6 => {
+                            if "diagnostics".starts_with(&query) || query.starts_with("diagnostics")
+                            {
+                                let diagnostics = project.diagnostics.entries();
+                                if !diagnostics.is_empty() {
+                                    let s = "diagnostics clear".to_string();
+                                    gen.send(QctCompletions::Set(
+                                        possible_commands.len(),
+                                        s.clone(),
+                                    ))
+                                    .unwrap();
+                                    possible_commands.push(s);
+                                }
+                                // newest first
+                                for diagnostic in diagnostics.iter().rev().take(10) {
+                                    let s = format!("! {}", diagnostic);
+                                    gen.send(QctCompletions::Set(
+                                        possible_commands.len(),
+                                        s.clone(),
+                                    ))
+                                    .unwrap();
+                                    possible_commands.push(s);
+                                }
+                            }
+                        }
                         _ => {
                             if "test".starts_with(&query) {
                                 let s = "test".to_string();
@@ -595,6 +1300,21 @@ enum EditingPartAbstract {
     Ffmpeg {
         path: std::path::PathBuf,
     },
+    Shape,
+    Solid,
+    Gradient,
+    Svg {
+        path: std::path::PathBuf,
+    },
+    AnimatedImage {
+        path: std::path::PathBuf,
+    },
+    Project {
+        path: std::path::PathBuf,
+    },
+    Instance {
+        label: String,
+    },
 }
 impl From<&crate::video::Video> for EditingPartAbstract {
     fn from(vid: &crate::video::Video) -> Self {
@@ -619,6 +1339,21 @@ impl From<&crate::video::Video> for EditingPartAbstract {
             crate::video::VideoTypeEnum::Ffmpeg(vid) => Self::Ffmpeg {
                 path: vid.path().clone(),
             },
+            crate::video::VideoTypeEnum::Shape(_) => Self::Shape,
+            crate::video::VideoTypeEnum::Solid(_) => Self::Solid,
+            crate::video::VideoTypeEnum::Gradient(_) => Self::Gradient,
+            crate::video::VideoTypeEnum::Svg(svg) => Self::Svg {
+                path: svg.path().clone(),
+            },
+            crate::video::VideoTypeEnum::AnimatedImage(img) => Self::AnimatedImage {
+                path: img.path().clone(),
+            },
+            crate::video::VideoTypeEnum::Project(proj) => Self::Project {
+                path: proj.path().clone(),
+            },
+            crate::video::VideoTypeEnum::Instance(instance) => Self::Instance {
+                label: instance.label().clone(),
+            },
         }
     }
 }
@@ -659,4 +1394,6 @@ pub enum QctCommand {
     SetEditingTo(u32),
     SetQueryTo(String),
     ApplyChanges(crate::video::VideoChanges),
+    /// The video was changed directly, so it has to be redrawn.
+    AppliedChangesToVideo,
 }
@@ -63,6 +63,9 @@ impl EditorWindowLayoutContentTrait for QVidRunner {
                 crate::gui::quick_commands::QctCommand::SetQueryTo(new_query) => {
                     self.query = new_query
                 }
+                crate::gui::quick_commands::QctCommand::AppliedChangesToVideo => self.data().requests.push(
+                    crate::gui::speedy2d::request::EditorWindowLayoutRequest::AppliedChangesToVideo,
+                ),
                 crate::gui::quick_commands::QctCommand::ApplyChanges(changes) => {
                     println!("Applying changes!");
                     // input.add_custom_action(crate::gui::speedy2d::layout::CustomDrawActions::)
@@ -91,6 +94,7 @@ impl EditorWindowLayoutContentTrait for QVidRunner {
                     crate::gui::speedy2d::layout::CustomDrawActions::ChangedVideo => {
                         self.command_handler.vid_updated()
                     }
+                    crate::gui::speedy2d::layout::CustomDrawActions::PreviewProgressChanged(_) => {}
                 }
             }
         }
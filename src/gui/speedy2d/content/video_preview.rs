@@ -227,6 +227,7 @@ impl EditorWindowLayoutContentTrait for VideoPreview {
                         self.video.pause(true); // clear cache
                         self.video.resume(); // start producing frames again
                     },
+                    CustomDrawActions::PreviewProgressChanged(_) => (),
                 };
             };
         };
@@ -283,7 +284,7 @@ impl EditorWindowLayoutContentTrait for VideoPreview {
                     let mouse_held_from_progress_bar = self.mouse_left_button_down_started_on_progress_bar && input.owned.mouse_down_buttons.contains_key(&speedy2d::window::MouseButton::Left);
                     self.mouse_on_progress_bar = if mouse_held_from_progress_bar || on_progress_bar(mouse_pos) {
                         let prog = ((mouse_pos.0 as f64 - 0.1) / 0.8).max(0.0).min(1.0);
-                        if mouse_held_from_progress_bar { self.progress = prog; self.video.set_desired_progress(prog); };
+                        if mouse_held_from_progress_bar { self.progress = prog; self.video.set_desired_progress(prog); input.add_custom_action(CustomDrawActions::PreviewProgressChanged(prog)); };
                         Some(prog)
                     } else { None };
                 },
@@ -338,7 +339,7 @@ impl EditorWindowLayoutContentTrait for VideoPreview {
                     }
                     match self.mouse_on_progress_bar {
                         Some(prog) => match btn {
-                            speedy2d::window::MouseButton::Left => {self.progress = prog; self.video.set_desired_progress(prog); self.mouse_left_button_down_started_on_progress_bar = true; }
+                            speedy2d::window::MouseButton::Left => {self.progress = prog; self.video.set_desired_progress(prog); input.add_custom_action(CustomDrawActions::PreviewProgressChanged(prog)); self.mouse_left_button_down_started_on_progress_bar = true; }
                             _ => (),
                         },
                         None => {
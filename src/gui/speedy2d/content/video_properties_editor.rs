@@ -12,7 +12,7 @@ use speedy2d::{
 
 use crate::{
     content::{content::Content, image::ImageChanges},
-    curve::Curve,
+    curve::{ChainPoint, ChainSegment, Curve},
     effect::{self, effects::EffectT},
     gui::speedy2d::{
         content_list::{EditorWindowLayoutContent, EditorWindowLayoutContentEnum},
@@ -24,7 +24,8 @@ use crate::{
     },
     useful,
     video::{
-        Video, VideoChanges, VideoType, VideoTypeChanges, VideoTypeChanges_List, VideoTypeEnum,
+        CompositingMethod, Video, VideoChanges, VideoType, VideoTypeChanges, VideoTypeChanges_List,
+        VideoTypeEnum,
     },
 };
 
@@ -46,6 +47,9 @@ pub struct VideoPropertiesEditor {
     tabs: Vec<Option<Box<dyn ExtraTabsInfo>>>,
     tabs_info: Vec<AnyTabInfo>,
 
+    /// The video preview's progress and the progress of the part that is being edited at that point in time (see useful::get_progress_from_index_recursive).
+    preview_progress: (f64, Option<f64>),
+
     layout_content_data: EditorWindowLayoutContentData,
 }
 #[derive(Default)]
@@ -112,7 +116,9 @@ impl ExtraTabsInfo for EtGeneral {
             "y-position",
             "width",
             "height",
+            "opacity",
             "time",
+            "compositing",
             "change type",
         ];
         let options = opts.len();
@@ -155,7 +161,7 @@ impl ExtraTabsInfo for EtGeneral {
                 MouseAction::Moved => (),
                 MouseAction::ButtonDown(_) => (),
                 MouseAction::ButtonUp(btn) => {
-                    let possibilities = 6;
+                    let possibilities = 8;
                     let mouse_pos = input.clonable.mouse_pos;
                     let mouse_index = if 0.0 < mouse_pos.0
                         && mouse_pos.0 < 1.0
@@ -184,10 +190,11 @@ impl ExtraTabsInfo for EtGeneral {
                                     supr.tabs.push(Some(Box::new(EtCurve::new(
                                         "x".to_string(),
                                         0,
+                                        |v| &v.set_pos.x,
                                         |v, c| {
                                             v.set_pos.x = c;
                                         },
-                                        supr.editing.0.as_ref().unwrap().1.set_pos.x.clone(),
+                                        &supr.editing.0.as_ref().unwrap().1,
                                     ))));
                                 };
                             }
@@ -204,10 +211,11 @@ impl ExtraTabsInfo for EtGeneral {
                                     supr.tabs.push(Some(Box::new(EtCurve::new(
                                         "y".to_string(),
                                         1,
+                                        |v| &v.set_pos.y,
                                         |v, c| {
                                             v.set_pos.y = c;
                                         },
-                                        supr.editing.0.as_ref().unwrap().1.set_pos.y.clone(),
+                                        &supr.editing.0.as_ref().unwrap().1,
                                     ))));
                                 };
                             }
@@ -224,10 +232,11 @@ impl ExtraTabsInfo for EtGeneral {
                                     supr.tabs.push(Some(Box::new(EtCurve::new(
                                         "width".to_string(),
                                         2,
+                                        |v| &v.set_pos.w,
                                         |v, c| {
                                             v.set_pos.w = c;
                                         },
-                                        supr.editing.0.as_ref().unwrap().1.set_pos.w.clone(),
+                                        &supr.editing.0.as_ref().unwrap().1,
                                     ))));
                                 };
                             }
@@ -244,14 +253,36 @@ impl ExtraTabsInfo for EtGeneral {
                                     supr.tabs.push(Some(Box::new(EtCurve::new(
                                         "height".to_string(),
                                         3,
+                                        |v| &v.set_pos.h,
                                         |v, c| {
                                             v.set_pos.h = c;
                                         },
-                                        supr.editing.0.as_ref().unwrap().1.set_pos.h.clone(),
+                                        &supr.editing.0.as_ref().unwrap().1,
                                     ))));
                                 };
                             }
                             4 => {
+                                if let Some(index) =
+                                    VideoPropertiesEditor::get_extra_tabs_index_where(
+                                        &supr.tabs,
+                                        |e| false,
+                                    )
+                                {
+                                    supr.change_tab_to(index, false);
+                                } else {
+                                    supr.change_tab_to(supr.tabs.len(), false);
+                                    supr.tabs.push(Some(Box::new(EtCurve::new(
+                                        "opacity".to_string(),
+                                        4,
+                                        |v| &v.opacity,
+                                        |v, c| {
+                                            v.opacity = c;
+                                        },
+                                        &supr.editing.0.as_ref().unwrap().1,
+                                    ))));
+                                };
+                            }
+                            5 => {
                                 if let Some(index) =
                                     VideoPropertiesEditor::get_extra_tabs_index_where(
                                         &supr.tabs,
@@ -268,7 +299,20 @@ impl ExtraTabsInfo for EtGeneral {
                                     ))));
                                 };
                             }
-                            5 => {
+                            6 => {
+                                if let Some(index) =
+                                    VideoPropertiesEditor::get_extra_tabs_index_where(
+                                        &supr.tabs,
+                                        |e| false,
+                                    )
+                                {
+                                    supr.change_tab_to(index, false);
+                                } else {
+                                    supr.change_tab_to(supr.tabs.len(), false);
+                                    supr.tabs.push(Some(Box::new(EtCompositing::new())));
+                                };
+                            }
+                            7 => {
                                 if let Some(index) =
                                     VideoPropertiesEditor::get_extra_tabs_index_where(
                                         &supr.tabs,
@@ -454,6 +498,9 @@ impl ExtraTabsInfo for EtTime {
                                                 pos: None,
                                                 start: Some(self.start),
                                                 length: Some(self.end - self.start),
+                                                opacity: None,
+                                                compositing: None,
+                                                label: None,
                                                 video: None,
                                                 wrap: None,
                                                 replace: None,
@@ -481,6 +528,147 @@ impl ExtraTabsInfo for EtTime {
     }
 }
 
+/// Lets the user pick the video's compositing method, including the blend modes.
+struct EtCompositing {
+    possibilities: Vec<(String, Option<CompositingMethod>)>,
+}
+impl EtCompositing {
+    pub fn new() -> Self {
+        let mut possibilities = vec![
+            ("inherit from parent".to_string(), None),
+            (
+                "ignore (hidden)".to_string(),
+                Some(CompositingMethod::Ignore),
+            ),
+            ("opaque".to_string(), Some(CompositingMethod::Opaque)),
+            ("direct".to_string(), Some(CompositingMethod::Direct)),
+            (
+                "normal (transparency)".to_string(),
+                Some(CompositingMethod::TransparencySupport),
+            ),
+        ];
+        for mode in crate::blend_mode::BlendMode::ALL {
+            possibilities.push((
+                mode.name().to_string(),
+                Some(CompositingMethod::Blend(mode)),
+            ));
+        }
+        Self { possibilities }
+    }
+}
+impl ExtraTabsInfo for EtCompositing {
+    fn title(&self) -> StringTypeForExtraTabTitle {
+        "compositing".into()
+    }
+    fn draw_icon(
+        &mut self,
+        vis: f32,
+        hovered: f32,
+        selected: f32,
+        graphics: &mut speedy2d::Graphics2D,
+        position: &(f32, f32, f32, f32),
+        shared_data: &SharedEtData,
+    ) {
+        graphics.draw_line(
+            Vector2 {
+                x: position.0,
+                y: position.1 + position.3,
+            },
+            Vector2 {
+                x: position.0 + position.2,
+                y: position.1,
+            },
+            1.0,
+            shared_data.unified_color,
+        );
+    }
+    fn draw(
+        &mut self,
+        supr: &mut VideoPropertiesEditor,
+        draw_opts: &mut crate::gui::speedy2d::layout::EditorWindowLayoutContentDrawOptions,
+        graphics: &mut speedy2d::Graphics2D,
+        position: &(f32, f32, f32, f32),
+        input: &mut crate::gui::speedy2d::layout::UserInput,
+    ) {
+        let vis = draw_opts.visibility_factors.video_properties_editor_tabs;
+        let current = supr
+            .editing
+            .0
+            .as_ref()
+            .map(|(_, editing)| Self::key(&editing.compositing));
+        let element_height = position.3 / self.possibilities.len() as f32;
+        for (i, possibility) in self.possibilities.iter().enumerate() {
+            let y = position.1 + position.3 * i as f32 / self.possibilities.len() as f32;
+            let txt = draw_opts.assets_manager.get_default_font().layout_text(
+                possibility.0.as_str(),
+                0.5 * element_height,
+                TextOptions::new(),
+            );
+            // the current method is highlighted
+            let brightness = if current.as_deref() == Some(&Self::key(&possibility.1)) {
+                1.0
+            } else {
+                0.7
+            };
+            graphics.draw_text(
+                Vector2 {
+                    x: position.0,
+                    y: y + element_height * 0.25,
+                },
+                Color::from_rgba(brightness, brightness, brightness, vis),
+                &txt,
+            );
+        }
+    }
+    fn handle_input(
+        &mut self,
+        supr: &mut VideoPropertiesEditor,
+        draw_opts: &mut crate::gui::speedy2d::layout::EditorWindowLayoutContentDrawOptions,
+        input: &mut crate::gui::speedy2d::layout::UserInput,
+    ) {
+        if let crate::gui::speedy2d::layout::InputAction::Mouse(MouseAction::ButtonUp(
+            speedy2d::window::MouseButton::Left,
+        )) = &input.owned.action
+        {
+            let (mx, my) = input.clonable.mouse_pos.clone();
+            if mx >= 0.0 && mx <= 1.0 && my >= 0.0 && my < 1.0 {
+                if let Some((_, compositing)) = self
+                    .possibilities
+                    .get((my * self.possibilities.len() as f32) as usize)
+                {
+                    supr.data()
+                        .requests
+                        .push(EditorWindowLayoutRequest::EditingChangesApply(
+                            VideoChanges {
+                                compositing: Some(compositing.clone()),
+                                ..Default::default()
+                            },
+                        ));
+                }
+            }
+        }
+    }
+    fn update(&mut self, supr: &mut VideoPropertiesEditor, ud: ETUpdate) {
+        match ud {
+            ETUpdate::VideoWasUpdated(_) => (),
+        }
+    }
+}
+impl EtCompositing {
+    /// CompositingMethod can't be compared (because of Manual), so this is used instead.
+    fn key(compositing: &Option<CompositingMethod>) -> String {
+        match compositing {
+            None => String::new(),
+            Some(CompositingMethod::Ignore) => "_".to_string(),
+            Some(CompositingMethod::Opaque) => "=".to_string(),
+            Some(CompositingMethod::Direct) => "|".to_string(),
+            Some(CompositingMethod::TransparencySupport) => "*".to_string(),
+            Some(CompositingMethod::Blend(mode)) => format!("b{}", mode.to_char()),
+            Some(CompositingMethod::Manual(_)) => "manual".to_string(),
+        }
+    }
+}
+
 struct EtChangeType {
     possibilities: Option<Vec<(String, Option<crate::video::VideoChangesReplaceWith>)>>,
 }
@@ -602,6 +790,50 @@ impl ExtraTabsInfo for EtChangeType {
                             Some(crate::video::VideoChangesReplaceWith::Image),
                         ),
                     ],
+                    VideoTypeEnum::Svg(..) | VideoTypeEnum::AnimatedImage(..) => vec![
+                        (
+                            "text (showing the path)".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::Text),
+                        ),
+                        (
+                            "image (with the same path)".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::Image),
+                        ),
+                    ],
+                    VideoTypeEnum::Instance(..) => vec![(
+                        "list".to_string(),
+                        Some(crate::video::VideoChangesReplaceWith::List),
+                    )],
+                    VideoTypeEnum::Project(..) => vec![
+                        (
+                            "text (showing the path)".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::Text),
+                        ),
+                        (
+                            "list".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::List),
+                        ),
+                    ],
+                    VideoTypeEnum::Solid(..) | VideoTypeEnum::Gradient(..) => vec![
+                        (
+                            "list".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::List),
+                        ),
+                        (
+                            "shape".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::Shape),
+                        ),
+                    ],
+                    VideoTypeEnum::Shape(..) => vec![
+                        (
+                            "list".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::List),
+                        ),
+                        (
+                            "text".to_string(),
+                            Some(crate::video::VideoChangesReplaceWith::Text),
+                        ),
+                    ],
                 });
             }
         }
@@ -655,6 +887,9 @@ impl ExtraTabsInfo for EtChangeType {
                                             pos: None,
                                             start: None,
                                             length: None,
+                                            opacity: None,
+                                            compositing: None,
+                                            label: None,
                                             video: None,
                                             wrap: None,
                                             replace: Some(p.clone()),
@@ -686,17 +921,184 @@ impl ExtraTabsInfo for EtChangeType {
 struct EtCurve {
     name: String,
     id: u32,
+    read_curve: fn(&Video) -> &Curve,
+    /// Writes the curve to the video. Since changes are sent as VideoChanges, this may only change the video's position curves.
     write_changes: fn(&mut Video, Curve),
     curve: Curve,
+    /// None if the curve can't be edited graphically (see Curve::to_chain_points).
+    points: Option<Vec<ChainPoint>>,
+    /// The values at the bottom and at the top of the graph.
+    view: (f64, f64),
+    dragging: Option<EtCurveDrag>,
+    context_menu: Option<EtCurveContextMenu>,
+}
+enum EtCurveDrag {
+    Point(usize),
+    /// The index of the point where the Bezier segment starts and whether this is the second control point.
+    Handle(usize, bool),
+    /// The value that was under the mouse when panning started. Panning keeps it under the mouse.
+    Pan(f64),
+}
+struct EtCurveContextMenu {
+    /// Relative to the tab's area, like the mouse position.
+    pos: (f32, f32),
+    /// The index of the point where the segment under the mouse starts.
+    segment: usize,
+    /// The point that was right-clicked, if any.
+    point: Option<usize>,
+    items: Vec<EtCurveMenuItem>,
+}
+enum EtCurveMenuItem {
+    SetSegment(ChainSegment),
+    RemovePoint,
+    MakeEditable,
+    ResetView,
+}
+impl EtCurveMenuItem {
+    fn text(&self) -> &'static str {
+        match self {
+            Self::SetSegment(ChainSegment::Hold) => "segment: hold",
+            Self::SetSegment(ChainSegment::Linear) => "segment: linear",
+            Self::SetSegment(ChainSegment::Smooth) => "segment: smooth",
+            Self::SetSegment(ChainSegment::Bezier(..)) => "segment: bezier",
+            Self::RemovePoint => "remove point",
+            Self::MakeEditable => "make editable (replaces the curve)",
+            Self::ResetView => "reset view",
+        }
+    }
 }
 impl EtCurve {
-    pub fn new(name: String, id: u32, write_changes: fn(&mut Video, Curve), curve: Curve) -> Self {
-        Self {
+    /// How close (in pixels) the mouse has to be to a point or control point to grab it.
+    const GRAB_DISTANCE: f32 = 8.0;
+    /// How many points are used when a curve that can't be edited graphically is made editable.
+    const MAKE_EDITABLE_SAMPLES: usize = 11;
+
+    pub fn new(
+        name: String,
+        id: u32,
+        read_curve: fn(&Video) -> &Curve,
+        write_changes: fn(&mut Video, Curve),
+        video: &Video,
+    ) -> Self {
+        let curve = read_curve(video).clone();
+        let mut s = Self {
             name,
             id,
+            read_curve,
             write_changes,
+            points: curve.to_chain_points(),
             curve,
+            view: (0.0, 1.0),
+            dragging: None,
+            context_menu: None,
+        };
+        s.reset_view();
+        s
+    }
+
+    /// Zooms so that the entire curve (and the 0.0 and 1.0 lines) can be seen.
+    fn reset_view(&mut self) {
+        let (mut min, mut max) = (0.0f64, 1.0f64);
+        for i in 0..=100 {
+            let v = self.curve.get_value(i as f64 / 100.0);
+            if v.is_finite() {
+                min = min.min(v);
+                max = max.max(v);
+            }
+        }
+        if let Some(points) = &self.points {
+            for p in points {
+                min = min.min(p.value);
+                max = max.max(p.value);
+            }
         }
+        let margin = (max - min) * 0.1;
+        self.view = (min - margin, max + margin);
+    }
+
+    /// Converts a value to a y-coordinate (0.0 is the top of the graph, 1.0 the bottom).
+    fn value_to_y(&self, value: f64) -> f32 {
+        ((self.view.1 - value) / (self.view.1 - self.view.0)) as f32
+    }
+    fn y_to_value(&self, y: f32) -> f64 {
+        self.view.1 - y as f64 * (self.view.1 - self.view.0)
+    }
+
+    /// The control points of the Bezier segment starting at point i as (time, value).
+    fn handles(points: &[ChainPoint], i: usize) -> Option<((f64, f64), (f64, f64))> {
+        match (points.get(i), points.get(i + 1)) {
+            (
+                Some(ChainPoint {
+                    time: t0,
+                    value: v0,
+                    segment: ChainSegment::Bezier((x1, y1), (x2, y2)),
+                }),
+                Some(ChainPoint {
+                    time: t1,
+                    value: v1,
+                    ..
+                }),
+            ) => Some((
+                (t0 + (t1 - t0) * x1, v0 + y1),
+                (t0 + (t1 - t0) * x2, v1 + y2),
+            )),
+            _ => None,
+        }
+    }
+
+    /// Finds the point or control point under the mouse. Control points are preferred because they can be on top of their point.
+    fn grab(&self, mouse_pos: (f32, f32), size: (f32, f32)) -> Option<EtCurveDrag> {
+        let points = self.points.as_ref()?;
+        let close = |(t, v): (f64, f64)| {
+            let dx = (t as f32 - mouse_pos.0) * size.0;
+            let dy = (self.value_to_y(v) - mouse_pos.1) * size.1;
+            dx * dx + dy * dy <= Self::GRAB_DISTANCE * Self::GRAB_DISTANCE
+        };
+        for i in 0..points.len() {
+            if let Some((h1, h2)) = Self::handles(points, i) {
+                if close(h1) {
+                    return Some(EtCurveDrag::Handle(i, false));
+                }
+                if close(h2) {
+                    return Some(EtCurveDrag::Handle(i, true));
+                }
+            }
+        }
+        points
+            .iter()
+            .position(|p| close((p.time, p.value)))
+            .map(EtCurveDrag::Point)
+    }
+
+    /// Builds a curve from the points and sends it to the video.
+    fn apply(&mut self, supr: &mut VideoPropertiesEditor) {
+        if let Some(points) = &self.points {
+            self.curve = Curve::from_chain_points(points);
+        }
+        if let Some((_, vid)) = &mut supr.editing.0 {
+            (self.write_changes)(vid, self.curve.clone());
+            supr.layout_content_data
+                .requests
+                .push(EditorWindowLayoutRequest::EditingChangesApply(
+                    VideoChanges {
+                        pos: Some((
+                            Some(vid.set_pos.x.clone()),
+                            Some(vid.set_pos.y.clone()),
+                            Some(vid.set_pos.w.clone()),
+                            Some(vid.set_pos.h.clone()),
+                        )),
+                        opacity: Some(vid.opacity.clone()),
+                        ..Default::default()
+                    },
+                ));
+        }
+    }
+
+    fn menu_item_height(supr: &VideoPropertiesEditor, size: (f32, f32)) -> f32 {
+        supr.get_height_of_element_abs(size.1) / size.1
+    }
+    fn menu_width() -> f32 {
+        0.5
     }
 }
 impl ExtraTabsInfo for EtCurve {
@@ -734,32 +1136,18 @@ impl ExtraTabsInfo for EtCurve {
         input: &mut crate::gui::speedy2d::layout::UserInput,
     ) {
         let vis = draw_opts.visibility_factors.video_properties_editor_tabs;
-        let diagram_width = position.2.ceil() as _;
-        let diagram_width_minus_one_float = (diagram_width - 1) as f64;
-        let mut values = Vec::with_capacity(diagram_width);
-        let (mut min, mut max) = (0.0, 1.0);
-        for i in 0..diagram_width {
-            let v = self
-                .curve
-                .get_value(i as f64 / diagram_width_minus_one_float);
-            if v > max {
-                max = v;
-            }
-            if v < min {
-                min = v;
-            }
-            values.push(v);
-        }
-        let minmaxdiff = max - min;
-        let mut prev_point = None;
+        let font = draw_opts.assets_manager.get_default_font();
+        let per_item_height = supr.get_height_of_element_abs(draw_opts.my_size_in_pixels.1);
+        let to_px = |t: f64, v: f64| Vector2 {
+            x: position.0 + position.2 * t as f32,
+            y: position.1 + position.3 * self.value_to_y(v),
+        };
         {
             // draw 0.0 and 1.0 lines
             let left = position.0;
             let right = position.0 + position.2;
-            let y = position.1;
-            let h = position.3;
-            let y1 = y + h * ((max - 1.0) / minmaxdiff) as f32;
-            let y2 = y + h * (max / minmaxdiff) as f32;
+            let y1 = to_px(0.0, 1.0).y;
+            let y2 = to_px(0.0, 0.0).y;
             graphics.draw_line(
                 Vector2 { x: left, y: y1 },
                 Vector2 { x: right, y: y1 },
@@ -773,23 +1161,139 @@ impl ExtraTabsInfo for EtCurve {
                 Color::from_rgba(0.7, 0.7, 1.0, vis),
             );
         }
-        for (i, v) in values.iter().enumerate() {
+        // draw the preview's current progress
+        if let Some(progress) = supr.preview_progress.1 {
+            if 0.0 <= progress && progress <= 1.0 {
+                let x = to_px(progress, 0.0).x;
+                graphics.draw_line(
+                    Vector2 { x, y: position.1 },
+                    Vector2 {
+                        x,
+                        y: position.1 + position.3,
+                    },
+                    1.0,
+                    Color::from_rgba(0.0, 1.0, 1.0, vis * 0.5),
+                );
+                graphics.draw_circle(
+                    to_px(progress, self.curve.get_value(progress)),
+                    3.0,
+                    Color::from_rgba(0.0, 1.0, 1.0, vis),
+                );
+            }
+        }
+        // draw the curve
+        let diagram_width = position.2.ceil() as usize;
+        let diagram_width_minus_one_float = (diagram_width.max(2) - 1) as f64;
+        let mut prev_point = None;
+        for i in 0..diagram_width {
+            let v = self
+                .curve
+                .get_value(i as f64 / diagram_width_minus_one_float);
             let v = if vis < 1.0 {
-                // on fadein/fadeout, smoothly fade to a flat line of y=0.5
-                0.5 + (v - 0.5) * (vis * vis) as f64
+                // on fadein/fadeout, smoothly fade to a flat line in the middle of the view
+                let middle = (self.view.0 + self.view.1) / 2.0;
+                middle + (v - middle) * (vis * vis) as f64
             } else {
-                *v
+                v
             };
-            let mut pos_y = position.1 + position.3 * ((max - v) / minmaxdiff) as f32;
             let this_vec = Vector2 {
                 x: position.0 + i as f32,
-                y: pos_y,
+                y: position.1 + position.3 * self.value_to_y(v),
             };
             if let Some(prev) = prev_point.take() {
                 graphics.draw_line(prev, this_vec, 1.0, Color::from_rgba(1.0, 1.0, 1.0, vis));
             }
             prev_point = Some(this_vec);
         }
+        // draw the points and control points
+        if let Some(points) = &self.points {
+            for (i, p) in points.iter().enumerate() {
+                let pos = to_px(p.time, p.value);
+                if let Some((h1, h2)) = Self::handles(points, i) {
+                    let next = &points[i + 1];
+                    for (from, h) in [(pos, h1), (to_px(next.time, next.value), h2)] {
+                        let h = to_px(h.0, h.1);
+                        graphics.draw_line(from, h, 1.0, Color::from_rgba(1.0, 0.8, 0.5, vis * 0.6));
+                        graphics.draw_circle(h, 3.0, Color::from_rgba(1.0, 0.8, 0.5, vis));
+                    }
+                }
+                let dragged = matches!(self.dragging, Some(EtCurveDrag::Point(d)) if d == i);
+                graphics.draw_circle(
+                    pos,
+                    if dragged { 5.0 } else { 4.0 },
+                    Color::from_rgba(1.0, if dragged { 0.6 } else { 1.0 }, 0.6, vis),
+                );
+            }
+            if let Some(EtCurveDrag::Point(i)) = &self.dragging {
+                if let Some(p) = points.get(*i) {
+                    let text = font.layout_text(
+                        format!("{:.3} | {:.3}", p.time, p.value).as_str(),
+                        per_item_height * 0.6,
+                        TextOptions::new(),
+                    );
+                    graphics.draw_text(
+                        Vector2 {
+                            x: position.0,
+                            y: position.1,
+                        },
+                        Color::from_rgba(1.0, 1.0, 1.0, vis),
+                        &text,
+                    );
+                }
+            }
+        } else {
+            let text = font.layout_text(
+                "this curve can't be edited here (right-click to make it editable)",
+                per_item_height * 0.6,
+                TextOptions::new().with_wrap_to_width(position.2, TextAlignment::Center),
+            );
+            graphics.draw_text(
+                Vector2 {
+                    x: position.0,
+                    y: position.1,
+                },
+                Color::from_rgba(1.0, 1.0, 1.0, vis),
+                &text,
+            );
+        }
+        // draw the context menu
+        if let Some(menu) = &self.context_menu {
+            let item_height = Self::menu_item_height(supr, draw_opts.my_size_in_pixels) * position.3;
+            let x = position.0 + position.2 * menu.pos.0;
+            let mut y = position.1 + position.3 * menu.pos.1;
+            graphics.draw_rectangle(
+                speedy2d::shape::Rectangle::new(
+                    Vector2 { x, y },
+                    Vector2 {
+                        x: x + position.2 * Self::menu_width(),
+                        y: y + item_height * menu.items.len() as f32,
+                    },
+                ),
+                Color::from_rgba(0.2, 0.2, 0.3, 0.75 * vis),
+            );
+            let current_segment = self
+                .points
+                .as_ref()
+                .and_then(|points| points.get(menu.segment))
+                .map(|p| p.segment.name());
+            for item in menu.items.iter() {
+                let selected = match item {
+                    EtCurveMenuItem::SetSegment(s) => Some(s.name()) == current_segment,
+                    _ => false,
+                };
+                let text = font.layout_text(item.text(), item_height * 0.75, TextOptions::new());
+                graphics.draw_text(
+                    Vector2 { x: x + 2.0, y },
+                    if selected {
+                        Color::from_rgba(0.6, 1.0, 0.6, vis)
+                    } else {
+                        Color::from_rgba(1.0, 1.0, 1.0, vis)
+                    },
+                    &text,
+                );
+                y += item_height;
+            }
+        }
     }
     fn handle_input(
         &mut self,
@@ -797,10 +1301,212 @@ impl ExtraTabsInfo for EtCurve {
         draw_opts: &mut crate::gui::speedy2d::layout::EditorWindowLayoutContentDrawOptions,
         input: &mut crate::gui::speedy2d::layout::UserInput,
     ) {
+        let mouse_pos = input.clonable.mouse_pos;
+        let size = {
+            let inner = supr.get_inner_position(&(
+                0.0,
+                0.0,
+                draw_opts.my_size_in_pixels.0,
+                draw_opts.my_size_in_pixels.1,
+            ));
+            (inner.2, inner.3)
+        };
+        let mouse_in_graph =
+            0.0 < mouse_pos.0 && mouse_pos.0 < 1.0 && 0.0 < mouse_pos.1 && mouse_pos.1 < 1.0;
+        let mouse_time = (mouse_pos.0 as f64).max(0.0).min(1.0);
+        let mouse_value = self.y_to_value(mouse_pos.1);
+        match &input.owned.action {
+            crate::gui::speedy2d::layout::InputAction::Mouse(action) => match action {
+                MouseAction::Moved => match self.dragging {
+                    Some(EtCurveDrag::Point(i)) => {
+                        if let Some(points) = &mut self.points {
+                            let last = points.len() - 1;
+                            // the first and last point always stay at 0 and 1
+                            if i != 0 && i != last {
+                                let min = points[i - 1].time;
+                                let max = points[i + 1].time;
+                                points[i].time = mouse_time.max(min).min(max);
+                            }
+                            points[i].value = mouse_value;
+                        }
+                    }
+                    Some(EtCurveDrag::Handle(i, second)) => {
+                        if let Some(points) = &mut self.points {
+                            let (t0, t1) = (points[i].time, points[i + 1].time);
+                            let x = if t1 > t0 {
+                                ((mouse_time - t0) / (t1 - t0)).max(0.0).min(1.0)
+                            } else {
+                                0.5
+                            };
+                            let base = if second {
+                                points[i + 1].value
+                            } else {
+                                points[i].value
+                            };
+                            if let ChainSegment::Bezier(h1, h2) = &mut points[i].segment {
+                                *if second { h2 } else { h1 } = (x, mouse_value - base);
+                            }
+                        }
+                    }
+                    Some(EtCurveDrag::Pan(value)) => {
+                        let diff = value - mouse_value;
+                        self.view = (self.view.0 + diff, self.view.1 + diff);
+                    }
+                    None => (),
+                },
+                MouseAction::ButtonDown(speedy2d::window::MouseButton::Left) => {
+                    if let Some(menu) = self.context_menu.take() {
+                        let item_height = Self::menu_item_height(supr, draw_opts.my_size_in_pixels);
+                        let index = ((mouse_pos.1 - menu.pos.1) / item_height).floor();
+                        if mouse_pos.0 >= menu.pos.0
+                            && mouse_pos.0 <= menu.pos.0 + Self::menu_width()
+                            && index >= 0.0
+                        {
+                            match menu.items.get(index as usize) {
+                                Some(EtCurveMenuItem::SetSegment(segment)) => {
+                                    if let Some(p) = self
+                                        .points
+                                        .as_mut()
+                                        .and_then(|points| points.get_mut(menu.segment))
+                                    {
+                                        if p.segment.name() != segment.name() {
+                                            p.segment = *segment;
+                                            self.apply(supr);
+                                        }
+                                    }
+                                }
+                                Some(EtCurveMenuItem::RemovePoint) => {
+                                    if let (Some(points), Some(point)) = (&mut self.points, menu.point) {
+                                        points.remove(point);
+                                        self.apply(supr);
+                                    }
+                                }
+                                Some(EtCurveMenuItem::MakeEditable) => {
+                                    self.points = Some(
+                                        self.curve
+                                            .bake(Self::MAKE_EDITABLE_SAMPLES)
+                                            .into_iter()
+                                            .map(|k| ChainPoint {
+                                                time: k.time,
+                                                value: k.value,
+                                                segment: ChainSegment::Linear,
+                                            })
+                                            .collect(),
+                                    );
+                                    self.apply(supr);
+                                }
+                                Some(EtCurveMenuItem::ResetView) => self.reset_view(),
+                                None => (),
+                            }
+                        }
+                    } else if mouse_in_graph {
+                        self.dragging = self.grab(mouse_pos, size);
+                        if self.dragging.is_none() {
+                            // add a point and start dragging it
+                            if let Some(points) = &mut self.points {
+                                let index = points.partition_point(|p| p.time <= mouse_time);
+                                if 0 < index && index < points.len() {
+                                    let segment = match points[index - 1].segment {
+                                        ChainSegment::Bezier(..) => ChainSegment::DEFAULT_BEZIER,
+                                        segment => segment,
+                                    };
+                                    points.insert(
+                                        index,
+                                        ChainPoint {
+                                            time: mouse_time,
+                                            value: mouse_value,
+                                            segment,
+                                        },
+                                    );
+                                    self.dragging = Some(EtCurveDrag::Point(index));
+                                }
+                            }
+                        }
+                    }
+                }
+                MouseAction::ButtonDown(speedy2d::window::MouseButton::Middle) => {
+                    if mouse_in_graph {
+                        self.dragging = Some(EtCurveDrag::Pan(mouse_value));
+                    }
+                }
+                MouseAction::ButtonDown(speedy2d::window::MouseButton::Right) => {
+                    self.context_menu = if mouse_in_graph {
+                        let mut items = Vec::new();
+                        let (segment, point) = if let Some(points) = &self.points {
+                            let point = match self.grab(mouse_pos, size) {
+                                Some(EtCurveDrag::Point(i)) if i != 0 && i != points.len() - 1 => {
+                                    items.push(EtCurveMenuItem::RemovePoint);
+                                    Some(i)
+                                }
+                                _ => None,
+                            };
+                            items.extend(
+                                [
+                                    ChainSegment::Hold,
+                                    ChainSegment::Linear,
+                                    ChainSegment::Smooth,
+                                    ChainSegment::DEFAULT_BEZIER,
+                                ]
+                                .into_iter()
+                                .map(EtCurveMenuItem::SetSegment),
+                            );
+                            (
+                                points
+                                    .partition_point(|p| p.time <= mouse_time)
+                                    .max(1)
+                                    .min(points.len() - 1)
+                                    - 1,
+                                point,
+                            )
+                        } else {
+                            items.push(EtCurveMenuItem::MakeEditable);
+                            (0, None)
+                        };
+                        items.push(EtCurveMenuItem::ResetView);
+                        Some(EtCurveContextMenu {
+                            pos: (mouse_pos.0.min(1.0 - Self::menu_width()), mouse_pos.1),
+                            segment,
+                            point,
+                            items,
+                        })
+                    } else {
+                        None
+                    };
+                }
+                MouseAction::ButtonUp(
+                    speedy2d::window::MouseButton::Left | speedy2d::window::MouseButton::Middle,
+                ) => match self.dragging.take() {
+                    Some(EtCurveDrag::Point(_) | EtCurveDrag::Handle(..)) => self.apply(supr),
+                    Some(EtCurveDrag::Pan(_)) | None => (),
+                },
+                MouseAction::Scroll(dist) => {
+                    if mouse_in_graph {
+                        // zoom the value axis, keeping the value under the mouse where it is
+                        let lines = match dist {
+                            speedy2d::window::MouseScrollDistance::Lines { y, .. } => *y,
+                            speedy2d::window::MouseScrollDistance::Pixels { y, .. } => *y / 20.0,
+                            speedy2d::window::MouseScrollDistance::Pages { y, .. } => *y * 10.0,
+                        };
+                        let factor = 1.1f64.powf(-lines);
+                        self.view = (
+                            mouse_value + (self.view.0 - mouse_value) * factor,
+                            mouse_value + (self.view.1 - mouse_value) * factor,
+                        );
+                    }
+                }
+                _ => (),
+            },
+            _ => (),
+        }
     }
     fn update(&mut self, supr: &mut VideoPropertiesEditor, ud: ETUpdate) {
         match ud {
-            ETUpdate::VideoWasUpdated(vid) => (), /* TODO */
+            ETUpdate::VideoWasUpdated(vid) => {
+                if self.dragging.is_none() {
+                    self.curve = (self.read_curve)(vid).clone();
+                    self.points = self.curve.to_chain_points();
+                }
+            }
         }
     }
 }
@@ -879,6 +1585,7 @@ impl ExtraTabsInfo for EtListAdd {
             ("Effect", "Applies effects to an object."),
             ("Image", "Displays a static image"),
             ("Video", "Displays a video."),
+            ("Shape", "Draws a rectangle, ellipse, polygon or path."),
         ];
         let mouse_index = if 0.0 < input.clonable.mouse_pos.0
             && input.clonable.mouse_pos.0 < 1.0
@@ -947,7 +1654,7 @@ impl ExtraTabsInfo for EtListAdd {
                 MouseAction::Moved => (),
                 MouseAction::ButtonDown(_) => (),
                 MouseAction::ButtonUp(btn) => {
-                    let possibilities = 4;
+                    let possibilities = 5;
                     let mouse_index = if 0.0 < mouse_pos.0
                         && mouse_pos.0 < 1.0
                         && 0.0 < mouse_pos.1
@@ -1005,6 +1712,19 @@ impl ExtraTabsInfo for EtListAdd {
                                     gcd,
                                 )),
                             )),
+                            4 => Some(VideoTypeChanges_List::Insert(
+                                0,
+                                Video::new_full(VideoType::new(
+                                    VideoTypeEnum::Shape(crate::content::shape::Shape::new(
+                                        crate::content::shape::ShapeKind::Rectangle {
+                                            corner_radius: crate::curve::CurveData::Constant(0.0)
+                                                .into(),
+                                        },
+                                        gcd.reset(),
+                                    )),
+                                    gcd,
+                                )),
+                            )),
                             _ => None,
                         };
                         if let Some(inner_changes) = inner_changes {
@@ -1012,6 +1732,9 @@ impl ExtraTabsInfo for EtListAdd {
                                 pos: None,
                                 start: None,
                                 length: None,
+                                opacity: None,
+                                compositing: None,
+                                label: None,
                                 video: Some(VideoTypeChanges::List(vec![inner_changes])),
                                 wrap: None,
                                 replace: None,
@@ -1321,6 +2044,8 @@ impl VideoPropertiesEditor {
             tabs: Vec::new(),
             tabs_info: Vec::new(),
 
+            preview_progress: (0.0, None),
+
             layout_content_data: EditorWindowLayoutContentData::default(),
         }
     }
@@ -1337,9 +2062,14 @@ impl EditorWindowLayoutContentTrait for VideoPropertiesEditor {
         position: &(f32, f32, f32, f32),
         input: &mut crate::gui::speedy2d::layout::UserInput,
     ) {
+        let mut preview_progress_changed = false;
         for action in input.get_custom_actions().unwrap() {
             match action {
                 CustomDrawActions::SetVideoPreviewActive(_) => (),
+                CustomDrawActions::PreviewProgressChanged(progress) => {
+                    self.preview_progress.0 = *progress;
+                    preview_progress_changed = true;
+                }
                 val @ CustomDrawActions::SetEditingTo(_)
                 | val @ CustomDrawActions::ChangedVideo => {
                     let new_index = if let CustomDrawActions::SetEditingTo(new_index) = val {
@@ -1395,6 +2125,16 @@ impl EditorWindowLayoutContentTrait for VideoPropertiesEditor {
                                         Some(Box::new(EtGeneral::new())),
                                         Some(Box::new(EtPlaceholder::new())),
                                     ],
+                                    VideoTypeEnum::Shape(_)
+                                    | VideoTypeEnum::Solid(_)
+                                    | VideoTypeEnum::Gradient(_)
+                                    | VideoTypeEnum::Svg(_)
+                                    | VideoTypeEnum::AnimatedImage(_)
+                                    | VideoTypeEnum::Project(_)
+                                    | VideoTypeEnum::Instance(_) => vec![
+                                        Some(Box::new(EtGeneral::new())),
+                                        Some(Box::new(EtPlaceholder::new())),
+                                    ],
                                 };
                             } else {
                                 let mut tabs = std::mem::replace(&mut self.tabs, Vec::new());
@@ -1412,9 +2152,21 @@ impl EditorWindowLayoutContentTrait for VideoPropertiesEditor {
                         },
                         Some(Instant::now()),
                     );
+                    preview_progress_changed = true;
                 }
             };
         }
+        if preview_progress_changed {
+            self.preview_progress.1 = if let Some((index, _)) = &self.editing.0 {
+                useful::get_progress_from_index_recursive(
+                    &self.video.lock().unwrap(),
+                    &mut index.clone(),
+                    self.preview_progress.0,
+                )
+            } else {
+                None
+            };
+        }
 
         match &draw_opts.draw_mode.clone() /* TODO: Can you not clone here? */ {
             EditorWindowLayoutContentDrawMode::Static(mode) => match mode {
@@ -1831,6 +2583,13 @@ impl VideoPropertiesEditor {
                             VideoTypeEnum::Image(_) => "Image",
                             VideoTypeEnum::Raw(_) => "Video",
                             VideoTypeEnum::Ffmpeg(_) => "ffmpeg",
+                            VideoTypeEnum::Shape(_) => "Shape",
+                            VideoTypeEnum::Solid(_) => "Solid",
+                            VideoTypeEnum::Gradient(_) => "Gradient",
+                            VideoTypeEnum::Svg(_) => "Svg",
+                            VideoTypeEnum::AnimatedImage(_) => "AnimatedImage",
+                            VideoTypeEnum::Project(_) => "Project",
+                            VideoTypeEnum::Instance(_) => "Instance",
                         });
                         s
                     }
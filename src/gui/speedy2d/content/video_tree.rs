@@ -43,6 +43,7 @@ impl EditorWindowLayoutContentTrait for VideoTree {
                 CustomDrawActions::ChangedVideo => {
                     self.local_video_copy = Some(self.video.lock().unwrap().clone_no_caching());
                 },
+                CustomDrawActions::PreviewProgressChanged(_) => (),
             };
         };
 
@@ -204,7 +205,7 @@ impl VideoTree {
     }
     fn draw_type_normal_one(&mut self, vis: f32, vid: &mut Video, draw_opts: &mut crate::gui::speedy2d::layout::EditorWindowLayoutContentDrawOptions, graphics: &mut speedy2d::Graphics2D, position: &(f32, f32, f32, f32), npos: &mut (f32, f32), index: &mut u32, input: &mut crate::gui::speedy2d::layout::UserInput) -> DrawTreeBranchRecursiveOptions {
         fn vid_to_str(vid: &Video) -> String {
-            match &vid.video.vt {
+            let name = match &vid.video.vt {
                 crate::video::VideoTypeEnum::List(_) => format!("List"),
                 crate::video::VideoTypeEnum::AspectRatio(_, _w, _h) => format!("AspectRatio"),
                 crate::video::VideoTypeEnum::WithEffect(_, e) => format!("Effect: {}", match &e.effect {
@@ -212,7 +213,7 @@ impl VideoTree {
                     effect::effects::EffectsEnum::BlackWhite(_) => format!("BlackWhite"),
                     effect::effects::EffectsEnum::Rotate(_) => format!("Rotate: [?]"),
                     effect::effects::EffectsEnum::Shake(e) => format!("Shake ({}x{}, {}x{})", e.shake_dist_x, e.shakes_count_x, e.shake_dist_y, e.shakes_count_y),
-                    effect::effects::EffectsEnum::ChangeTime(_) => format!("ChangeSpeed"),
+                    effect::effects::EffectsEnum::ChangeTime(e) => format!("ChangeSpeed ({})", e.mode.name()),
                     effect::effects::EffectsEnum::ColorAdjust(e) => format!("ColorAdjust: {}", match &e.mode { effect::effects::ColorAdjust_Mode::Rgba(..) => "rgba", }),
                     effect::effects::EffectsEnum::Blur(e) => format!("Blur: {}", match &e.mode { effect::effects::Blur_Mode::Square {..} => "Square", effect::effects::Blur_Mode::Downscale {..} => "Downscale", }),
                     effect::effects::EffectsEnum::ColorKey(_) => format!("ColorKey"),
@@ -224,6 +225,26 @@ impl VideoTree {
                 crate::video::VideoTypeEnum::Image(i) => format!("Image: {}", match i.path().file_name() { Some(n) => n.to_string_lossy().to_string(), None => i.path().to_string_lossy().to_string(), }),
                 crate::video::VideoTypeEnum::Raw(i) => format!("Video: {}", i.get_dir().to_string_lossy().to_string()),
                 crate::video::VideoTypeEnum::Ffmpeg(i) => format!("ffmpeg: {}", i.path().to_string_lossy().to_string()),
+                crate::video::VideoTypeEnum::Shape(s) => format!("Shape: {}", match s.kind() {
+                    crate::content::shape::ShapeKind::Rectangle { .. } => "Rectangle",
+                    crate::content::shape::ShapeKind::Ellipse => "Ellipse",
+                    crate::content::shape::ShapeKind::Polygon { .. } => "Polygon",
+                    crate::content::shape::ShapeKind::Path(_) => "Path",
+                }),
+                crate::video::VideoTypeEnum::Solid(_) => format!("Solid"),
+                crate::video::VideoTypeEnum::Gradient(g) => format!("Gradient: {}", match g.kind() {
+                    crate::content::gradient::GradientKind::Linear { .. } => "linear",
+                    crate::content::gradient::GradientKind::Radial { .. } => "radial",
+                    crate::content::gradient::GradientKind::Conic { .. } => "conic",
+                }),
+                crate::video::VideoTypeEnum::AnimatedImage(i) => format!("Animated: {}", match i.path().file_name() { Some(n) => n.to_string_lossy().to_string(), None => i.path().to_string_lossy().to_string(), }),
+                crate::video::VideoTypeEnum::Project(p) => format!("Project: {}", match p.path().file_name() { Some(n) => n.to_string_lossy().to_string(), None => p.path().to_string_lossy().to_string(), }),
+                crate::video::VideoTypeEnum::Instance(i) => format!("Instance: {}", i.label()),
+                crate::video::VideoTypeEnum::Svg(i) => format!("Svg: {}", match i.path().file_name() { Some(n) => n.to_string_lossy().to_string(), None => i.path().to_string_lossy().to_string(), }),
+            };
+            match &vid.label {
+                Some(label) => format!("{name} (shared as '{label}')"),
+                None => name,
             }
         }
 
@@ -86,6 +86,8 @@ pub enum CustomDrawActions {
     SetEditingTo(Option<u32>),
     /// Made changes to the video
     ChangedVideo,
+    /// The user moved the video preview to a different point in time (0.0 to 1.0).
+    PreviewProgressChanged(f64),
 }
 impl<'a> UserInput<'a> {
     pub fn new_no_actions(owned: UserInputOwned<'a>, clonable: UserInputClonable, custom_actions_next: &'a mut Vec<CustomDrawActions>) -> UserInput<'a> {
@@ -165,6 +165,11 @@ impl RequestActions {
                 ));
         };
         if self.edited_part_requires_update {
+            // labels may have been added or removed
+            container
+                .project
+                .shared_videos
+                .sync(&container.project.vid().lock().unwrap());
             container
                 .custom_actions
                 .push(super::layout::CustomDrawActions::ChangedVideo);
@@ -1,15 +1,19 @@
 use crate::cli::Clz;
 
+mod alpha_compositing;
 mod assets;
+mod blend_mode;
 mod cli;
 mod content;
 mod curve;
 mod effect;
 mod external_program;
 mod files;
+mod font_manager;
 mod gui;
 mod multithreading;
 mod project;
+mod transform;
 mod types;
 mod useful;
 mod video;
@@ -22,6 +26,9 @@ mod video_render_settings;
 
 fn main() {
     let mut args = cli::CustomArgs::read_from_env();
+    for dir in args.font_dirs.iter() {
+        font_manager::get().add_directory(dir.clone());
+    }
     if let Err(_e) = std::fs::create_dir_all("/tmp/dummi26/rusty_editor_for_da_moving_pictures/") {
         println!("Failed to setup /tmp/... dir. This might cause some functionality that relies on /tmp/... to break, but is not necessarily fatal.");
     }
@@ -34,6 +41,8 @@ fn main() {
                     cli::Action::OpenProjectInGui => gui::main(args),
                     cli::Action::OpenProjectInCli => gui::main_cli(args),
                     cli::Action::ExportProjectToFrames => export_to_frames(args),
+                    cli::Action::ImportSubtitles => import_subtitles(args),
+                    cli::Action::ExportSubtitles => export_subtitles(args),
                     cli::Action::Exit => break,
                 }
             }
@@ -47,6 +56,80 @@ fn main() {
     }
 }
 
+/// Prints a List video with one Text per subtitle, which can be pasted into a project file.
+fn import_subtitles(args: cli::CustomArgs) -> cli::CustomArgs {
+    let (path, duration) = match &args.subtitles {
+        Some(v) => v.clone(),
+        None => panic!("\n{}\n",
+            Clz::error_info("No subtitle file was specified. Please use --subtitles [path] [project duration in seconds (optional)]."),
+        ),
+    };
+    let template = match &args.subtitle_template {
+        Some(template_path) => match files::subtitles::read_template(template_path) {
+            Ok(v) => v,
+            Err(err) => panic!("\n{}{}{}{}\n",
+                Clz::error_info("Could not read the subtitle template '"), Clz::error_cause(template_path.to_string_lossy().as_ref()), Clz::error_info("': "), Clz::error_details(err.to_string().as_str()),
+            ),
+        },
+        None => files::subtitles::DEFAULT_TEMPLATE.to_string(),
+    };
+    let cues = match files::subtitles::import(&path) {
+        Ok(v) => v,
+        Err(err) => panic!("\n{}{}{}{}\n",
+            Clz::error_info("Could not import subtitles from '"), Clz::error_cause(path.to_string_lossy().as_ref()), Clz::error_info("': "), Clz::error_details(err.to_string().as_str()),
+        ),
+    };
+    eprintln!("{}", Clz::completed(format!("Imported {} subtitles.", cues.len()).as_str()));
+    println!("{}", files::subtitles::to_project_syntax(&cues, duration, &template));
+    args
+}
+
+/// Writes all static Text videos in the project to an .srt or .vtt file.
+fn export_subtitles(args: cli::CustomArgs) -> cli::CustomArgs {
+    let (path, duration) = match &args.subtitles {
+        Some((path, Some(duration))) => (path.clone(), *duration),
+        _ => panic!("\n{}\n",
+            Clz::error_info("Please use --subtitles [path] [project duration in seconds] to specify where to save the subtitles and how long the project is."),
+        ),
+    };
+    let proj_path = match &args.project_path {
+        Some(v) => v.clone(),
+        None => panic!("\n{}\n",
+            Clz::error_info("Could not export subtitles because no project was specified. Please use --proj-path to point to a project file."),
+        ),
+    };
+    let proj = match files::file_handler::read_from_file(&proj_path) {
+        Err(err) => panic!("\n{}\n{}\n",
+            Clz::error_info("Encountered an IO error trying to open your project:"),
+            Clz::error_details(err.to_string().as_str()),
+        ),
+        Ok(Err(err)) => panic!("\n{}\n{}\n",
+            Clz::error_info("Could not open your project, as the parser returned the following error:"),
+            Clz::error_details(err.to_string().as_str()),
+        ),
+        Ok(Ok(v)) => v,
+    };
+    let vid = proj.vid();
+    let vid = vid.lock().unwrap();
+    let root_timing = (vid.set_start_frame, vid.set_length);
+    let cues = match &args.subtitle_root {
+        Some(label) => match files::subtitles::find_labeled(&vid, label, root_timing) {
+            Some((root, timing)) => files::subtitles::cues_from_video(root, timing, duration),
+            None => panic!("\n{}{}{}\n",
+                Clz::error_info("There is no video with the label '"), Clz::error_cause(label.as_str()), Clz::error_info("' (--subtitle-root)."),
+            ),
+        },
+        None => files::subtitles::cues_from_video(&vid, root_timing, duration),
+    };
+    if let Err(err) = files::subtitles::export(&path, &cues) {
+        panic!("\n{}{}{}{}\n",
+            Clz::error_info("Could not export subtitles to '"), Clz::error_cause(path.to_string_lossy().as_ref()), Clz::error_info("': "), Clz::error_details(err.to_string().as_str()),
+        );
+    }
+    eprintln!("{}", Clz::completed(format!("Exported {} subtitles.", cues.len()).as_str()));
+    args
+}
+
 fn export_to_frames(args: cli::CustomArgs) -> cli::CustomArgs {
     eprintln!(
         "{}\n{}",
@@ -6,7 +6,10 @@ use crate::{
     video::{Video, VideoType, VideoTypeEnum},
     video_render_settings::{FrameRenderInfo, VideoRenderSettings},
 };
-use std::sync::{Arc, Mutex};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
 
 pub type SharedCurvesId = u64;
 
@@ -15,6 +18,8 @@ pub struct Project {
     pub proj: Arc<Mutex<ProjectData>>,
     vid: Option<Arc<Mutex<Video>>>,
     pub shared_curves: SharedCurves,
+    pub shared_videos: SharedVideos,
+    pub diagnostics: Diagnostics,
 }
 pub struct ProjectData {
     pub name: String,
@@ -38,6 +43,8 @@ impl Project {
             proj: Arc::new(Mutex::new(proj)),
             vid: None,
             shared_curves: SharedCurves::new(),
+            shared_videos: SharedVideos::new(),
+            diagnostics: Diagnostics::new(),
         };
         s.vid = Some(Arc::new(Mutex::new(Video::new_full(VideoType::new(
             VideoTypeEnum::List(vec![]),
@@ -72,6 +79,16 @@ impl SharedCurves {
             None => None,
         }
     }
+    /// Replaces the data of an existing shared curve. Returns false if there is no curve with this id. Curves that use it only change once they are updated (see Curve::update).
+    pub fn set(&self, id: SharedCurvesId, curve: CurveData) -> bool {
+        match self.curves.lock().unwrap().get_mut(&id) {
+            Some(v) => {
+                *v = curve;
+                true
+            }
+            None => false,
+        }
+    }
     pub fn insert(&self, curve: CurveData) -> SharedCurvesId {
         let mut curves = self.curves.lock().unwrap();
         let id = if let Some(id) = self.free_ids.lock().unwrap().pop() {
@@ -83,3 +100,198 @@ impl SharedCurves {
         id
     }
 }
+
+/// Videos that can be drawn in multiple places (see VideoTypeEnum::Instance). The source of a shared video is a normal video in the project's tree which has a label (see Video::label), so it can be edited like any other video. While the project is drawn, the content of every labeled video is lent to this (see lend), so the source and all instances draw the same content, with the same caches.
+#[derive(Clone)]
+pub struct SharedVideos {
+    /// Only contains videos while they are lent.
+    videos: Arc<Mutex<HashMap<String, Arc<Mutex<Video>>>>>,
+    /// The labels used in the project's tree, updated by sync.
+    labels: Arc<Mutex<Vec<String>>>,
+    lent: Arc<AtomicBool>,
+}
+impl SharedVideos {
+    pub fn new() -> Self {
+        Self {
+            videos: Arc::new(Mutex::new(HashMap::new())),
+            labels: Arc::new(Mutex::new(Vec::new())),
+            lent: Arc::new(AtomicBool::new(false)),
+        }
+    }
+    pub fn get(&self, label: &str) -> Option<Arc<Mutex<Video>>> {
+        self.videos.lock().unwrap().get(label).cloned()
+    }
+    /// Updates the list of labels (see labels) after root was changed.
+    pub fn sync(&self, root: &Video) {
+        let mut labels = Vec::new();
+        Self::collect_labels(root, &mut labels);
+        labels.sort();
+        labels.dedup();
+        *self.labels.lock().unwrap() = labels;
+    }
+    fn collect_labels(vid: &Video, labels: &mut Vec<String>) {
+        if let Some(label) = &vid.label {
+            labels.push(label.clone());
+        }
+        for child in vid.children() {
+            Self::collect_labels(child, labels);
+        }
+    }
+    /// The labels of all shared videos, sorted.
+    pub fn labels(&self) -> Vec<String> {
+        self.labels.lock().unwrap().clone()
+    }
+
+    /// Moves the content of every labeled video in root (including labeled videos inside of other labeled videos) here, and replaces it with an Instance of its own label, so that the source draws the shared content like all other instances. If a label is used more than once, the first video that has it is used. Returns false (and does nothing) if the videos were already lent by an outer draw of this project. Every successful call must be followed by give_back.
+    pub fn lend(&self, root: &mut Video) -> bool {
+        if self.lent.swap(true, Ordering::SeqCst) {
+            return false;
+        }
+        Self::lend_from(root, &mut self.videos.lock().unwrap());
+        true
+    }
+    fn lend_from(vid: &mut Video, videos: &mut HashMap<String, Arc<Mutex<Video>>>) {
+        if let Some(label) = vid.label.clone() {
+            if !videos.contains_key(&label) {
+                let generic_content_data = vid.generic_content_data().reset();
+                let placeholder = VideoType::new(
+                    VideoTypeEnum::Instance(crate::content::instance::Instance::new(
+                        label.clone(),
+                        generic_content_data.reset(),
+                    )),
+                    generic_content_data,
+                );
+                // the instance's position and timing are used, so the content fills its box
+                let shared = Arc::new(Mutex::new(Video::new_full(std::mem::replace(
+                    &mut vid.video,
+                    placeholder,
+                ))));
+                videos.insert(label, Arc::clone(&shared));
+                let mut shared = shared.lock().unwrap();
+                for child in shared.children_mut() {
+                    Self::lend_from(child, videos);
+                }
+                return;
+            }
+        }
+        for child in vid.children_mut() {
+            Self::lend_from(child, videos);
+        }
+    }
+    /// Puts the content that was moved here by lend back into root.
+    pub fn give_back(&self, root: &mut Video) {
+        let mut videos = self.videos.lock().unwrap();
+        Self::give_back_to(root, &mut videos);
+        videos.clear();
+        self.lent.store(false, Ordering::SeqCst);
+    }
+    fn give_back_to(vid: &mut Video, videos: &mut HashMap<String, Arc<Mutex<Video>>>) {
+        if let (Some(label), VideoTypeEnum::Instance(instance)) = (&vid.label, &vid.video.vt) {
+            if instance.label() == label {
+                if let Some(shared) = videos.remove(label) {
+                    std::mem::swap(&mut vid.video, &mut shared.lock().unwrap().video);
+                }
+            }
+        }
+        for child in vid.children_mut() {
+            Self::give_back_to(child, videos);
+        }
+    }
+}
+
+/// How many diagnostics are kept. When there are more, the oldest ones are removed.
+const MAX_DIAGNOSTICS: usize = 100;
+
+/// Problems that happen while videos are loaded or drawn (an external command failed, ...). Unlike messages on stderr, these can be shown in the editor.
+#[derive(Clone)]
+pub struct Diagnostics {
+    entries: Arc<Mutex<Vec<Diagnostic>>>,
+}
+#[derive(Clone)]
+pub struct Diagnostic {
+    /// What caused the problem, for example the image's path.
+    pub source: String,
+    pub message: String,
+    /// How often this was reported in a row.
+    pub count: usize,
+}
+impl Diagnostics {
+    pub fn new() -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+    /// If the last diagnostic is the same as this one, only its count is increased.
+    pub fn report(&self, source: String, message: String) {
+        let mut entries = self.entries.lock().unwrap();
+        if let Some(last) = entries.last_mut() {
+            if last.source == source && last.message == message {
+                last.count += 1;
+                return;
+            }
+        }
+        entries.push(Diagnostic {
+            source,
+            message,
+            count: 1,
+        });
+        if entries.len() > MAX_DIAGNOSTICS {
+            let too_many = entries.len() - MAX_DIAGNOSTICS;
+            entries.drain(..too_many);
+        }
+    }
+    /// All diagnostics, oldest first.
+    pub fn entries(&self) -> Vec<Diagnostic> {
+        self.entries.lock().unwrap().clone()
+    }
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.source, self.message)?;
+        if self.count > 1 {
+            write!(f, " (x{})", self.count)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instances_draw_the_labeled_source() {
+        let project = Project::new(Default::default());
+        // red on the left, an instance of it on the right
+        let syntax = "compositing:*video:List:+pos:10;0;0.5;1;label:src\\!video:Solid:r1;0;0;1;:+pos:10.5;0;0.5;1;video:Instance:src\\!:;:";
+        let mut vid = match crate::files::parser_v0::parse_vid(
+            &mut syntax.chars(),
+            GenericContentData::new(project.clone()),
+        ) {
+            Ok(v) => v,
+            Err(e) => panic!("{e}"),
+        };
+        project.shared_videos.sync(&vid);
+        assert_eq!(project.shared_videos.labels(), vec!["src".to_string()]);
+        let mut img = image::DynamicImage::new_rgba8(8, 4);
+        if let Some(prep_draw) = vid.prep_draw(0.5, None) {
+            vid.draw(
+                &mut img,
+                prep_draw,
+                &mut VideoRenderSettings::preview(FrameRenderInfo::new(2.0)),
+            );
+        }
+        let img = img.into_rgba8();
+        assert_eq!(img.get_pixel(1, 2).0, [255, 0, 0, 255]);
+        assert_eq!(img.get_pixel(6, 2).0, [255, 0, 0, 255]);
+        // the source's content is back in the tree, and nothing is left in the map
+        assert!(matches!(
+            vid.children()[0].video.vt,
+            VideoTypeEnum::Solid(_)
+        ));
+        assert!(project.shared_videos.get("src").is_none());
+    }
+}
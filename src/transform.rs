@@ -0,0 +1,299 @@
+use image::RgbaImage;
+
+use crate::curve::{Curve, CurveData};
+
+/// Rotation, scale and skew of a video, applied around its anchor point after the video has been positioned. Transforms are composed down the tree, so transforming a List also transforms its contents.
+#[derive(Clone)]
+pub struct Transform {
+    /// In degrees, clockwise.
+    pub rotation: Curve,
+    pub scale_x: Curve,
+    pub scale_y: Curve,
+    /// The point that stays where it is, relative to the video's box: (0, 0) is the top left corner, (1, 1) the bottom right one.
+    pub anchor_x: Curve,
+    pub anchor_y: Curve,
+    /// In degrees. skew_x moves the bottom of the video to the right, skew_y moves its right side down.
+    pub skew_x: Curve,
+    pub skew_y: Curve,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self {
+            rotation: CurveData::Constant(0.0).into(),
+            scale_x: CurveData::Constant(1.0).into(),
+            scale_y: CurveData::Constant(1.0).into(),
+            anchor_x: CurveData::Constant(0.5).into(),
+            anchor_y: CurveData::Constant(0.5).into(),
+            skew_x: CurveData::Constant(0.0).into(),
+            skew_y: CurveData::Constant(0.0).into(),
+        }
+    }
+}
+
+impl Transform {
+    /// The transform at this progress, for a video whose box (in pixels) is pos_px.
+    pub fn matrix(&self, progress: f64, pos_px: (f64, f64, f64, f64)) -> Affine {
+        let anchor = (
+            pos_px.0 + pos_px.2 * self.anchor_x.get_value(progress),
+            pos_px.1 + pos_px.3 * self.anchor_y.get_value(progress),
+        );
+        Affine::translate(anchor.0, anchor.1)
+            .mul(&Affine::rotate(
+                self.rotation.get_value(progress).to_radians(),
+            ))
+            .mul(&Affine::skew(
+                self.skew_x.get_value(progress).to_radians(),
+                self.skew_y.get_value(progress).to_radians(),
+            ))
+            .mul(&Affine::scale(
+                self.scale_x.get_value(progress),
+                self.scale_y.get_value(progress),
+            ))
+            .mul(&Affine::translate(-anchor.0, -anchor.1))
+    }
+}
+
+/// A 2D affine transformation: (x, y) becomes (a * x + c * y + e, b * x + d * y + f).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Affine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+    pub d: f64,
+    pub e: f64,
+    pub f: f64,
+}
+
+impl Affine {
+    pub const IDENTITY: Self = Self {
+        a: 1.0,
+        b: 0.0,
+        c: 0.0,
+        d: 1.0,
+        e: 0.0,
+        f: 0.0,
+    };
+    pub fn translate(x: f64, y: f64) -> Self {
+        Self {
+            e: x,
+            f: y,
+            ..Self::IDENTITY
+        }
+    }
+    /// Clockwise (because y points down), in radians.
+    pub fn rotate(angle: f64) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            a: cos,
+            b: sin,
+            c: -sin,
+            d: cos,
+            ..Self::IDENTITY
+        }
+    }
+    pub fn scale(x: f64, y: f64) -> Self {
+        Self {
+            a: x,
+            d: y,
+            ..Self::IDENTITY
+        }
+    }
+    /// In radians.
+    pub fn skew(x: f64, y: f64) -> Self {
+        Self {
+            b: y.tan(),
+            c: x.tan(),
+            ..Self::IDENTITY
+        }
+    }
+    /// The transformation that first applies other, then self.
+    pub fn mul(&self, other: &Self) -> Self {
+        Self {
+            a: self.a * other.a + self.c * other.b,
+            b: self.b * other.a + self.d * other.b,
+            c: self.a * other.c + self.c * other.d,
+            d: self.b * other.c + self.d * other.d,
+            e: self.a * other.e + self.c * other.f + self.e,
+            f: self.b * other.e + self.d * other.f + self.f,
+        }
+    }
+    pub fn apply(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.a * x + self.c * y + self.e,
+            self.b * x + self.d * y + self.f,
+        )
+    }
+    /// None if the transformation squashes everything onto a line or point (for example, a scale of 0).
+    pub fn inverse(&self) -> Option<Self> {
+        let det = self.a * self.d - self.b * self.c;
+        if det.abs() < 1e-12 || !det.is_finite() {
+            return None;
+        }
+        Some(Self {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+    pub fn is_identity(&self) -> bool {
+        self.whole_pixel_translation() == Some((0, 0))
+    }
+    /// If the transformation only moves things by a whole number of pixels, returns that offset.
+    pub fn whole_pixel_translation(&self) -> Option<(i32, i32)> {
+        let close = |v: f64, target: f64| (v - target).abs() < 1e-9;
+        if close(self.a, 1.0)
+            && close(self.b, 0.0)
+            && close(self.c, 0.0)
+            && close(self.d, 1.0)
+            && close(self.e, self.e.round())
+            && close(self.f, self.f.round())
+        {
+            Some((self.e.round() as i32, self.f.round() as i32))
+        } else {
+            None
+        }
+    }
+}
+
+/// How pixels are interpolated when an image is resampled.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ResampleFilter {
+    /// Mixes the 4 nearest pixels. Fast, but slightly blurry.
+    Bilinear,
+    /// Catmull-Rom spline through the 16 nearest pixels. Sharper, but slower.
+    Bicubic,
+}
+
+/// Transforms src, whose pixel (x, y) ends up at matrix.apply((x, y)). The result only covers the part of the transformed image that is inside of (0, 0, width, height), its top left corner is returned with it. None if nothing would be visible.
+/// The edges are anti-aliased: the alpha of each output pixel is multiplied with how much of that pixel is covered by the transformed image.
+pub fn resample(
+    src: &RgbaImage,
+    matrix: &Affine,
+    width: u32,
+    height: u32,
+    filter: ResampleFilter,
+) -> Option<(RgbaImage, i32, i32)> {
+    let inverse = matrix.inverse()?;
+    if src.width() == 0 || src.height() == 0 {
+        return None;
+    }
+    let (w, h) = (src.width() as f64, src.height() as f64);
+    // the bounding box of the transformed image
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for corner in [(0.0, 0.0), (w, 0.0), (0.0, h), (w, h)] {
+        let (x, y) = matrix.apply(corner);
+        min_x = min_x.min(x);
+        min_y = min_y.min(y);
+        max_x = max_x.max(x);
+        max_y = max_y.max(y);
+    }
+    let x0 = min_x.floor().max(0.0);
+    let y0 = min_y.floor().max(0.0);
+    let x1 = max_x.ceil().min(width as f64);
+    let y1 = max_y.ceil().min(height as f64);
+    if !(x0 < x1 && y0 < y1) {
+        return None;
+    }
+    let (x0, y0) = (x0 as i32, y0 as i32);
+    // how far (in src's pixels) src's x or y changes when moving one output pixel
+    let step_x = inverse.a.hypot(inverse.c);
+    let step_y = inverse.b.hypot(inverse.d);
+    let out = RgbaImage::from_fn((x1 as i32 - x0) as u32, (y1 as i32 - y0) as u32, |x, y| {
+        // the center of the output pixel, in src's pixels
+        let (sx, sy) = inverse.apply(((x0 + x as i32) as f64 + 0.5, (y0 + y as i32) as f64 + 0.5));
+        // the distance to the closest edge, in output pixels, is used to approximate the coverage
+        let coverage = |pos: f64, size: f64, step: f64| {
+            (pos.min(size - pos) / step.max(1e-12) + 0.5)
+                .max(0.0)
+                .min(1.0)
+        };
+        let coverage = coverage(sx, w, step_x) * coverage(sy, h, step_y);
+        if coverage <= 0.0 {
+            return image::Rgba([0, 0, 0, 0]);
+        }
+        let mut px = match filter {
+            ResampleFilter::Bilinear => sample_bilinear(src, sx, sy),
+            ResampleFilter::Bicubic => sample_bicubic(src, sx, sy),
+        };
+        px[3] = (px[3] as f64 * coverage).round() as u8;
+        image::Rgba(px)
+    });
+    Some((out, x0, y0))
+}
+
+/// Samples src at (x, y), where (0.5, 0.5) is the center of the top left pixel. Outside of src, the closest edge pixel is used (the edges are handled by resample).
+fn sample_bilinear(src: &RgbaImage, x: f64, y: f64) -> [u8; 4] {
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (fx, fy) = (x.floor(), y.floor());
+    let (tx, ty) = (x - fx, y - fy);
+    let (ix, iy) = (fx as i64, fy as i64);
+    sample_weighted(
+        src,
+        [(0, 0), (1, 0), (0, 1), (1, 1)].map(|(dx, dy)| {
+            (
+                ix + dx,
+                iy + dy,
+                if dx == 0 { 1.0 - tx } else { tx } * if dy == 0 { 1.0 - ty } else { ty },
+            )
+        }),
+    )
+}
+
+/// Like sample_bilinear, but with a Catmull-Rom spline through the 4x4 nearest pixels.
+fn sample_bicubic(src: &RgbaImage, x: f64, y: f64) -> [u8; 4] {
+    let (x, y) = (x - 0.5, y - 0.5);
+    let (fx, fy) = (x.floor(), y.floor());
+    let (wx, wy) = (catmull_rom(x - fx), catmull_rom(y - fy));
+    let (ix, iy) = (fx as i64, fy as i64);
+    let mut samples = [(0, 0, 0.0); 16];
+    for dy in 0..4 {
+        for dx in 0..4 {
+            samples[dy * 4 + dx] = (ix + dx as i64 - 1, iy + dy as i64 - 1, wx[dx] * wy[dy]);
+        }
+    }
+    sample_weighted(src, samples)
+}
+
+/// The weights of the 4 pixels around a point that is t (0.0 to 1.0) after the second one.
+fn catmull_rom(t: f64) -> [f64; 4] {
+    let (t2, t3) = (t * t, t * t * t);
+    [
+        0.5 * (-t3 + 2.0 * t2 - t),
+        0.5 * (3.0 * t3 - 5.0 * t2 + 2.0),
+        0.5 * (-3.0 * t3 + 4.0 * t2 + t),
+        0.5 * (t3 - t2),
+    ]
+}
+
+/// Mixes the pixels (x, y, weight) as premultiplied values, so that transparent pixels don't darken the result. Coordinates outside of src are clamped to its edges.
+fn sample_weighted<const N: usize>(src: &RgbaImage, samples: [(i64, i64, f64); N]) -> [u8; 4] {
+    let mut sum = [0.0f64; 4];
+    for (px, py, weight) in samples {
+        if weight == 0.0 {
+            continue;
+        }
+        let px = px.max(0).min(src.width() as i64 - 1);
+        let py = py.max(0).min(src.height() as i64 - 1);
+        let p = src.get_pixel(px as u32, py as u32).0;
+        let alpha = p[3] as f64 * weight;
+        for i in 0..3 {
+            sum[i] += p[i] as f64 * alpha;
+        }
+        sum[3] += alpha;
+    }
+    // bicubic weights can be negative, so the result can be out of range
+    if sum[3] <= 0.0 {
+        return [0, 0, 0, 0];
+    }
+    let channel = |v: f64| v.round().max(0.0).min(255.0) as u8;
+    [
+        channel(sum[0] / sum[3]),
+        channel(sum[1] / sum[3]),
+        channel(sum[2] / sum[3]),
+        channel(sum[3]),
+    ]
+}
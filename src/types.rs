@@ -1,6 +1,7 @@
 use crate::curve::Curve;
 use std::str::Chars;
 
+#[derive(Clone)]
 pub enum Color {
     RGBA(Curve, Curve, Curve, Curve),
 }
@@ -11,6 +12,12 @@ impl Color {
         }
     }
 
+    /// A color that doesn't change.
+    pub fn constant((r, g, b, a): (f64, f64, f64, f64)) -> Self {
+        use crate::curve::CurveData;
+        Self::RGBA(CurveData::Constant(r).into(), CurveData::Constant(g).into(), CurveData::Constant(b).into(), CurveData::Constant(a).into())
+    }
+
     pub fn parse(chars: &mut Chars) -> Result<Self, crate::files::parser_general::ParserError> {
         Ok(match chars.next() {
             Some('r') => Self::RGBA(crate::files::parser_v0::parse_vid_curve(chars)?, crate::files::parser_v0::parse_vid_curve(chars)?, crate::files::parser_v0::parse_vid_curve(chars)?, crate::files::parser_v0::parse_vid_curve(chars)?),
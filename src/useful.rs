@@ -56,4 +56,30 @@ pub fn get_elem_from_index_recursive_mut<'a>(vid: &'a mut crate::video::Video, i
         };
         None
     }
-}
\ No newline at end of file
+}
+/// Like get_elem_from_index_recursive, but returns the progress (usually 0 to 1) the element at the index would be drawn with when the root is drawn with outer_progress. Values outside of 0-1 mean that the element is not visible at that point. Effects that change the time (like ChangeTime) are ignored.
+pub fn get_progress_from_index_recursive(vid: &crate::video::Video, index: &mut u32, outer_progress: f64) -> Option<f64> {
+    let progress = (outer_progress - vid.set_start_frame) / vid.set_length;
+    if *index == 0 {
+        Some(progress)
+    } else {
+        for child in crate::content::content::Content::children(vid) {
+            *index -= 1;
+            if let Some(v) = get_progress_from_index_recursive(child, index, progress) { return Some(v); };
+        };
+        None
+    }
+}
+/// Like get_elem_from_index_recursive, but returns when the element at the index starts and how long it is, in the root's outer progress (root_timing is the root's (start, length)). Effects that change the time (like ChangeTime) are ignored.
+pub fn get_timing_from_index_recursive(vid: &crate::video::Video, index: &mut u32, root_timing: (f64, f64)) -> Option<(f64, f64)> {
+    if *index == 0 {
+        Some(root_timing)
+    } else {
+        for child in crate::content::content::Content::children(vid) {
+            *index -= 1;
+            let timing = (root_timing.0 + child.set_start_frame * root_timing.1, child.set_length * root_timing.1);
+            if let Some(v) = get_timing_from_index_recursive(child, index, timing) { return Some(v); };
+        };
+        None
+    }
+}
@@ -21,6 +21,14 @@ pub struct Video {
     pub video: VideoType,
     /// how to write the pixels onto the underlying surface. If None, inherits from parent.
     pub compositing: Option<CompositingMethod>,
+    /// 0.0 (invisible) to 1.0 (fully visible). This is multiplied with the parent's opacity and applied when the content is composited, so it works for every type of video.
+    pub opacity: Curve,
+    /// If true, colors are mixed in linear light instead of in sRGB when compositing, which looks more natural for transparency and blurry edges. If None, inherits from parent (default: false).
+    pub linear_light: Option<bool>,
+    /// Rotation, scale and skew, applied around the anchor point after positioning. This also transforms all of the video's children.
+    pub transform: crate::transform::Transform,
+    /// If set, Instance videos with this label draw this video's content (see project::SharedVideos).
+    pub label: Option<String>,
     // - -     -     - -
     // done: The values that are set after drawing
     /// Due to caching, the rendered image might not be exactly the desired one. If this is the case, this value will differ from the progress used by draw() etc.
@@ -32,6 +40,11 @@ pub struct VideoChanges {
     pub pos: Option<(Option<Curve>, Option<Curve>, Option<Curve>, Option<Curve>)>,
     pub start: Option<f64>,
     pub length: Option<f64>,
+    pub opacity: Option<Curve>,
+    /// Some(None) makes the video inherit its parent's compositing method.
+    pub compositing: Option<Option<CompositingMethod>>,
+    /// Some(None) removes the label.
+    pub label: Option<Option<String>>,
     pub video: Option<VideoTypeChanges>,
     pub wrap: Option<VideoChangesWrapWith>,
     pub replace: Option<VideoChangesReplaceWith>,
@@ -51,15 +64,21 @@ pub enum VideoChangesReplaceWith {
     Image,
     Raw,
     Ffmpeg,
+    Shape,
 }
 impl Content for Video {
     fn clone_no_caching(&self) -> Self {
-        Self::new(
+        let mut vid = Self::new(
             self.set_pos.clone(),
             self.set_start_frame.clone(),
             self.set_length.clone(),
             self.video.clone_no_caching(),
-        )
+        );
+        vid.opacity = self.opacity.clone();
+        vid.linear_light = self.linear_light;
+        vid.transform = self.transform.clone();
+        vid.label = self.label.clone();
+        vid
     }
 
     fn children(&self) -> Vec<&Self> {
@@ -70,7 +89,14 @@ impl Content for Video {
             VideoTypeEnum::Text(_)
             | VideoTypeEnum::Image(_)
             | VideoTypeEnum::Raw(_)
-            | VideoTypeEnum::Ffmpeg(_) => Vec::new(),
+            | VideoTypeEnum::Ffmpeg(_)
+            | VideoTypeEnum::Shape(_)
+            | VideoTypeEnum::Solid(_)
+            | VideoTypeEnum::Gradient(_)
+            | VideoTypeEnum::Svg(_)
+            | VideoTypeEnum::AnimatedImage(_)
+            | VideoTypeEnum::Project(_)
+            | VideoTypeEnum::Instance(_) => Vec::new(),
         }
     }
     fn children_mut(&mut self) -> Vec<&mut Self> {
@@ -81,7 +107,14 @@ impl Content for Video {
             VideoTypeEnum::Text(_)
             | VideoTypeEnum::Image(_)
             | VideoTypeEnum::Raw(_)
-            | VideoTypeEnum::Ffmpeg(_) => Vec::new(),
+            | VideoTypeEnum::Ffmpeg(_)
+            | VideoTypeEnum::Shape(_)
+            | VideoTypeEnum::Solid(_)
+            | VideoTypeEnum::Gradient(_)
+            | VideoTypeEnum::Svg(_)
+            | VideoTypeEnum::AnimatedImage(_)
+            | VideoTypeEnum::Project(_)
+            | VideoTypeEnum::Instance(_) => Vec::new(),
         }
     }
 
@@ -89,6 +122,9 @@ impl Content for Video {
         self.as_content_changes.pos.is_some()
             | self.as_content_changes.start.is_some()
             | self.as_content_changes.length.is_some()
+            | self.as_content_changes.opacity.is_some()
+            | self.as_content_changes.compositing.is_some()
+            | self.as_content_changes.label.is_some()
             | self.as_content_changes.video.is_some()
             | self.as_content_changes.wrap.is_some()
             | self.as_content_changes.replace.is_some()
@@ -119,6 +155,18 @@ impl Content for Video {
             self.set_length = length;
             out = true;
         };
+        if let Some(opacity) = self.as_content_changes.opacity.take() {
+            self.opacity = opacity;
+            out = true;
+        };
+        if let Some(compositing) = self.as_content_changes.compositing.take() {
+            self.compositing = compositing;
+            out = true;
+        };
+        if let Some(label) = self.as_content_changes.label.take() {
+            self.label = label;
+            out = true;
+        };
         if let Some(video) = self.as_content_changes.video.take() {
             self.video.apply_changes();
             self.video.changes = Some(video);
@@ -186,7 +234,8 @@ impl Content for Video {
                     | (VideoTypeEnum::Text(..), VideoChangesReplaceWith::Text)
                     | (VideoTypeEnum::Image(..), VideoChangesReplaceWith::Image)
                     | (VideoTypeEnum::Raw(..), VideoChangesReplaceWith::Raw)
-                    | (VideoTypeEnum::Ffmpeg(..), VideoChangesReplaceWith::Ffmpeg) => {
+                    | (VideoTypeEnum::Ffmpeg(..), VideoChangesReplaceWith::Ffmpeg)
+                    | (VideoTypeEnum::Shape(..), VideoChangesReplaceWith::Shape) => {
                         break 'replace_with
                     }
                     _ => (),
@@ -200,7 +249,8 @@ impl Content for Video {
                     | (VideoTypeEnum::Text(..), VideoChangesReplaceWith::Text)
                     | (VideoTypeEnum::Image(..), VideoChangesReplaceWith::Image)
                     | (VideoTypeEnum::Raw(..), VideoChangesReplaceWith::Raw)
-                    | (VideoTypeEnum::Ffmpeg(..), VideoChangesReplaceWith::Ffmpeg) => {
+                    | (VideoTypeEnum::Ffmpeg(..), VideoChangesReplaceWith::Ffmpeg)
+                    | (VideoTypeEnum::Shape(..), VideoChangesReplaceWith::Shape) => {
                         unreachable!()
                     } // because of the break 'replace_with above
                     // raw (no change because things will probably break/crash if we try to do pretty much anyting)
@@ -286,6 +336,18 @@ impl Content for Video {
                             self.generic_content_data.reset(),
                         ))
                     }
+                    (VideoTypeEnum::AnimatedImage(v), VideoChangesReplaceWith::Image) => {
+                        VideoTypeEnum::Image(crate::content::image::Image::new(
+                            v.path().clone(),
+                            self.generic_content_data.reset(),
+                        ))
+                    }
+                    (VideoTypeEnum::Svg(v), VideoChangesReplaceWith::Image) => {
+                        VideoTypeEnum::Image(crate::content::image::Image::new(
+                            v.path().clone(),
+                            self.generic_content_data.reset(),
+                        ))
+                    }
                     (VideoTypeEnum::Ffmpeg(v), VideoChangesReplaceWith::Image) => {
                         VideoTypeEnum::Image(crate::content::image::Image::new(
                             v.path().clone(),
@@ -293,6 +355,30 @@ impl Content for Video {
                         ))
                     }
                     // to text (where a string representation makes sense) and back
+                    (VideoTypeEnum::Project(v), VideoChangesReplaceWith::Text) => {
+                        VideoTypeEnum::Text(crate::content::text::Text::new(
+                            crate::content::text::TextType::Static(
+                                v.path().to_string_lossy().to_string(),
+                            ),
+                            self.generic_content_data.clone(),
+                        ))
+                    }
+                    (VideoTypeEnum::AnimatedImage(v), VideoChangesReplaceWith::Text) => {
+                        VideoTypeEnum::Text(crate::content::text::Text::new(
+                            crate::content::text::TextType::Static(
+                                v.path().to_string_lossy().to_string(),
+                            ),
+                            self.generic_content_data.clone(),
+                        ))
+                    }
+                    (VideoTypeEnum::Svg(v), VideoChangesReplaceWith::Text) => {
+                        VideoTypeEnum::Text(crate::content::text::Text::new(
+                            crate::content::text::TextType::Static(
+                                v.path().to_string_lossy().to_string(),
+                            ),
+                            self.generic_content_data.clone(),
+                        ))
+                    }
                     (VideoTypeEnum::Image(v), VideoChangesReplaceWith::Text) => {
                         VideoTypeEnum::Text(crate::content::text::Text::new(
                             crate::content::text::TextType::Static(
@@ -359,6 +445,14 @@ impl Content for Video {
                                     self.generic_content_data.reset(),
                                 ))
                             }
+                            VideoChangesReplaceWith::Shape => {
+                                VideoTypeEnum::Shape(crate::content::shape::Shape::new(
+                                    crate::content::shape::ShapeKind::Rectangle {
+                                        corner_radius: CurveData::Constant(0.0).into(),
+                                    },
+                                    self.generic_content_data.reset(),
+                                ))
+                            }
                         }
                     }
                     // don't use any information of the old one
@@ -400,6 +494,14 @@ impl Content for Video {
                             self.generic_content_data.reset(),
                         ))
                     }
+                    (_, VideoChangesReplaceWith::Shape) => {
+                        VideoTypeEnum::Shape(crate::content::shape::Shape::new(
+                            crate::content::shape::ShapeKind::Rectangle {
+                                corner_radius: CurveData::Constant(0.0).into(),
+                            },
+                            self.generic_content_data.reset(),
+                        ))
+                    }
                 };
                 self.video.vt = new;
                 out = true;
@@ -425,6 +527,10 @@ impl Video {
             set_start_frame: 0.0,
             set_length: 1.0,
             compositing: None,
+            opacity: CurveData::Constant(1.0).into(),
+            linear_light: None,
+            transform: crate::transform::Transform::default(),
+            label: None,
             generic_content_data: video.generic_content_data.reset(),
             video,
             as_content_changes: VideoChanges::default(),
@@ -442,6 +548,10 @@ impl Video {
             set_start_frame: start_frame,
             set_length: length,
             compositing: None,
+            opacity: CurveData::Constant(1.0).into(),
+            linear_light: None,
+            transform: crate::transform::Transform::default(),
+            label: None,
             generic_content_data: video.generic_content_data.clone(),
             video,
             as_content_changes: VideoChanges::default(),
@@ -453,6 +563,10 @@ impl Video {
             set_start_frame: start_frame,
             set_length: length,
             compositing: None,
+            opacity: CurveData::Constant(1.0).into(),
+            linear_light: None,
+            transform: crate::transform::Transform::default(),
+            label: None,
             generic_content_data: video.generic_content_data.clone(),
             video,
             as_content_changes: VideoChanges::default(),
@@ -500,6 +614,15 @@ impl Video {
                     CompositingMethod::Ignore
                 }
             },
+            opacity: self.opacity.get_value(progress).max(0.0).min(1.0)
+                * parent_data.map(|pd| pd.opacity).unwrap_or(1.0),
+            linear_light: self
+                .linear_light
+                .unwrap_or_else(|| parent_data.map(|pd| pd.linear_light).unwrap_or(false)),
+            transform: parent_data
+                .map(|pd| pd.transform)
+                .unwrap_or(crate::transform::Affine::IDENTITY),
+            sub_pixel: None,
             _private: (),
         };
         if pos_px.is_some() {
@@ -514,6 +637,14 @@ pub struct PrepDrawData {
     pub pos_px: (f64, f64, f64, f64),
     pub pos_px_from_canvas: bool,
     pub compositing: CompositingMethod,
+    /// The video's opacity, already multiplied with its parents' opacity.
+    pub opacity: f64,
+    /// If colors are mixed in linear light (see Video::linear_light).
+    pub linear_light: bool,
+    /// Maps points from where the video would be without any transforms (pos_px) to where they are drawn. Includes the parents' transforms and, once draw has been called, the video's own.
+    pub transform: crate::transform::Affine,
+    /// How images are resampled to place them at fractional positions. None rounds positions to whole pixels. Copied from the render settings in Video::draw.
+    pub sub_pixel: Option<crate::transform::ResampleFilter>,
     /// This prevents construction of this struct
     _private: (),
 }
@@ -539,11 +670,24 @@ impl Video {
         mut prep_data: PrepDrawData,
         render_settings: &mut VideoRenderSettings,
     ) {
+        if prep_data.opacity <= 0.0 {
+            return;
+        }
         if prep_data.pos_px_from_canvas {
             prep_data.pos_px = (0.0, 0.0, img.width() as _, img.height() as _);
             prep_data.calc_pos_px();
         }
+        prep_data.sub_pixel = render_settings.sub_pixel;
+        prep_data.transform = prep_data
+            .transform
+            .mul(&self.transform.matrix(prep_data.progress, prep_data.pos_px));
+        // the outermost draw of a project lends the labeled videos to its instances
+        let shared_videos = self.generic_content_data.project.shared_videos.clone();
+        let lent = shared_videos.lend(self);
         self.draw2(img, prep_data, render_settings);
+        if lent {
+            shared_videos.give_back(self);
+        }
     }
 
     fn draw2(
@@ -576,6 +720,8 @@ pub enum CompositingMethod {
     Direct,
     /// Based on the alpha value of each pixel, merges what was there before with what is there now.
     TransparencySupport,
+    /// Like TransparencySupport, but the new color is first combined with what was there before using a blend mode (multiply, screen, ...).
+    Blend(crate::blend_mode::BlendMode),
     Manual(crate::external_program::ExternalProgram),
 }
 
@@ -598,6 +744,70 @@ pub enum CompositingMethod {
 // }
 
 impl Video {
+    /// All of the video's curves with their names: x, y, w, h, opacity, the transform's curves, and the curves of its effect (for WithEffect) or aspect ratio (for AspectRatio).
+    pub fn named_curves(&self) -> Vec<(&'static str, &Curve)> {
+        let mut curves = vec![
+            ("x", &self.set_pos.x),
+            ("y", &self.set_pos.y),
+            ("w", &self.set_pos.w),
+            ("h", &self.set_pos.h),
+            ("opacity", &self.opacity),
+            ("rotation", &self.transform.rotation),
+            ("scale_x", &self.transform.scale_x),
+            ("scale_y", &self.transform.scale_y),
+            ("anchor_x", &self.transform.anchor_x),
+            ("anchor_y", &self.transform.anchor_y),
+            ("skew_x", &self.transform.skew_x),
+            ("skew_y", &self.transform.skew_y),
+        ];
+        match &self.video.vt {
+            VideoTypeEnum::WithEffect(_, effect) => curves.extend(effect.effect.curves()),
+            VideoTypeEnum::AspectRatio(_, w, h) => {
+                curves.push(("aspect_w", w));
+                curves.push(("aspect_h", h));
+            }
+            _ => (),
+        }
+        curves
+    }
+
+    /// Like named_curves, but mutable.
+    pub fn named_curves_mut(&mut self) -> Vec<(&'static str, &mut Curve)> {
+        let mut curves = vec![
+            ("x", &mut self.set_pos.x),
+            ("y", &mut self.set_pos.y),
+            ("w", &mut self.set_pos.w),
+            ("h", &mut self.set_pos.h),
+            ("opacity", &mut self.opacity),
+            ("rotation", &mut self.transform.rotation),
+            ("scale_x", &mut self.transform.scale_x),
+            ("scale_y", &mut self.transform.scale_y),
+            ("anchor_x", &mut self.transform.anchor_x),
+            ("anchor_y", &mut self.transform.anchor_y),
+            ("skew_x", &mut self.transform.skew_x),
+            ("skew_y", &mut self.transform.skew_y),
+        ];
+        match &mut self.video.vt {
+            VideoTypeEnum::WithEffect(_, effect) => curves.extend(effect.effect.curves_mut()),
+            VideoTypeEnum::AspectRatio(_, w, h) => {
+                curves.push(("aspect_w", w));
+                curves.push(("aspect_h", h));
+            }
+            _ => (),
+        }
+        curves
+    }
+
+    /// Updates this video's and its children's curves that use shared curves (see Curve::update), after a shared curve was changed.
+    pub fn update_shared_curves(&mut self, shared_curves: &crate::project::SharedCurves) {
+        for (_, curve) in self.named_curves_mut() {
+            curve.update(shared_curves);
+        }
+        for child in self.children_mut() {
+            child.update_shared_curves(shared_curves);
+        }
+    }
+
     /// Assumes align is set to TopLeft!
     fn get_inner_pos(pos_outer: &Pos<i32, u32>, pos_inner: &Pos<f64, f64>) -> Pos<i32, u32> {
         Pos {
@@ -642,6 +852,16 @@ pub enum VideoTypeEnum {
     Image(crate::content::image::Image),
     Raw(crate::content::input_video::InputVideo),
     Ffmpeg(crate::content::ffmpeg_vid::FfmpegVid),
+    Shape(crate::content::shape::Shape),
+    /// Fills the video's box with one color.
+    Solid(crate::types::Color),
+    Gradient(crate::content::gradient::Gradient),
+    Svg(crate::content::svg::Svg),
+    AnimatedImage(crate::content::animated_image::AnimatedImage),
+    /// Another project file, drawn inside this video's box.
+    Project(crate::content::nested_project::NestedProject),
+    /// One of the project's shared videos, drawn inside this video's box.
+    Instance(crate::content::instance::Instance),
 }
 
 impl Drawable for VideoTypeEnum {
@@ -669,9 +889,38 @@ impl Drawable for VideoTypeEnum {
             }
 
             Self::Image(img) => {
+                img.set_wait_for_external_command(render_settings.wait_for_external_commands);
                 img.draw(image, &prep_data, render_settings.image_scaling_filter_type)
             }
 
+            Self::Shape(shape) => shape.draw(image, &prep_data),
+
+            Self::Solid(color) => {
+                let (width, height) = (prep_data.pos_px.2 as u32, prep_data.pos_px.3 as u32);
+                if width > 0 && height > 0 {
+                    let (r, g, b, a) = color.get_rgba(prep_data.progress);
+                    let channel = |v: f64| (v.max(0.0).min(1.0) * 255.0).round() as u8;
+                    let img = image::RgbaImage::from_pixel(
+                        width,
+                        height,
+                        image::Rgba([channel(r), channel(g), channel(b), channel(a)]),
+                    );
+                    composite_images(image, &DynamicImage::ImageRgba8(img), &prep_data);
+                }
+            }
+
+            Self::Gradient(gradient) => gradient.draw(image, &prep_data),
+
+            Self::Svg(svg) => svg.draw(image, &prep_data),
+
+            Self::AnimatedImage(img) => {
+                img.draw(image, &prep_data, render_settings.image_scaling_filter_type)
+            }
+
+            Self::Project(proj) => proj.draw(image, &prep_data, render_settings),
+
+            Self::Instance(instance) => instance.draw(image, &prep_data, render_settings),
+
             Self::Text(txt) => txt.draw(
                 image,
                 &prep_data,
@@ -731,13 +980,59 @@ impl Drawable for VideoTypeEnum {
 }
 
 /// Draws the image onto the other one, following the compositing method and position provided by prep_draw. ASSUMES THE IMAGE HAS THE CORRECT SIZE!
+/// Without sub-pixel positioning, the position is rounded to whole pixels and only transformed images are resampled (bilinear).
 pub fn composite_images(image: &mut DynamicImage, img: &DynamicImage, prep_draw: &PrepDrawData) {
-    let pos = (
-        prep_draw.pos_px.0.ceil() as i32,
-        prep_draw.pos_px.1.ceil() as i32,
-        prep_draw.pos_px.2 as u32,
-        prep_draw.pos_px.3 as u32,
-    );
+    let origin = if prep_draw.sub_pixel.is_some() {
+        (prep_draw.pos_px.0, prep_draw.pos_px.1)
+    } else {
+        (prep_draw.pos_px.0.ceil(), prep_draw.pos_px.1.ceil())
+    };
+    // content that was rendered at the box's size (rounded down) is stretched to the exact size, so all of its edges are anti-aliased
+    let (w, h) = (prep_draw.pos_px.2, prep_draw.pos_px.3);
+    let scale = if prep_draw.sub_pixel.is_some()
+        && img.width() > 0
+        && img.height() > 0
+        && (img.width(), img.height()) == (w as u32, h as u32)
+    {
+        (w / img.width() as f64, h / img.height() as f64)
+    } else {
+        (1.0, 1.0)
+    };
+    let matrix = prep_draw
+        .transform
+        .mul(&crate::transform::Affine::translate(origin.0, origin.1))
+        .mul(&crate::transform::Affine::scale(scale.0, scale.1));
+    if let Some(pos) = matrix.whole_pixel_translation() {
+        composite_images_at(image, img, pos, false, prep_draw);
+    } else if let Some((img, x, y)) = crate::transform::resample(
+        &img.to_rgba8(),
+        &matrix,
+        image.width(),
+        image.height(),
+        prep_draw
+            .sub_pixel
+            .unwrap_or(crate::transform::ResampleFilter::Bilinear),
+    ) {
+        composite_images_at(
+            image,
+            &DynamicImage::ImageRgba8(img),
+            (x, y),
+            true,
+            prep_draw,
+        );
+    }
+}
+
+/// Like composite_images, but img's top left corner is at pos and the transform is ignored.
+/// If resampled is true, img's alpha includes how much of each pixel is covered (see transform::resample): Opaque and Direct then skip fully transparent pixels, and Opaque mixes partially covered ones with what was there before.
+fn composite_images_at(
+    image: &mut DynamicImage,
+    img: &DynamicImage,
+    pos: (i32, i32),
+    resampled: bool,
+    prep_draw: &PrepDrawData,
+) {
+    let opacity = prep_draw.opacity;
     match &prep_draw.compositing {
         CompositingMethod::Ignore => (),
         CompositingMethod::Opaque => {
@@ -749,14 +1044,32 @@ pub fn composite_images(image: &mut DynamicImage, img: &DynamicImage, prep_draw:
                     && !y.is_negative()
                     && (x as u32) < image.width()
                     && (y as u32) < image.height()
+                    && !(resampled && pixel.0[3] == 0)
                 {
-                    image.put_pixel(
-                        x as _,
-                        y as _,
-                        image::Rgba {
-                            0: [pixel.0[0], pixel.0[1], pixel.0[2], 255],
-                        },
-                    );
+                    let coverage = if resampled { pixel.0[3] } else { 255 };
+                    if opacity < 1.0 || coverage < 255 {
+                        // mixed with what was there before
+                        let mut px = image.get_pixel(x as _, y as _);
+                        crate::alpha_compositing::over(
+                            &mut px.0,
+                            &[
+                                pixel.0[0],
+                                pixel.0[1],
+                                pixel.0[2],
+                                apply_opacity(coverage, opacity),
+                            ],
+                            prep_draw.linear_light,
+                        );
+                        image.put_pixel(x as _, y as _, px);
+                    } else {
+                        image.put_pixel(
+                            x as _,
+                            y as _,
+                            image::Rgba {
+                                0: [pixel.0[0], pixel.0[1], pixel.0[2], 255],
+                            },
+                        );
+                    }
                 }
             }
         }
@@ -769,12 +1082,15 @@ pub fn composite_images(image: &mut DynamicImage, img: &DynamicImage, prep_draw:
                     && !y.is_negative()
                     && (x as u32) < image.width()
                     && (y as u32) < image.height()
+                    && !(resampled && pixel.0[3] == 0)
                 {
-                    image.put_pixel(x as _, y as _, pixel.clone());
+                    let mut pixel = pixel.clone();
+                    pixel.0[3] = apply_opacity(pixel.0[3], opacity);
+                    image.put_pixel(x as _, y as _, pixel);
                 }
             }
         }
-        CompositingMethod::TransparencySupport => {
+        CompositingMethod::TransparencySupport | CompositingMethod::Blend(_) => {
             for pixel in img.to_rgba8().enumerate_pixels() {
                 let (x, y, pixel) = (pixel.0, pixel.1, pixel.2);
                 let x = pos.0 + x as i32;
@@ -785,7 +1101,14 @@ pub fn composite_images(image: &mut DynamicImage, img: &DynamicImage, prep_draw:
                     && (y as u32) < image.height()
                 {
                     let mut px = image.get_pixel(x as _, y as _).clone();
-                    composite_pixels_transparency_support(&mut px.0, &pixel.0);
+                    let mut pixel = pixel.clone();
+                    pixel.0[3] = apply_opacity(pixel.0[3], opacity);
+                    composite_pixels(
+                        &mut px.0,
+                        &pixel.0,
+                        &prep_draw.compositing,
+                        prep_draw.linear_light,
+                    );
                     image.put_pixel(x as _, y as _, px);
                 }
             }
@@ -794,14 +1117,28 @@ pub fn composite_images(image: &mut DynamicImage, img: &DynamicImage, prep_draw:
     }
 }
 
-/// Draws onto old. This is to be used for CompositingMethod::TransparencySupport.
-pub fn composite_pixels_transparency_support(old: &mut [u8; 4], new: &[u8; 4]) {
-    let factor_new = new[3] as u16;
-    let factor_old = 255 - factor_new;
-    for i in 0..3 {
-        old[i] = ((old[i] as u16 * factor_old + new[i] as u16 * factor_new) / 255) as u8;
+/// Multiplies an alpha value with an opacity (0.0 to 1.0).
+pub fn apply_opacity(alpha: u8, opacity: f64) -> u8 {
+    if opacity >= 1.0 {
+        alpha
+    } else {
+        (alpha as f64 * opacity.max(0.0)).round() as u8
+    }
+}
+
+/// Draws onto old, for the compositing methods that work on single pixels (TransparencySupport and Blend). Other methods are treated like TransparencySupport. If linear_light is true, colors are mixed in linear light instead of in sRGB.
+pub fn composite_pixels(
+    old: &mut [u8; 4],
+    new: &[u8; 4],
+    compositing: &CompositingMethod,
+    linear_light: bool,
+) {
+    match compositing {
+        CompositingMethod::Blend(mode) => {
+            crate::alpha_compositing::blend(old, new, *mode, linear_light)
+        }
+        _ => crate::alpha_compositing::over(old, new, linear_light),
     }
-    old[3] = 255;
 }
 
 pub enum VideoTypeChanges {
@@ -812,6 +1149,13 @@ pub enum VideoTypeChanges {
     Image(ImageChanges),
     Raw(InputVideoChanges),
     Ffmpeg(super::content::ffmpeg_vid::FfmpegVidChanges),
+    Shape(crate::content::shape::ShapeChanges),
+    Solid(crate::types::Color),
+    Gradient(crate::content::gradient::GradientChanges),
+    Svg(crate::content::svg::SvgChanges),
+    AnimatedImage(crate::content::animated_image::AnimatedImageChanges),
+    Project(crate::content::nested_project::NestedProjectChanges),
+    Instance(crate::content::instance::InstanceChanges),
     ChangeType(VideoTypeEnum),
 }
 
@@ -849,6 +1193,15 @@ impl Content for VideoType {
                 VideoTypeEnum::Image(img) => VideoTypeEnum::Image(img.clone_no_caching()),
                 VideoTypeEnum::Raw(v) => VideoTypeEnum::Raw(v.clone_no_caching()),
                 VideoTypeEnum::Ffmpeg(v) => VideoTypeEnum::Ffmpeg(v.clone_no_caching()),
+                VideoTypeEnum::Shape(v) => VideoTypeEnum::Shape(v.clone_no_caching()),
+                VideoTypeEnum::Solid(c) => VideoTypeEnum::Solid(c.clone()),
+                VideoTypeEnum::Gradient(v) => VideoTypeEnum::Gradient(v.clone_no_caching()),
+                VideoTypeEnum::Svg(v) => VideoTypeEnum::Svg(v.clone_no_caching()),
+                VideoTypeEnum::AnimatedImage(v) => {
+                    VideoTypeEnum::AnimatedImage(v.clone_no_caching())
+                }
+                VideoTypeEnum::Project(v) => VideoTypeEnum::Project(v.clone_no_caching()),
+                VideoTypeEnum::Instance(v) => VideoTypeEnum::Instance(v.clone_no_caching()),
             },
             self.generic_content_data.reset(),
         )
@@ -942,6 +1295,34 @@ impl Content for VideoType {
                     vid.as_content_changes = changes;
                     vid.apply_changes()
                 }
+                (VideoTypeChanges::Shape(changes), VideoTypeEnum::Shape(shape)) => {
+                    shape.as_content_changes = changes;
+                    shape.apply_changes()
+                }
+                (VideoTypeChanges::Solid(new), VideoTypeEnum::Solid(color)) => {
+                    *color = new;
+                    true
+                }
+                (VideoTypeChanges::Gradient(changes), VideoTypeEnum::Gradient(gradient)) => {
+                    gradient.as_content_changes = changes;
+                    gradient.apply_changes()
+                }
+                (VideoTypeChanges::Svg(changes), VideoTypeEnum::Svg(svg)) => {
+                    svg.as_content_changes = changes;
+                    svg.apply_changes()
+                }
+                (VideoTypeChanges::AnimatedImage(changes), VideoTypeEnum::AnimatedImage(img)) => {
+                    img.as_content_changes = changes;
+                    img.apply_changes()
+                }
+                (VideoTypeChanges::Project(changes), VideoTypeEnum::Project(proj)) => {
+                    proj.as_content_changes = changes;
+                    proj.apply_changes()
+                }
+                (VideoTypeChanges::Instance(changes), VideoTypeEnum::Instance(instance)) => {
+                    instance.as_content_changes = changes;
+                    instance.apply_changes()
+                }
                 (VideoTypeChanges::ChangeType(new), _) => {
                     self.vt = new;
                     true
@@ -961,6 +1342,13 @@ impl Content for VideoType {
                         VideoTypeChanges::Image(_) => "Image",
                         VideoTypeChanges::Raw(_) => "Video",
                         VideoTypeChanges::Ffmpeg(_) => "ffmpeg",
+                        VideoTypeChanges::Shape(_) => "Shape",
+                        VideoTypeChanges::Solid(_) => "Solid",
+                        VideoTypeChanges::Gradient(_) => "Gradient",
+                        VideoTypeChanges::Svg(_) => "Svg",
+                        VideoTypeChanges::AnimatedImage(_) => "AnimatedImage",
+                        VideoTypeChanges::Project(_) => "Project",
+                        VideoTypeChanges::Instance(_) => "Instance",
                     }),
                     Clz::error_details(" to data of type "),
                     Clz::error_cause(match data {
@@ -971,6 +1359,13 @@ impl Content for VideoType {
                         VideoTypeEnum::Image(_) => "Image",
                         VideoTypeEnum::Raw(_) => "Video",
                         VideoTypeEnum::Ffmpeg(_) => "ffmpeg",
+                        VideoTypeEnum::Shape(_) => "Shape",
+                        VideoTypeEnum::Solid(_) => "Solid",
+                        VideoTypeEnum::Gradient(_) => "Gradient",
+                        VideoTypeEnum::Svg(_) => "Svg",
+                        VideoTypeEnum::AnimatedImage(_) => "AnimatedImage",
+                        VideoTypeEnum::Project(_) => "Project",
+                        VideoTypeEnum::Instance(_) => "Instance",
                     }),
                     Clz::error_details(".")
                 ),
@@ -1029,7 +1424,7 @@ impl<T> PosAlign<T> {
         }
     }
     /// a is 0, b is 1/2, c is 1
-    fn get_anchor(&self, a: T, b: T, c: T) -> (T, T)
+    pub fn get_anchor(&self, a: T, b: T, c: T) -> (T, T)
     where
         T: Clone,
     {
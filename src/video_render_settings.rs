@@ -1,10 +1,16 @@
 use image::imageops::FilterType;
 
+use crate::transform::ResampleFilter;
+
 pub struct VideoRenderSettings {
     /// The distance (in frames) that the displayed frame can have from the desired frame. This might become useless once actual good video loading is implemented.
     pub max_distance_when_retrieving_closest_frame: i8,
     /// How to up- or downscale images. Very likely to have a big performance impact.
     pub image_scaling_filter_type: FilterType,
+    /// If false, images generated by external commands show the previous image until the command finishes (see ExternalImageCommand).
+    pub wait_for_external_commands: bool,
+    /// If Some, videos are placed at their exact (fractional) position by resampling them with this filter, and their edges are anti-aliased. If None, positions are rounded to whole pixels, which is faster but makes slow movements jittery.
+    pub sub_pixel: Option<ResampleFilter>,
     pub this_frame: FrameRenderInfo,
 }
 impl VideoRenderSettings {
@@ -12,12 +18,16 @@ impl VideoRenderSettings {
     pub fn preview(this_frame: FrameRenderInfo) -> Self { Self {
         max_distance_when_retrieving_closest_frame: 0,
         image_scaling_filter_type: FilterType::Gaussian,
+        wait_for_external_commands: false,
+        sub_pixel: None,
         this_frame,
     } }
     /// This is used for final render. It prevents inaccuracies.
     pub fn export(this_frame: FrameRenderInfo) -> Self { Self {
         max_distance_when_retrieving_closest_frame: 0,
         image_scaling_filter_type: FilterType::Gaussian,
+        wait_for_external_commands: true,
+        sub_pixel: Some(ResampleFilter::Bicubic),
         this_frame,
     } }
 }